@@ -22,12 +22,10 @@ fn main() {
     for arg in args {
         // println!("{arg}");
         if let Ok(paths) = glob(&arg) {
-            for entry in paths {
-                if let Ok(path) = entry {
-                    if path.is_file() {
-                        let t = match_all(path.as_path());
-                        println!("{}: {}", path.display(), t);
-                    }
+            for path in paths.flatten() {
+                if path.is_file() {
+                    let t = match_all(path.as_path());
+                    println!("{}: {}", path.display(), t);
                 }
             }
         }
@@ -0,0 +1,231 @@
+use crate::{
+    constants::NUM_SIGNATURE_BYTES,
+    types::{
+        archive::{self, ElfInfo, MachOInfo, PdfInfo, PeInfo},
+        audio::{self, AudioInfo},
+        font::{self, FontInfo},
+        image::{self, ImageInfo, JpegScan, Metadata},
+        text::{self, ConfigInfo, CsvDialect, Shebang, TextInfo},
+        video::{self, TsInfo, VideoInfo},
+    },
+    utils::{get_bytes_at, get_signature_bytes},
+};
+use std::{fs, path::Path, time::Duration};
+
+// Signature-sized reads made while looking for a JPEG frame header.
+const MAX_JPEG_READS: usize = 64;
+// Top-level boxes skipped while looking for `moov`, and the largest `moov` read.
+const MAX_TOP_LEVEL_BOXES: usize = 64;
+const MAX_MOOV_BYTES: u64 = 16 << 20;
+// The prefix read for the headers of video containers other than ISO BMFF.
+const VIDEO_HEADER_BYTES: usize = 256 << 10;
+// The prefix read for image metadata, which precedes the image data except in HEIF, and
+// the largest HEIF Exif item read from elsewhere.
+const METADATA_BYTES: usize = 1 << 20;
+// The largest font read whole, as its tables may be anywhere; CJK collections run to
+// tens of megabytes.
+const MAX_FONT_BYTES: u64 = 64 << 20;
+
+fn info_wrapper<T>(path: &Path, f: fn(&[u8]) -> Option<T>) -> Option<T> {
+    let mut sig = [0_u8; NUM_SIGNATURE_BYTES];
+    let n = get_signature_bytes(path, &mut sig);
+    f(&sig[..n])
+}
+
+pub fn pe_info(path: &Path) -> Option<PeInfo> {
+    info_wrapper(path, archive::pe_info)
+}
+
+pub fn elf_info(path: &Path) -> Option<ElfInfo> {
+    info_wrapper(path, archive::elf_info)
+}
+
+// Images of a universal binary are page aligned and usually start past the signature
+// bytes, so their headers are read from the file.
+pub fn macho_info(path: &Path) -> Option<MachOInfo> {
+    let mut info = info_wrapper(path, archive::macho_info)?;
+    for arch in info.archs.iter_mut().filter(|a| a.filetype.is_none()) {
+        let mut header = [0_u8; 32];
+        let n = get_bytes_at(path, arch.offset, &mut header);
+        arch.filetype = archive::macho_info(&header[..n])
+            .filter(|thin| !thin.fat)
+            .and_then(|thin| thin.filetype());
+    }
+    Some(info)
+}
+
+// JPEG files may hold tens of kilobytes of EXIF, XMP or ICC data ahead of the frame
+// header, so the marker segments are followed through the file.
+pub fn image_info(path: &Path) -> Option<ImageInfo> {
+    let mut buf = [0_u8; NUM_SIGNATURE_BYTES];
+    let n = get_signature_bytes(path, &mut buf);
+    if !buf[..n].starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return image::image_info(&buf[..n]);
+    }
+
+    let mut offset = 0;
+    let mut scan = image::jpeg_scan(&buf[..n], 2);
+    for _ in 0..MAX_JPEG_READS {
+        match scan {
+            JpegScan::Frame(info) => return Some(info),
+            // Truncated at the end of the file.
+            JpegScan::Invalid | JpegScan::Next(0) => return None,
+            JpegScan::Next(next) => {
+                offset += next as u64;
+                let n = get_bytes_at(path, offset, &mut buf);
+                scan = image::jpeg_scan(&buf[..n], 0);
+            }
+        }
+    }
+    None
+}
+
+pub fn metadata(path: &Path) -> Option<Metadata> {
+    let mut head = vec![0_u8; METADATA_BYTES];
+    let n = get_signature_bytes(path, &mut head);
+    head.truncate(n);
+    let mut meta = image::metadata(&head)?;
+
+    // Fields of an Exif item inside the prefix are already set.
+    match image::exif_item_extent(&head) {
+        Some((offset, len)) if offset.saturating_add(len) > n as u64 => {
+            let mut item = vec![0_u8; (len as usize).min(METADATA_BYTES)];
+            let n = get_bytes_at(path, offset, &mut item);
+            image::apply_exif_item(&mut meta, &item[..n]);
+        }
+        _ => {}
+    }
+    Some(meta)
+}
+
+// The cross-reference data is at the end and the objects it points to are anywhere, so
+// PDF files are read a window at a time.
+pub fn pdf_info(path: &Path) -> Option<PdfInfo> {
+    let len = fs::metadata(path).ok()?.len();
+    archive::pdf_info_from(len, &|offset, buf| get_bytes_at(path, offset, buf))
+}
+
+pub fn font_info(path: &Path) -> Option<FontInfo> {
+    if fs::metadata(path).ok()?.len() > MAX_FONT_BYTES {
+        return None;
+    }
+    font::font_info(&fs::read(path).ok()?)
+}
+
+// An ISO BMFF or QuickTime file cut down to its `ftyp` and `moov` boxes, for files that
+// store the movie header after the media data. The top-level box headers are read one
+// by one.
+fn read_moov(path: &Path) -> Option<Vec<u8>> {
+    let mut ret = vec![];
+    let mut offset = 0;
+    for _ in 0..MAX_TOP_LEVEL_BOXES {
+        let mut header = [0_u8; 16];
+        if get_bytes_at(path, offset, &mut header) < 16 {
+            return None;
+        }
+        let size = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            1 => u64::from_be_bytes(header[8..16].try_into().unwrap()),
+            // Only the last box may run to the end of the file.
+            0 => return None,
+            size => size as u64,
+        };
+        let kind = &header[4..8];
+        if (kind == b"ftyp" || kind == b"moov") && size <= MAX_MOOV_BYTES {
+            let start = ret.len();
+            ret.resize(start + size as usize, 0);
+            let n = get_bytes_at(path, offset, &mut ret[start..]);
+            ret.truncate(start + n);
+        }
+        if kind == b"moov" {
+            return Some(ret);
+        }
+        offset = offset.checked_add(size.max(8))?;
+    }
+    None
+}
+
+// Ogg streams are timed by their last page, MP4 files may keep `moov` at the end, and
+// streams whose headers give only a bitrate or only a duration are completed with the
+// file size.
+pub fn audio_info(path: &Path) -> Option<AudioInfo> {
+    let mut buf = [0_u8; NUM_SIGNATURE_BYTES];
+    let n = get_signature_bytes(path, &mut buf);
+    let head = &buf[..n];
+    let mut info = match audio::audio_info(head) {
+        None if head.get(4..8) == Some(b"ftyp") => audio::audio_info(&read_moov(path)?)?,
+        info => info?,
+    };
+    let len = fs::metadata(path).ok()?.len();
+
+    if head.starts_with(b"OggS") {
+        let mut tail = [0_u8; NUM_SIGNATURE_BYTES];
+        let start = len.saturating_sub(NUM_SIGNATURE_BYTES as u64);
+        let n = get_bytes_at(path, start, &mut tail);
+        info.duration = audio::ogg_duration(head, &tail[..n]);
+    }
+    match (info.duration, info.bitrate) {
+        (None, Some(bitrate)) if bitrate > 0 => {
+            info.duration = Some(Duration::from_secs_f64(len as f64 * 8.0 / bitrate as f64));
+        }
+        (Some(duration), None) if !duration.is_zero() => {
+            info.bitrate = Some((len as f64 * 8.0 / duration.as_secs_f64()) as u32);
+        }
+        _ => {}
+    }
+    Some(info)
+}
+
+// Container headers usually fit in the first few hundred kilobytes; MP4 and QuickTime
+// files may keep `moov` anywhere.
+pub fn video_info(path: &Path) -> Option<VideoInfo> {
+    let mut head = vec![0_u8; VIDEO_HEADER_BYTES];
+    let n = get_signature_bytes(path, &mut head);
+    head.truncate(n);
+    match video::video_info(&head) {
+        None if matches!(
+            head.get(4..8),
+            Some(b"ftyp" | b"mdat" | b"wide" | b"free" | b"skip")
+        ) =>
+        {
+            video::video_info(&read_moov(path)?)
+        }
+        info => info,
+    }
+}
+
+// The `codecs` values of an MP4, QuickTime or Matroska file, read like `video_info`.
+pub fn codecs(path: &Path) -> Option<Vec<String>> {
+    let mut head = vec![0_u8; VIDEO_HEADER_BYTES];
+    let n = get_signature_bytes(path, &mut head);
+    head.truncate(n);
+    match video::codecs(&head) {
+        None if matches!(
+            head.get(4..8),
+            Some(b"ftyp" | b"mdat" | b"wide" | b"free" | b"skip")
+        ) =>
+        {
+            video::codecs(&read_moov(path)?)
+        }
+        codecs => codecs,
+    }
+}
+
+pub fn ts_info(path: &Path) -> Option<TsInfo> {
+    info_wrapper(path, video::ts_info)
+}
+
+pub fn text_info(path: &Path) -> Option<TextInfo> {
+    info_wrapper(path, text::text_info)
+}
+
+pub fn config_info(path: &Path) -> Option<ConfigInfo> {
+    info_wrapper(path, text::config_info)
+}
+
+pub fn csv_info(path: &Path) -> Option<CsvDialect> {
+    info_wrapper(path, text::csv_info)
+}
+
+pub fn shebang_info(path: &Path) -> Option<Shebang> {
+    info_wrapper(path, text::shebang_info)
+}
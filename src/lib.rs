@@ -1,4 +1,5 @@
 mod constants;
+pub mod info;
 pub mod matcher;
 pub mod types;
 mod utils;
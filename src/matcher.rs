@@ -1,15 +1,14 @@
 use crate::{
     constants::NUM_SIGNATURE_BYTES,
+    info,
     types::{self, HashMapTypeMatcher, Type, TYPE_UNKNOWN},
     utils::get_signature_bytes,
 };
 use std::path::Path;
 
-fn match_wrapper(path: &Path, tm: HashMapTypeMatcher) -> Type {
-    let mut sig = [0_u8; NUM_SIGNATURE_BYTES];
-    get_signature_bytes(path, &mut sig);
+fn match_bytes(sig: &[u8], tm: HashMapTypeMatcher) -> Type<'static> {
     for (t, m) in tm {
-        if m(&sig) {
+        if m(sig) {
             return t;
         }
     }
@@ -17,36 +16,78 @@ fn match_wrapper(path: &Path, tm: HashMapTypeMatcher) -> Type {
     TYPE_UNKNOWN
 }
 
-pub type Matcher = fn(&Path) -> Type;
+fn match_wrapper(path: &Path, tm: HashMapTypeMatcher) -> Type<'static> {
+    let mut sig = [0_u8; NUM_SIGNATURE_BYTES];
+    let n = get_signature_bytes(path, &mut sig);
+    match_bytes(&sig[..n], tm)
+}
 
-pub fn match_all(path: &Path) -> Type {
-    match_wrapper(path, types::sum())
+pub type Matcher = fn(&Path) -> Type<'static>;
+
+// Magic numbers first; only content none of them claims is classified as text.
+pub fn match_all(path: &Path) -> Type<'static> {
+    let mut sig = [0_u8; NUM_SIGNATURE_BYTES];
+    let n = get_signature_bytes(path, &mut sig);
+    let sig = &sig[..n];
+
+    let t = match_bytes(sig, types::sum());
+    if t == TYPE_UNKNOWN {
+        types::text::identify(sig)
+    } else {
+        t
+    }
 }
 
-pub fn match_application(path: &Path) -> Type {
+pub fn match_application(path: &Path) -> Type<'static> {
     match_wrapper(path, types::application::sum())
 }
 
-pub fn match_archive(path: &Path) -> Type {
+pub fn match_archive(path: &Path) -> Type<'static> {
     match_wrapper(path, types::archive::sum())
 }
 
-pub fn match_audio(path: &Path) -> Type {
+pub fn match_audio(path: &Path) -> Type<'static> {
     match_wrapper(path, types::audio::sum())
 }
 
-pub fn match_document(path: &Path) -> Type {
+pub fn match_document(path: &Path) -> Type<'static> {
     match_wrapper(path, types::document::sum())
 }
 
-pub fn match_font(path: &Path) -> Type {
+pub fn match_font(path: &Path) -> Type<'static> {
     match_wrapper(path, types::font::sum())
 }
 
-pub fn match_image(path: &Path) -> Type {
+pub fn match_image(path: &Path) -> Type<'static> {
     match_wrapper(path, types::image::sum())
 }
 
-pub fn match_video(path: &Path) -> Type {
+pub fn match_video(path: &Path) -> Type<'static> {
     match_wrapper(path, types::video::sum())
 }
+
+// The MIME type with an RFC 6381 `codecs` parameter, as used by HLS and DASH manifests,
+// e.g. `video/mp4; codecs="avc1.64001F, mp4a.40.2"`. Types without codec information
+// are returned as they are.
+fn with_codecs(path: &Path, t: Type<'static>) -> String {
+    match info::codecs(path) {
+        Some(codecs) if t != TYPE_UNKNOWN && !t.mime.contains("codecs=") => {
+            format!("{}; codecs=\"{}\"", t.mime, codecs.join(", "))
+        }
+        _ => t.mime.to_string(),
+    }
+}
+
+pub fn match_video_with_codecs(path: &Path) -> String {
+    with_codecs(path, match_video(path))
+}
+
+pub fn match_audio_with_codecs(path: &Path) -> String {
+    with_codecs(path, match_audio(path))
+}
+
+pub fn match_text(path: &Path) -> Type<'static> {
+    let mut sig = [0_u8; NUM_SIGNATURE_BYTES];
+    let n = get_signature_bytes(path, &mut sig);
+    types::text::identify(&sig[..n])
+}
@@ -1,11 +1,12 @@
 use super::{
     base::{new_type, HashMapTypeMatcher, Type},
-    utils::compare_bytes,
+    utils::{compare_bytes, macho},
 };
 
 const TYPE_WASM: Type = new_type("application/wasm", "wasm");
 const TYPE_DEX: Type = new_type("application/vnd.android.dex", "dex");
 const TYPE_DEY: Type = new_type("application/vnd.android.dey", "dey");
+const TYPE_CLASS: Type = new_type("application/java-vm", "class");
 
 fn is_wasm(buf: &[u8]) -> bool {
     let signatures = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
@@ -25,7 +26,12 @@ fn is_dey(buf: &[u8]) -> bool {
     // dey magic
     buf[..4] == [0x64 , 0x65, 0x79, 0x0A] &&
     // dex
-    is_dex(&buf[40..100].to_vec())
+    is_dex(&buf[40..100])
+}
+
+// Shares its magic with Mach-O universal binaries.
+fn is_class(buf: &[u8]) -> bool {
+    macho::is_java_class(buf)
 }
 
 pub fn sum() -> HashMapTypeMatcher {
@@ -34,6 +40,7 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_WASM, is_wasm);
     ret.insert(TYPE_DEX, is_dex);
     ret.insert(TYPE_DEY, is_dey);
+    ret.insert(TYPE_CLASS, is_class);
 
     ret
 }
@@ -1,6 +1,17 @@
 use super::{
     base::{new_type, HashMapTypeMatcher, Type},
-    utils::{compare_bytes, littleendian_bytes},
+    utils::{
+        compare_bytes, elf, font, littleendian_bytes,
+        macho::{self, MH_BUNDLE, MH_DSYM, MH_DYLIB, MH_OBJECT},
+        markup, pdf, pe,
+    },
+};
+
+pub use super::utils::{
+    elf::{ElfInfo, ElfKind},
+    macho::{MachOArch, MachOInfo},
+    pdf::{PdfInfo, Permissions},
+    pe::{PeInfo, PeKind},
 };
 
 const TYPE_EPUB: Type = new_type("application/epub+zip", "epub");
@@ -13,7 +24,18 @@ const TYPE_7Z: Type = new_type("application/x-7z-compressed", "7z");
 const TYPE_XZ: Type = new_type("application/x-xz", "xz");
 const TYPE_ZST: Type = new_type("application/zstd", "zst");
 const TYPE_PDF: Type = new_type("application/pdf", "pdf");
+const TYPE_AI: Type = new_type("application/illustrator", "ai");
+const TYPE_FDF: Type = new_type("application/vnd.fdf", "fdf");
 const TYPE_EXE: Type = new_type("application/vnd.microsoft.portable-executable", "exe");
+const TYPE_DLL: Type = new_type("application/vnd.microsoft.portable-executable", "dll");
+const TYPE_SYS: Type = new_type("application/vnd.microsoft.portable-executable", "sys");
+const TYPE_EFI: Type = new_type("application/vnd.microsoft.portable-executable", "efi");
+const TYPE_DOTNET_EXE: Type = new_type("application/x-dotnet-assembly", "exe");
+const TYPE_DOTNET_DLL: Type = new_type("application/x-dotnet-assembly", "dll");
+const TYPE_DOS_EXE: Type = new_type("application/x-dosexec", "exe");
+const TYPE_NE_EXE: Type = new_type("application/x-ms-ne-executable", "exe");
+const TYPE_LE_EXE: Type = new_type("application/x-ms-le-executable", "exe");
+const TYPE_LX_EXE: Type = new_type("application/x-os2-lx-executable", "exe");
 const TYPE_SWF: Type = new_type("application/x-shockwave-flash", "swf");
 const TYPE_RTF: Type = new_type("application/rtf", "rtf");
 const TYPE_EOT: Type = new_type("application/octet-stream", "eot");
@@ -28,9 +50,17 @@ const TYPE_Z: Type = new_type("application/x-compress", "Z");
 const TYPE_LZ: Type = new_type("application/x-lzip", "lz");
 const TYPE_RPM: Type = new_type("application/x-rpm", "rpm");
 const TYPE_ELF: Type = new_type("application/x-executable", "elf");
+const TYPE_ELF_PIE: Type = new_type("application/x-pie-executable", "elf");
+const TYPE_ELF_SO: Type = new_type("application/x-sharedlib", "so");
+const TYPE_ELF_OBJ: Type = new_type("application/x-object", "o");
+const TYPE_ELF_CORE: Type = new_type("application/x-coredump", "core");
 const TYPE_DCM: Type = new_type("application/dicom", "dcm");
 const TYPE_ISO: Type = new_type("application/x-iso9660-image", "iso");
 const TYPE_MACHO: Type = new_type("application/x-mach-binary", "macho"); // Mach-O binaries have no common extension.
+const TYPE_MACHO_DYLIB: Type = new_type("application/x-mach-o-dylib", "dylib");
+const TYPE_MACHO_BUNDLE: Type = new_type("application/x-mach-o-bundle", "bundle");
+const TYPE_MACHO_OBJECT: Type = new_type("application/x-mach-o-object", "o");
+const TYPE_MACHO_DSYM: Type = new_type("application/x-mach-o-dsym", "dsym");
 
 fn is_epub(buf: &[u8]) -> bool {
     let subs = [
@@ -41,9 +71,10 @@ fn is_epub(buf: &[u8]) -> bool {
     compare_bytes(buf, &subs, 0)
 }
 
+// Compressed SVG is reported as an image.
 fn is_gz(buf: &[u8]) -> bool {
     let subs = [0x1F, 0x8B, 0x08];
-    compare_bytes(buf, &subs, 0)
+    compare_bytes(buf, &subs, 0) && !markup::is_svgz(buf)
 }
 
 fn is_bz2(buf: &[u8]) -> bool {
@@ -56,14 +87,63 @@ fn is_7z(buf: &[u8]) -> bool {
     compare_bytes(buf, &subs, 0)
 }
 
+// Also accepts the leading junk that readers skip.
 fn is_pdf(buf: &[u8]) -> bool {
-    let subs = [0x25, 0x50, 0x44, 0x46];
+    pdf::header_offset(buf).is_some() && !pdf::is_illustrator(buf)
+}
+
+// Illustrator saves PDF files with its own data for editing.
+fn is_ai(buf: &[u8]) -> bool {
+    pdf::header_offset(buf).is_some() && pdf::is_illustrator(buf)
+}
+
+fn is_fdf(buf: &[u8]) -> bool {
+    let subs = [0x25, 0x46, 0x44, 0x46, 0x2D];
     compare_bytes(buf, &subs, 0)
 }
 
+fn is_pe_kind(buf: &[u8], kind: PeKind, dotnet: bool) -> bool {
+    matches!(pe::parse(buf), Some(info) if info.kind == kind && info.dotnet == dotnet)
+}
+
 fn is_exe(buf: &[u8]) -> bool {
-    let subs = [0x4D, 0x5A];
-    compare_bytes(buf, &subs, 0)
+    is_pe_kind(buf, PeKind::Exe, false)
+}
+
+fn is_dll(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Dll, false)
+}
+
+fn is_sys(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Driver, false)
+}
+
+fn is_efi(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Efi, false)
+}
+
+fn is_dotnet_exe(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Exe, true)
+}
+
+fn is_dotnet_dll(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Dll, true)
+}
+
+fn is_dos_exe(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Dos, false)
+}
+
+fn is_ne_exe(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Ne, false)
+}
+
+fn is_le_exe(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Le, false)
+}
+
+fn is_lx_exe(buf: &[u8]) -> bool {
+    is_pe_kind(buf, PeKind::Lx, false)
 }
 
 fn is_rtf(buf: &[u8]) -> bool {
@@ -81,9 +161,10 @@ fn is_crx(buf: &[u8]) -> bool {
     compare_bytes(buf, &subs, 0)
 }
 
+// Type 1 fonts are PostScript programs too, reported as fonts.
 fn is_ps(buf: &[u8]) -> bool {
     let subs = [0x25, 0x21];
-    compare_bytes(buf, &subs, 0)
+    compare_bytes(buf, &subs, 0) && !font::is_type1(buf)
 }
 
 fn is_xz(buf: &[u8]) -> bool {
@@ -158,15 +239,36 @@ fn is_eot(buf: &[u8]) -> bool {
 }
 
 fn is_z(buf: &[u8]) -> bool {
-    buf.len() > 1 && ((buf[0] == 0x1F && buf[1] == 0xA0) || (buf[0] == 0x1F && buf[1] == 0x9D))
+    buf.len() > 1 && buf[0] == 0x1F && (buf[1] == 0xA0 || buf[1] == 0x9D)
 }
 
 fn is_rpm(buf: &[u8]) -> bool {
     buf.len() > 96 && buf[0] == 0xED && buf[1] == 0xAB && buf[2] == 0xEE && buf[3] == 0xDB
 }
 
+fn is_elf_kind(buf: &[u8], kind: ElfKind) -> bool {
+    matches!(elf::parse(buf), Some(info) if info.kind == kind)
+}
+
+// Also covers ELF files whose `e_type` is unknown or OS/processor specific.
 fn is_elf(buf: &[u8]) -> bool {
-    buf.len() > 52 && buf[0] == 0x7F && buf[1] == 0x45 && buf[2] == 0x4C && buf[3] == 0x46
+    is_elf_kind(buf, ElfKind::Executable) || is_elf_kind(buf, ElfKind::Unknown)
+}
+
+fn is_elf_pie(buf: &[u8]) -> bool {
+    is_elf_kind(buf, ElfKind::Pie)
+}
+
+fn is_elf_so(buf: &[u8]) -> bool {
+    is_elf_kind(buf, ElfKind::SharedObject)
+}
+
+fn is_elf_obj(buf: &[u8]) -> bool {
+    is_elf_kind(buf, ElfKind::Relocatable)
+}
+
+fn is_elf_core(buf: &[u8]) -> bool {
+    is_elf_kind(buf, ElfKind::Core)
 }
 
 fn is_dcm(buf: &[u8]) -> bool {
@@ -178,15 +280,33 @@ fn is_iso(buf: &[u8]) -> bool {
     compare_bytes(buf, &subs, 32769)
 }
 
+fn macho_filetype(buf: &[u8]) -> Option<Option<u32>> {
+    macho::parse(buf).map(|info| info.filetype())
+}
+
+// Executables, plus universal binaries whose images lie past the inspected bytes.
 fn is_macho(buf: &[u8]) -> bool {
-    buf.len() > 3
-        && ((buf[0] == 0xFE && buf[1] == 0xED && buf[2] == 0xFA && buf[3] == 0xCF) ||
-(buf[0] == 0xFE && buf[1] == 0xED && buf[2] == 0xFA && buf[3] == 0xCE) ||
-(buf[0] == 0xBE && buf[1] == 0xBA && buf[2] == 0xFE && buf[3] == 0xCA) ||
-// Big endian versions below here...
-(buf[0] == 0xCF && buf[1] == 0xFA && buf[2] == 0xED && buf[3] == 0xFE) ||
-(buf[0] == 0xCE && buf[1] == 0xFA && buf[2] == 0xED && buf[3] == 0xFE) ||
-(buf[0] == 0xCA && buf[1] == 0xFE && buf[2] == 0xBA && buf[3] == 0xBE))
+    match macho_filetype(buf) {
+        Some(Some(MH_DYLIB | MH_BUNDLE | MH_OBJECT | MH_DSYM)) => false,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn is_macho_dylib(buf: &[u8]) -> bool {
+    macho_filetype(buf) == Some(Some(MH_DYLIB))
+}
+
+fn is_macho_bundle(buf: &[u8]) -> bool {
+    macho_filetype(buf) == Some(Some(MH_BUNDLE))
+}
+
+fn is_macho_object(buf: &[u8]) -> bool {
+    macho_filetype(buf) == Some(Some(MH_OBJECT))
+}
+
+fn is_macho_dsym(buf: &[u8]) -> bool {
+    macho_filetype(buf) == Some(Some(MH_DSYM))
 }
 
 const ZSTD_MAGIC_SKIPPABLE_START: u32 = 0x184D2A50;
@@ -212,6 +332,33 @@ fn is_zst(buf: &[u8]) -> bool {
     false
 }
 
+// Header analysis of `MZ` executables: kind, machine, subsystem and CLR presence.
+pub fn pe_info(buf: &[u8]) -> Option<PeInfo> {
+    pe::parse(buf)
+}
+
+// Header analysis of ELF files: object type, class, byte order, ABI, machine and interpreter.
+pub fn elf_info(buf: &[u8]) -> Option<ElfInfo> {
+    elf::parse(buf)
+}
+
+// Mach-O header analysis: fat or thin, the contained architectures and their filetypes.
+pub fn macho_info(buf: &[u8]) -> Option<MachOInfo> {
+    macho::parse(buf)
+}
+
+// PDF structure: version, encryption and permissions, linearization, page count, producer,
+// tagging and the PDF/A, PDF/UA, PDF/X and PDF/E claims.
+pub fn pdf_info(buf: &[u8]) -> Option<PdfInfo> {
+    pdf::parse(buf)
+}
+
+// Like `pdf_info`, for a file of `len` bytes read through `read`, which fills a buffer
+// from an offset and returns how many bytes it read.
+pub fn pdf_info_from(len: u64, read: &dyn Fn(u64, &mut [u8]) -> usize) -> Option<PdfInfo> {
+    pdf::parse_from(len, read)
+}
+
 pub fn sum() -> HashMapTypeMatcher {
     let mut ret = HashMapTypeMatcher::new();
 
@@ -225,9 +372,17 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_Z, is_z);
     ret.insert(TYPE_RPM, is_rpm);
     ret.insert(TYPE_ELF, is_elf);
+    ret.insert(TYPE_ELF_PIE, is_elf_pie);
+    ret.insert(TYPE_ELF_SO, is_elf_so);
+    ret.insert(TYPE_ELF_OBJ, is_elf_obj);
+    ret.insert(TYPE_ELF_CORE, is_elf_core);
     ret.insert(TYPE_DCM, is_dcm);
     ret.insert(TYPE_ISO, is_iso);
     ret.insert(TYPE_MACHO, is_macho);
+    ret.insert(TYPE_MACHO_DYLIB, is_macho_dylib);
+    ret.insert(TYPE_MACHO_BUNDLE, is_macho_bundle);
+    ret.insert(TYPE_MACHO_OBJECT, is_macho_object);
+    ret.insert(TYPE_MACHO_DSYM, is_macho_dsym);
     ret.insert(TYPE_ZST, is_zst);
 
     ret.insert(TYPE_EPUB, is_epub);
@@ -235,7 +390,18 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_BZ2, is_bz2);
     ret.insert(TYPE_7Z, is_7z);
     ret.insert(TYPE_PDF, is_pdf);
+    ret.insert(TYPE_AI, is_ai);
+    ret.insert(TYPE_FDF, is_fdf);
     ret.insert(TYPE_EXE, is_exe);
+    ret.insert(TYPE_DLL, is_dll);
+    ret.insert(TYPE_SYS, is_sys);
+    ret.insert(TYPE_EFI, is_efi);
+    ret.insert(TYPE_DOTNET_EXE, is_dotnet_exe);
+    ret.insert(TYPE_DOTNET_DLL, is_dotnet_dll);
+    ret.insert(TYPE_DOS_EXE, is_dos_exe);
+    ret.insert(TYPE_NE_EXE, is_ne_exe);
+    ret.insert(TYPE_LE_EXE, is_le_exe);
+    ret.insert(TYPE_LX_EXE, is_lx_exe);
     ret.insert(TYPE_RTF, is_rtf);
     ret.insert(TYPE_NES, is_nes);
     ret.insert(TYPE_CRX, is_crx);
@@ -1,23 +1,78 @@
-use super::base::{new_type, HashMapTypeMatcher, Type};
+use super::{
+    base::{new_type, HashMapTypeMatcher, Type},
+    utils::{
+        asf, codecs, compare_bytes,
+        ebml::{self, Variant},
+        mpeg,
+        ogg::{self, Codec},
+        riff::{self, Container, Kind},
+        sound, syncframe,
+    },
+};
+use std::time::Duration;
+
+pub use super::utils::sound::{AudioInfo, MidiHeader};
 
 const TYPE_MIDI: Type = new_type("audio/midi", "mid");
 const TYPE_MP3: Type = new_type("audio/mpeg", "mp3");
+const TYPE_MP2: Type = new_type("audio/mpeg", "mp2");
+const TYPE_MP1: Type = new_type("audio/mpeg", "mp1");
 const TYPE_M4A: Type = new_type("audio/mp4", "m4a");
 const TYPE_OGG: Type = new_type("audio/ogg", "ogg");
+// Xiph reserves .ogg for Vorbis I audio and recommends .oga for other Ogg audio.
+const TYPE_OGG_VORBIS: Type = new_type("audio/ogg; codecs=vorbis", "ogg");
+const TYPE_OGG_FLAC: Type = new_type("audio/ogg; codecs=flac", "oga");
+const TYPE_OPUS: Type = new_type("audio/opus", "opus");
+const TYPE_SPEEX: Type = new_type("audio/ogg; codecs=speex", "spx");
 const TYPE_FLAC: Type = new_type("audio/x-flac", "flac");
 const TYPE_WAV: Type = new_type("audio/x-wav", "wav");
 const TYPE_AMR: Type = new_type("audio/amr", "amr");
 const TYPE_AAC: Type = new_type("audio/aac", "aac");
+const TYPE_LOAS: Type = new_type("audio/mp4a-latm", "loas");
 const TYPE_AIFF: Type = new_type("audio/x-aiff", "aiff");
+const TYPE_BWF: Type = new_type("audio/x-wav", "bwf");
+const TYPE_RF64: Type = new_type("audio/x-wav", "rf64");
+const TYPE_RMID: Type = new_type("audio/mid", "rmi");
+const TYPE_SF2: Type = new_type("audio/x-soundfont", "sf2");
+const TYPE_DLS: Type = new_type("audio/dls", "dls");
+const TYPE_QCP: Type = new_type("audio/qcelp", "qcp");
+const TYPE_MKA: Type = new_type("audio/x-matroska", "mka");
+const TYPE_WEBA: Type = new_type("audio/webm", "weba");
+const TYPE_WMA: Type = new_type("audio/x-ms-wma", "wma");
+const TYPE_AMR_WB: Type = new_type("audio/amr-wb", "awb");
+const TYPE_APE: Type = new_type("audio/x-ape", "ape");
+const TYPE_WAVPACK: Type = new_type("audio/x-wavpack", "wv");
+const TYPE_DSF: Type = new_type("audio/x-dsf", "dsf");
+const TYPE_DFF: Type = new_type("audio/x-dff", "dff");
+const TYPE_CAF: Type = new_type("audio/x-caf", "caf");
+const TYPE_AU: Type = new_type("audio/basic", "au");
+const TYPE_W64: Type = new_type("audio/x-w64", "w64");
+const TYPE_MPC: Type = new_type("audio/x-musepack", "mpc");
+const TYPE_TTA: Type = new_type("audio/x-tta", "tta");
+const TYPE_OFR: Type = new_type("audio/x-optimfrog", "ofr");
+const TYPE_AC3: Type = new_type("audio/ac3", "ac3");
+const TYPE_EAC3: Type = new_type("audio/eac3", "eac3");
+const TYPE_DTS: Type = new_type("audio/vnd.dts", "dts");
+const TYPE_MOD: Type = new_type("audio/x-mod", "mod");
+const TYPE_XM: Type = new_type("audio/x-xm", "xm");
+const TYPE_S3M: Type = new_type("audio/x-s3m", "s3m");
+const TYPE_IT: Type = new_type("audio/x-it", "it");
+const TYPE_VOC: Type = new_type("audio/x-voc", "voc");
 
 fn is_midi(buf: &[u8]) -> bool {
     buf.len() > 3 && buf[0] == 0x4D && buf[1] == 0x54 && buf[2] == 0x68 && buf[3] == 0x64
 }
 
 fn is_mp3(buf: &[u8]) -> bool {
-    buf.len() > 2
-        && ((buf[0] == 0x49 && buf[1] == 0x44 && buf[2] == 0x33)
-            || (buf[0] == 0xFF && buf[1] == 0xfb))
+    matches!(mpeg::identify(buf), Some(mpeg::Kind::Mp3))
+}
+
+fn is_mp2(buf: &[u8]) -> bool {
+    matches!(mpeg::identify(buf), Some(mpeg::Kind::Mp2))
+}
+
+fn is_mp1(buf: &[u8]) -> bool {
+    matches!(mpeg::identify(buf), Some(mpeg::Kind::Mp1))
 }
 
 fn is_m4a(buf: &[u8]) -> bool {
@@ -32,8 +87,25 @@ fn is_m4a(buf: &[u8]) -> bool {
             || (buf[0] == 0x4D && buf[1] == 0x34 && buf[2] == 0x41 && buf[3] == 0x20))
 }
 
+// Ogg files whose codec is not recognised; video streams are left to the video matchers.
 fn is_ogg(buf: &[u8]) -> bool {
-    buf.len() > 3 && buf[0] == 0x4F && buf[1] == 0x67 && buf[2] == 0x67 && buf[3] == 0x53
+    matches!(ogg::identify(buf), Some(Codec::Unknown))
+}
+
+fn is_ogg_vorbis(buf: &[u8]) -> bool {
+    matches!(ogg::identify(buf), Some(Codec::Vorbis))
+}
+
+fn is_ogg_flac(buf: &[u8]) -> bool {
+    matches!(ogg::identify(buf), Some(Codec::Flac))
+}
+
+fn is_opus(buf: &[u8]) -> bool {
+    matches!(ogg::identify(buf), Some(Codec::Opus))
+}
+
+fn is_speex(buf: &[u8]) -> bool {
+    matches!(ogg::identify(buf), Some(Codec::Speex))
 }
 
 fn is_flac(buf: &[u8]) -> bool {
@@ -41,15 +113,41 @@ fn is_flac(buf: &[u8]) -> bool {
 }
 
 fn is_wav(buf: &[u8]) -> bool {
-    buf.len() > 11
-        && buf[0] == 0x52
-        && buf[1] == 0x49
-        && buf[2] == 0x46
-        && buf[3] == 0x46
-        && buf[8] == 0x57
-        && buf[9] == 0x41
-        && buf[10] == 0x56
-        && buf[11] == 0x45
+    matches!(
+        riff::identify(buf),
+        Some((Container::Riff | Container::Rifx, Kind::Wave))
+    )
+}
+
+fn is_bwf(buf: &[u8]) -> bool {
+    matches!(
+        riff::identify(buf),
+        Some((Container::Riff | Container::Rifx, Kind::Bwf))
+    )
+}
+
+// RF64 and BW64 lift the 4 GiB limit of WAVE; both may carry a `bext` chunk.
+fn is_rf64(buf: &[u8]) -> bool {
+    matches!(
+        riff::identify(buf),
+        Some((Container::Rf64 | Container::Bw64, Kind::Wave | Kind::Bwf))
+    )
+}
+
+fn is_rmid(buf: &[u8]) -> bool {
+    matches!(riff::identify(buf), Some((_, Kind::Rmid)))
+}
+
+fn is_sf2(buf: &[u8]) -> bool {
+    matches!(riff::identify(buf), Some((_, Kind::Sf2)))
+}
+
+fn is_dls(buf: &[u8]) -> bool {
+    matches!(riff::identify(buf), Some((_, Kind::Dls)))
+}
+
+fn is_qcp(buf: &[u8]) -> bool {
+    matches!(riff::identify(buf), Some((_, Kind::Qcp)))
 }
 
 fn is_amr(buf: &[u8]) -> bool {
@@ -63,7 +161,11 @@ fn is_amr(buf: &[u8]) -> bool {
 }
 
 fn is_aac(buf: &[u8]) -> bool {
-    buf.len() > 1 && ((buf[0] == 0xFF && buf[1] == 0xF1) || (buf[0] == 0xFF && buf[1] == 0xF9))
+    matches!(mpeg::identify(buf), Some(mpeg::Kind::Adts))
+}
+
+fn is_loas(buf: &[u8]) -> bool {
+    matches!(mpeg::identify(buf), Some(mpeg::Kind::Loas))
 }
 
 fn is_aiff(buf: &[u8]) -> bool {
@@ -78,19 +180,196 @@ fn is_aiff(buf: &[u8]) -> bool {
         && buf[11] == 0x46
 }
 
+fn is_mka(buf: &[u8]) -> bool {
+    matches!(ebml::identify(buf), Some(("matroska", Variant::Audio)))
+}
+
+fn is_weba(buf: &[u8]) -> bool {
+    matches!(ebml::identify(buf), Some(("webm", Variant::Audio)))
+}
+
+fn is_wma(buf: &[u8]) -> bool {
+    matches!(asf::identify(buf), Some(asf::Kind::Wma))
+}
+
+fn is_amr_wb(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"#!AMR-WB\n", 0)
+}
+
+fn is_ape(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"MAC ", 0)
+}
+
+// `wvpk` block header with a stream version from 0x402 to 0x410.
+fn is_wavpack(buf: &[u8]) -> bool {
+    buf.len() > 9
+        && compare_bytes(buf, b"wvpk", 0)
+        && (0x402..=0x410).contains(&u16::from_le_bytes([buf[8], buf[9]]))
+}
+
+// The `DSD ` chunk is always 28 bytes long.
+fn is_dsf(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"DSD \x1C\0\0\0\0\0\0\0", 0)
+}
+
+fn is_dff(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"FRM8", 0) && compare_bytes(buf, b"DSD ", 12)
+}
+
+fn is_caf(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"caff\0\x01", 0)
+}
+
+fn is_au(buf: &[u8]) -> bool {
+    compare_bytes(buf, b".snd", 0)
+}
+
+// Sony Wave64 replaces the RIFF FourCCs with GUIDs.
+fn is_w64(buf: &[u8]) -> bool {
+    let riff = [
+        0x72, 0x69, 0x66, 0x66, 0x2E, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00,
+        0x00,
+    ];
+    let wave = [
+        0x77, 0x61, 0x76, 0x65, 0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB,
+        0x8A,
+    ];
+    compare_bytes(buf, &riff, 0) && compare_bytes(buf, &wave, 24)
+}
+
+// SV8 streams start with `MPCK`, SV7 with `MP+` and the version in the low nibble.
+fn is_mpc(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"MPCK", 0)
+        || (compare_bytes(buf, b"MP+", 0) && buf.len() > 3 && buf[3] & 0x0F == 7)
+}
+
+fn is_tta(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"TTA1", 0)
+}
+
+fn is_ofr(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"OFR ", 0)
+}
+
+fn is_ac3(buf: &[u8]) -> bool {
+    matches!(syncframe::identify(buf), Some(syncframe::Kind::Ac3))
+}
+
+fn is_eac3(buf: &[u8]) -> bool {
+    matches!(syncframe::identify(buf), Some(syncframe::Kind::Eac3))
+}
+
+fn is_dts(buf: &[u8]) -> bool {
+    matches!(syncframe::identify(buf), Some(syncframe::Kind::Dts))
+}
+
+// ProTracker and compatible modules keep a channel tag after the 31 sample headers
+// and the pattern table, at offset 1080. Two-digit tags are common in other data, so
+// the sample headers (finetune and volume) and the song length and pattern numbers
+// must be in range as well.
+fn is_mod(buf: &[u8]) -> bool {
+    let tag = match buf.get(1080..1084) {
+        Some(t) => t,
+        None => return false,
+    };
+    let known = match tag {
+        b"M.K." | b"M!K!" | b"M&K!" | b"FLT4" | b"FLT8" | b"CD81" | b"OKTA" | b"OCTA" => true,
+        // `2CHN` to `9CHN` and `10CH` to `32CH`
+        [n, b'C', b'H', b'N'] => (b'2'..=b'9').contains(n),
+        [n1, n2, b'C', b'H'] if n1.is_ascii_digit() && n2.is_ascii_digit() => {
+            (10..=32).contains(&((n1 - b'0') * 10 + (n2 - b'0')))
+        }
+        _ => false,
+    };
+
+    known
+        && buf[20..950]
+            .chunks_exact(30)
+            .all(|sample| sample[24] <= 0x0F && sample[25] <= 64)
+        && (1..=128).contains(&buf[950])
+        && buf[952..1080].iter().all(|pattern| *pattern < 128)
+}
+
+fn is_xm(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"Extended Module: ", 0)
+}
+
+fn is_s3m(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"SCRM", 44)
+}
+
+fn is_it(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"IMPM", 0)
+}
+
+fn is_voc(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"Creative Voice File\x1A", 0)
+}
+
+// Header analysis of audio streams: sample rate, channels, sample size, duration, bitrate
+// and the MIDI header.
+pub fn audio_info(buf: &[u8]) -> Option<AudioInfo> {
+    sound::parse(buf)
+}
+
+// RFC 6381 `codecs` values of the MP4, QuickTime and Matroska tracks, in track order.
+pub fn codecs(buf: &[u8]) -> Option<Vec<String>> {
+    codecs::parse(buf)
+}
+
+// The length of an Ogg stream, from the start of the file and a read of its end.
+pub fn ogg_duration(head: &[u8], tail: &[u8]) -> Option<Duration> {
+    sound::ogg_duration(head, tail)
+}
+
 pub fn sum() -> HashMapTypeMatcher {
     let mut ret = HashMapTypeMatcher::new();
 
     // ret.insert(XXX, is_xxx);
     ret.insert(TYPE_MIDI, is_midi);
     ret.insert(TYPE_MP3, is_mp3);
+    ret.insert(TYPE_MP2, is_mp2);
+    ret.insert(TYPE_MP1, is_mp1);
     ret.insert(TYPE_M4A, is_m4a);
     ret.insert(TYPE_OGG, is_ogg);
+    ret.insert(TYPE_OGG_VORBIS, is_ogg_vorbis);
+    ret.insert(TYPE_OGG_FLAC, is_ogg_flac);
+    ret.insert(TYPE_OPUS, is_opus);
+    ret.insert(TYPE_SPEEX, is_speex);
     ret.insert(TYPE_FLAC, is_flac);
     ret.insert(TYPE_WAV, is_wav);
     ret.insert(TYPE_AMR, is_amr);
     ret.insert(TYPE_AAC, is_aac);
+    ret.insert(TYPE_LOAS, is_loas);
     ret.insert(TYPE_AIFF, is_aiff);
+    ret.insert(TYPE_BWF, is_bwf);
+    ret.insert(TYPE_RF64, is_rf64);
+    ret.insert(TYPE_RMID, is_rmid);
+    ret.insert(TYPE_SF2, is_sf2);
+    ret.insert(TYPE_DLS, is_dls);
+    ret.insert(TYPE_QCP, is_qcp);
+    ret.insert(TYPE_MKA, is_mka);
+    ret.insert(TYPE_WEBA, is_weba);
+    ret.insert(TYPE_WMA, is_wma);
+    ret.insert(TYPE_AMR_WB, is_amr_wb);
+    ret.insert(TYPE_APE, is_ape);
+    ret.insert(TYPE_WAVPACK, is_wavpack);
+    ret.insert(TYPE_DSF, is_dsf);
+    ret.insert(TYPE_DFF, is_dff);
+    ret.insert(TYPE_CAF, is_caf);
+    ret.insert(TYPE_AU, is_au);
+    ret.insert(TYPE_W64, is_w64);
+    ret.insert(TYPE_MPC, is_mpc);
+    ret.insert(TYPE_TTA, is_tta);
+    ret.insert(TYPE_OFR, is_ofr);
+    ret.insert(TYPE_AC3, is_ac3);
+    ret.insert(TYPE_EAC3, is_eac3);
+    ret.insert(TYPE_DTS, is_dts);
+    ret.insert(TYPE_MOD, is_mod);
+    ret.insert(TYPE_XM, is_xm);
+    ret.insert(TYPE_S3M, is_s3m);
+    ret.insert(TYPE_IT, is_it);
+    ret.insert(TYPE_VOC, is_voc);
 
     ret
 }
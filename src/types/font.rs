@@ -1,50 +1,72 @@
-use super::base::{new_type, HashMapTypeMatcher, Type};
+use super::{
+    base::{new_type, HashMapTypeMatcher, Type},
+    utils::font,
+};
+
+pub use super::utils::font::{FontInfo, Outlines};
 
 const TYPE_WOFF: Type = new_type("application/font-woff", "woff");
 const TYPE_WOFF2: Type = new_type("application/font-woff", "woff2");
 const TYPE_TTF: Type = new_type("application/font-sfnt", "ttf");
 const TYPE_OTF: Type = new_type("application/font-sfnt", "otf");
+const TYPE_TTC: Type = new_type("font/collection", "ttc");
+const TYPE_SFNT_TYPE1: Type = new_type("application/font-sfnt", "sfnt"); // Mac sfnt-wrapped Type 1 fonts have no extension.
+const TYPE_PFA: Type = new_type("application/x-font-type1", "pfa");
+const TYPE_PFB: Type = new_type("application/x-font-type1", "pfb");
+const TYPE_DFONT: Type = new_type("application/x-dfont", "dfont");
+const TYPE_BDF: Type = new_type("application/x-font-bdf", "bdf");
+const TYPE_PCF: Type = new_type("application/x-font-pcf", "pcf");
 
 fn is_woff(buf: &[u8]) -> bool {
-    buf.len() > 7
-        && buf[0] == 0x77
-        && buf[1] == 0x4F
-        && buf[2] == 0x46
-        && buf[3] == 0x46
-        && buf[4] == 0x00
-        && buf[5] == 0x01
-        && buf[6] == 0x00
-        && buf[7] == 0x00
+    font::is_woff(buf, b"wOFF")
 }
 
 fn is_woff2(buf: &[u8]) -> bool {
-    buf.len() > 7
-        && buf[0] == 0x77
-        && buf[1] == 0x4F
-        && buf[2] == 0x46
-        && buf[3] == 0x32
-        && buf[4] == 0x00
-        && buf[5] == 0x01
-        && buf[6] == 0x00
-        && buf[7] == 0x00
+    font::is_woff(buf, b"wOF2")
 }
 
+// Version 1.0 or Apple's `true`, with a table directory.
 fn is_ttf(buf: &[u8]) -> bool {
-    buf.len() > 4
-        && buf[0] == 0x00
-        && buf[1] == 0x01
-        && buf[2] == 0x00
-        && buf[3] == 0x00
-        && buf[4] == 0x00
+    font::is_sfnt_version(buf, b"\x00\x01\x00\x00") || font::is_sfnt_version(buf, b"true")
 }
 
 fn is_otf(buf: &[u8]) -> bool {
-    buf.len() > 4
-        && buf[0] == 0x4F
-        && buf[1] == 0x54
-        && buf[2] == 0x54
-        && buf[3] == 0x4F
-        && buf[4] == 0x00
+    font::is_sfnt_version(buf, b"OTTO")
+}
+
+fn is_ttc(buf: &[u8]) -> bool {
+    font::is_collection(buf)
+}
+
+fn is_sfnt_type1(buf: &[u8]) -> bool {
+    font::is_sfnt_version(buf, b"typ1")
+}
+
+fn is_pfa(buf: &[u8]) -> bool {
+    font::is_type1(buf)
+}
+
+fn is_pfb(buf: &[u8]) -> bool {
+    font::is_pfb(buf)
+}
+
+// Also covers font suitcases saved from a resource fork.
+fn is_dfont(buf: &[u8]) -> bool {
+    font::is_resource_font(buf)
+}
+
+fn is_bdf(buf: &[u8]) -> bool {
+    font::is_bdf(buf)
+}
+
+fn is_pcf(buf: &[u8]) -> bool {
+    font::is_pcf(buf)
+}
+
+// Name, weight, outline format and variation axes of sfnt-based fonts, and what Type 1,
+// BDF and PCF files declare.
+pub fn font_info(buf: &[u8]) -> Option<FontInfo> {
+    font::parse(buf)
 }
 
 pub fn sum() -> HashMapTypeMatcher {
@@ -55,6 +77,13 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_WOFF2, is_woff2);
     ret.insert(TYPE_TTF, is_ttf);
     ret.insert(TYPE_OTF, is_otf);
+    ret.insert(TYPE_TTC, is_ttc);
+    ret.insert(TYPE_SFNT_TYPE1, is_sfnt_type1);
+    ret.insert(TYPE_PFA, is_pfa);
+    ret.insert(TYPE_PFB, is_pfb);
+    ret.insert(TYPE_DFONT, is_dfont);
+    ret.insert(TYPE_BDF, is_bdf);
+    ret.insert(TYPE_PCF, is_pcf);
 
     ret
 }
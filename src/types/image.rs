@@ -1,8 +1,17 @@
 use super::{
     base::{new_type, HashMapTypeMatcher, Type},
-    utils::image::{get_ftyp, is_iso_bmf},
+    utils::{
+        font,
+        image::{get_ftyp, is_iso_bmf},
+        markup, metadata, raster,
+        riff::{self, Container, Kind},
+        tiff,
+    },
 };
 
+pub use super::utils::metadata::{GpsPosition, Metadata};
+pub use super::utils::raster::{ColorType, ImageInfo, JpegScan};
+
 const TYPE_JPEG: Type = new_type("image/jpeg", "jpg");
 const TYPE_JPEG2000: Type = new_type("image/jp2", "jp2");
 const TYPE_PNG: Type = new_type("image/png", "png");
@@ -10,6 +19,22 @@ const TYPE_GIF: Type = new_type("image/gif", "gif");
 const TYPE_WEBP: Type = new_type("image/webp", "webp");
 const TYPE_CR2: Type = new_type("image/x-canon-cr2", "cr2");
 const TYPE_TIFF: Type = new_type("image/tiff", "tif");
+const TYPE_DNG: Type = new_type("image/x-adobe-dng", "dng");
+const TYPE_NEF: Type = new_type("image/x-nikon-nef", "nef");
+const TYPE_NRW: Type = new_type("image/x-nikon-nrw", "nrw");
+const TYPE_ARW: Type = new_type("image/x-sony-arw", "arw");
+const TYPE_SR2: Type = new_type("image/x-sony-sr2", "sr2");
+const TYPE_PEF: Type = new_type("image/x-pentax-pef", "pef");
+const TYPE_ORF: Type = new_type("image/x-olympus-orf", "orf");
+const TYPE_RW2: Type = new_type("image/x-panasonic-rw2", "rw2");
+const TYPE_ERF: Type = new_type("image/x-epson-erf", "erf");
+const TYPE_3FR: Type = new_type("image/x-hasselblad-3fr", "3fr");
+const TYPE_IIQ: Type = new_type("image/x-phaseone-iiq", "iiq");
+const TYPE_SRW: Type = new_type("image/x-samsung-srw", "srw");
+const TYPE_RAF: Type = new_type("image/x-fuji-raf", "raf");
+const TYPE_MRW: Type = new_type("image/x-minolta-mrw", "mrw");
+const TYPE_X3F: Type = new_type("image/x-sigma-x3f", "x3f");
+const TYPE_CR3: Type = new_type("image/x-canon-cr3", "cr3");
 const TYPE_BMP: Type = new_type("image/bmp", "bmp");
 const TYPE_JXR: Type = new_type("image/vnd.ms-photo", "jxr");
 const TYPE_PSD: Type = new_type("image/vnd.adobe.photosh", "psd");
@@ -18,6 +43,10 @@ const TYPE_HEIF: Type = new_type("image/heif", "heif");
 const TYPE_DWG: Type = new_type("image/vnd.dwg", "dwg");
 const TYPE_EXR: Type = new_type("image/x-exr", "exr");
 const TYPE_AVIF: Type = new_type("image/avif", "avif");
+const TYPE_ANI: Type = new_type("application/x-navi-animation", "ani");
+const TYPE_CDR: Type = new_type("application/vnd.corel-draw", "cdr");
+const TYPE_SVG: Type = new_type("image/svg+xml", "svg");
+const TYPE_SVGZ: Type = new_type("image/svg+xml", "svgz");
 
 fn is_jpeg(buf: &[u8]) -> bool {
     buf.len() > 2 && buf[..3] == [0xFF, 0xD8, 0xFF]
@@ -40,27 +69,86 @@ fn is_gif(buf: &[u8]) -> bool {
 }
 
 fn is_webp(buf: &[u8]) -> bool {
-    buf.len() > 11 && buf[8..12] == [0x57, 0x45, 0x42, 0x50]
+    matches!(
+        riff::identify(buf),
+        Some((
+            Container::Riff,
+            Kind::WebpLossy | Kind::WebpLossless | Kind::WebpExtended
+        ))
+    )
 }
 
 fn is_cr2(buf: &[u8]) -> bool {
-    buf.len() > 10 &&
-    (
-        buf[..4] == [0x49, 0x49, 0x2A, 0x00] || // Little Endian
-        buf[..4] == [0x4D, 0x4D, 0x00, 0x2A] // Big Endian
-    ) &&
-    buf[8..10] == [0x43, 0x52] && // CR2 magic word
-    buf[10] == 0x02 // CR2 major version
+    tiff::identify(buf) == Some(tiff::Kind::Cr2)
 }
 
+// TIFF-based RAW formats are told apart from plain TIFF by their IFD contents.
 fn is_tiff(buf: &[u8]) -> bool {
-    buf.len() > 10
-        && (
-            buf[..4] == [ 0x49, 0x49, 0x2A, 0x00 ] || // Little Endian
-        buf[..4] == [ 0x4D, 0x4D, 0x00, 0x2A ]
-            // Big Endian
-        )
-        && !is_cr2(buf) // To avoid conflicts differentiate Tiff from CR2
+    tiff::identify(buf) == Some(tiff::Kind::Tiff)
+}
+
+fn is_dng(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Dng)
+}
+
+fn is_nef(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Nef)
+}
+
+fn is_nrw(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Nrw)
+}
+
+fn is_arw(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Arw)
+}
+
+fn is_sr2(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Sr2)
+}
+
+fn is_pef(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Pef)
+}
+
+fn is_orf(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Orf)
+}
+
+fn is_rw2(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Rw2)
+}
+
+fn is_erf(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Erf)
+}
+
+fn is_3fr(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Fff)
+}
+
+fn is_iiq(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Iiq)
+}
+
+fn is_srw(buf: &[u8]) -> bool {
+    tiff::identify(buf) == Some(tiff::Kind::Srw)
+}
+
+fn is_raf(buf: &[u8]) -> bool {
+    buf.len() > 15 && buf[..16] == *b"FUJIFILMCCD-RAW "
+}
+
+fn is_mrw(buf: &[u8]) -> bool {
+    buf.len() > 3 && buf[..4] == [0x00, 0x4D, 0x52, 0x4D]
+}
+
+fn is_x3f(buf: &[u8]) -> bool {
+    buf.len() > 3 && buf[..4] == *b"FOVb"
+}
+
+fn is_cr3(buf: &[u8]) -> bool {
+    is_iso_bmf(buf) && get_ftyp(buf).0 == "crx "
 }
 
 fn is_bmp(buf: &[u8]) -> bool {
@@ -75,8 +163,9 @@ fn is_psd(buf: &[u8]) -> bool {
     buf.len() > 3 && buf[..4] == [0x38, 0x42, 0x50, 0x53]
 }
 
+// The resource fork header of a dfont starts with the same bytes.
 fn is_ico(buf: &[u8]) -> bool {
-    buf.len() > 3 && buf[..4] == [0x00, 0x00, 0x01, 0x00]
+    buf.len() > 3 && buf[..4] == [0x00, 0x00, 0x01, 0x00] && !font::is_resource_font(buf)
 }
 
 fn is_heif(buf: &[u8]) -> bool {
@@ -128,6 +217,48 @@ fn is_avif(buf: &[u8]) -> bool {
     false
 }
 
+fn is_ani(buf: &[u8]) -> bool {
+    matches!(riff::identify(buf), Some((_, Kind::Ani)))
+}
+
+fn is_cdr(buf: &[u8]) -> bool {
+    matches!(riff::identify(buf), Some((_, Kind::Cdr)))
+}
+
+fn is_svg(buf: &[u8]) -> bool {
+    markup::is_svg(buf)
+}
+
+fn is_svgz(buf: &[u8]) -> bool {
+    markup::is_svgz(buf)
+}
+
+// Header analysis of raster images: dimensions, bit depth, color type, alpha and frames.
+pub fn image_info(buf: &[u8]) -> Option<ImageInfo> {
+    raster::parse(buf)
+}
+
+// The JPEG marker segments from `offset` on, for frame headers past the end of `buf`.
+pub fn jpeg_scan(buf: &[u8], offset: usize) -> JpegScan {
+    raster::jpeg_scan(buf, offset)
+}
+
+// EXIF, XMP and ICC metadata of JPEG, TIFF, PNG, HEIF and AVIF images: camera make and
+// model, orientation, capture time, GPS position and the color profile name.
+pub fn metadata(buf: &[u8]) -> Option<Metadata> {
+    metadata::parse(buf)
+}
+
+// The file range of a HEIF Exif item, for items stored past the end of `buf`.
+pub fn exif_item_extent(buf: &[u8]) -> Option<(u64, u64)> {
+    metadata::exif_item_extent(buf)
+}
+
+// Adds the EXIF fields of an item read from the range given by `exif_item_extent`.
+pub fn apply_exif_item(meta: &mut Metadata, item: &[u8]) {
+    metadata::apply_exif_item(meta, item)
+}
+
 pub fn sum() -> HashMapTypeMatcher {
     let mut ret = HashMapTypeMatcher::new();
 
@@ -138,6 +269,22 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_WEBP, is_webp);
     ret.insert(TYPE_CR2, is_cr2);
     ret.insert(TYPE_TIFF, is_tiff);
+    ret.insert(TYPE_DNG, is_dng);
+    ret.insert(TYPE_NEF, is_nef);
+    ret.insert(TYPE_NRW, is_nrw);
+    ret.insert(TYPE_ARW, is_arw);
+    ret.insert(TYPE_SR2, is_sr2);
+    ret.insert(TYPE_PEF, is_pef);
+    ret.insert(TYPE_ORF, is_orf);
+    ret.insert(TYPE_RW2, is_rw2);
+    ret.insert(TYPE_ERF, is_erf);
+    ret.insert(TYPE_3FR, is_3fr);
+    ret.insert(TYPE_IIQ, is_iiq);
+    ret.insert(TYPE_SRW, is_srw);
+    ret.insert(TYPE_RAF, is_raf);
+    ret.insert(TYPE_MRW, is_mrw);
+    ret.insert(TYPE_X3F, is_x3f);
+    ret.insert(TYPE_CR3, is_cr3);
     ret.insert(TYPE_BMP, is_bmp);
     ret.insert(TYPE_JXR, is_jxr);
     ret.insert(TYPE_PSD, is_psd);
@@ -146,6 +293,10 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_DWG, is_dwg);
     ret.insert(TYPE_EXR, is_exr);
     ret.insert(TYPE_AVIF, is_avif);
+    ret.insert(TYPE_ANI, is_ani);
+    ret.insert(TYPE_CDR, is_cdr);
+    ret.insert(TYPE_SVG, is_svg);
+    ret.insert(TYPE_SVGZ, is_svgz);
 
     ret
 }
@@ -5,6 +5,7 @@ mod base;
 pub mod document;
 pub mod font;
 pub mod image;
+pub mod text;
 mod utils;
 pub mod video;
 
@@ -12,6 +13,7 @@ pub use base::HashMapTypeMatcher;
 pub use base::Type;
 pub use base::TYPE_UNKNOWN;
 
+// Every category with a magic number; `text` is kept apart as the fallback.
 pub fn sum() -> HashMapTypeMatcher {
     let mut ret = HashMapTypeMatcher::new();
 
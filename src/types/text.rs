@@ -0,0 +1,350 @@
+use super::{
+    base::{new_type, Type, TYPE_UNKNOWN},
+    utils::{
+        data::{self, Format, JsonDialect},
+        markup::{self, Markup, XmlDialect},
+        source::{self, Language},
+        text::{self, Encoding},
+    },
+};
+use crate::constants::NUM_SIGNATURE_BYTES;
+
+pub use super::utils::{
+    data::{ConfigDialect, ConfigInfo, CsvDialect},
+    source::Shebang,
+    text::{Encoding as TextEncoding, LineEnding, TextInfo},
+};
+
+const TYPE_ASCII: Type = new_type("text/plain; charset=us-ascii", "txt");
+const TYPE_UTF8: Type = new_type("text/plain; charset=utf-8", "txt");
+const TYPE_UTF16LE: Type = new_type("text/plain; charset=utf-16le", "txt");
+const TYPE_UTF16BE: Type = new_type("text/plain; charset=utf-16be", "txt");
+const TYPE_UTF32LE: Type = new_type("text/plain; charset=utf-32le", "txt");
+const TYPE_UTF32BE: Type = new_type("text/plain; charset=utf-32be", "txt");
+const TYPE_LATIN1: Type = new_type("text/plain; charset=iso-8859-1", "txt");
+const TYPE_WINDOWS1252: Type = new_type("text/plain; charset=windows-1252", "txt");
+const TYPE_JSON: Type = new_type("application/json", "json");
+const TYPE_IPYNB: Type = new_type("application/x-ipynb+json", "ipynb");
+const TYPE_GEOJSON: Type = new_type("application/geo+json", "geojson");
+const TYPE_TOPOJSON: Type = new_type("application/topo+json", "topojson");
+const TYPE_GLTF: Type = new_type("model/gltf+json", "gltf");
+const TYPE_HAR: Type = new_type("application/x-har+json", "har");
+const TYPE_JSONLD: Type = new_type("application/ld+json", "jsonld");
+const TYPE_PACKAGE_JSON: Type = new_type("application/vnd.npm.package+json", "json");
+const TYPE_PACKAGE_LOCK: Type = new_type("application/vnd.npm.package-lock+json", "json");
+const TYPE_COMPOSER: Type = new_type("application/vnd.composer+json", "json");
+const TYPE_TFSTATE: Type = new_type("application/vnd.terraform.state+json", "tfstate");
+const TYPE_OPENAPI_JSON: Type = new_type("application/vnd.oai.openapi+json", "json");
+const TYPE_JSON_SCHEMA: Type = new_type("application/schema+json", "json");
+const TYPE_LOTTIE: Type = new_type("video/lottie+json", "json");
+const TYPE_WEB_MANIFEST: Type = new_type("application/manifest+json", "webmanifest");
+const TYPE_NDJSON: Type = new_type("application/x-ndjson", "ndjson");
+const TYPE_TOML: Type = new_type("application/toml", "toml");
+const TYPE_KUBERNETES: Type = new_type("application/vnd.kubernetes+yaml", "yaml");
+const TYPE_HELM_CHART: Type = new_type("application/vnd.helm.chart+yaml", "yaml");
+const TYPE_DOCKER_COMPOSE: Type = new_type("application/vnd.docker.compose+yaml", "yaml");
+const TYPE_GITHUB_ACTIONS: Type = new_type("application/vnd.github.actions+yaml", "yml");
+const TYPE_GITLAB_CI: Type = new_type("application/vnd.gitlab.ci+yaml", "yml");
+const TYPE_ANSIBLE: Type = new_type("application/vnd.ansible.playbook+yaml", "yml");
+const TYPE_OPENAPI_YAML: Type = new_type("application/vnd.oai.openapi", "yaml");
+const TYPE_CONDA: Type = new_type("application/vnd.conda.environment+yaml", "yml");
+const TYPE_CARGO_TOML: Type = new_type("application/vnd.cargo.manifest+toml", "toml");
+const TYPE_CARGO_LOCK: Type = new_type("application/vnd.cargo.lock+toml", "lock");
+const TYPE_PYPROJECT: Type = new_type("application/vnd.python.pyproject+toml", "toml");
+const TYPE_POETRY_LOCK: Type = new_type("application/vnd.poetry.lock+toml", "lock");
+const TYPE_UV_LOCK: Type = new_type("application/vnd.uv.lock+toml", "lock");
+const TYPE_INI: Type = new_type("text/x-ini", "ini");
+const TYPE_PROPERTIES: Type = new_type("text/x-java-properties", "properties");
+const TYPE_YAML: Type = new_type("application/yaml", "yaml");
+const TYPE_CSV: Type = new_type("text/csv", "csv");
+const TYPE_TSV: Type = new_type("text/tab-separated-values", "tsv");
+const TYPE_HTML: Type = new_type("text/html", "html");
+const TYPE_XHTML: Type = new_type("application/xhtml+xml", "xhtml");
+const TYPE_XML: Type = new_type("application/xml", "xml");
+// Also matched as an image by `image::is_svg`.
+const TYPE_SVG: Type = new_type("image/svg+xml", "svg");
+const TYPE_RSS: Type = new_type("application/rss+xml", "rss");
+const TYPE_ATOM: Type = new_type("application/atom+xml", "atom");
+const TYPE_XSLT: Type = new_type("application/xslt+xml", "xsl");
+const TYPE_XSD: Type = new_type("application/x-xsd", "xsd");
+const TYPE_WSDL: Type = new_type("application/wsdl+xml", "wsdl");
+const TYPE_SOAP: Type = new_type("application/soap+xml", "xml");
+const TYPE_PLIST: Type = new_type("application/x-plist", "plist");
+const TYPE_GPX: Type = new_type("application/gpx+xml", "gpx");
+const TYPE_KML: Type = new_type("application/vnd.google-earth.kml+xml", "kml");
+const TYPE_COLLADA: Type = new_type("model/vnd.collada+xml", "dae");
+const TYPE_MATHML: Type = new_type("application/mathml+xml", "mml");
+const TYPE_DOCBOOK: Type = new_type("application/docbook+xml", "dbk");
+const TYPE_XLIFF: Type = new_type("application/xliff+xml", "xlf");
+const TYPE_MUSICXML: Type = new_type("application/vnd.recordare.musicxml+xml", "musicxml");
+const TYPE_ANDROID_MANIFEST: Type = new_type("application/vnd.android.manifest+xml", "xml");
+const TYPE_ANDROID_RESOURCE: Type = new_type("application/vnd.android.resource+xml", "xml");
+const TYPE_POM: Type = new_type("application/x-maven+xml", "pom");
+const TYPE_MSBUILD: Type = new_type("application/x-msbuild+xml", "csproj");
+const TYPE_FODT: Type = new_type("application/vnd.oasis.opendocument.text-flat-xml", "fodt");
+const TYPE_FODS: Type = new_type(
+    "application/vnd.oasis.opendocument.spreadsheet-flat-xml",
+    "fods",
+);
+const TYPE_FODP: Type = new_type(
+    "application/vnd.oasis.opendocument.presentation-flat-xml",
+    "fodp",
+);
+const TYPE_FODG: Type = new_type(
+    "application/vnd.oasis.opendocument.graphics-flat-xml",
+    "fodg",
+);
+const TYPE_WORDML: Type = new_type("application/vnd.ms-wordml", "xml");
+const TYPE_SPREADSHEETML: Type = new_type("application/vnd.ms-spreadsheetml", "xml");
+const TYPE_XFDF: Type = new_type("application/vnd.adobe.xfdf", "xfdf");
+const TYPE_LATEX: Type = new_type("text/x-tex", "tex");
+const TYPE_MAN: Type = new_type("application/x-troff-man", "man");
+const TYPE_TROFF: Type = new_type("text/troff", "roff");
+const TYPE_ASCIIDOC: Type = new_type("text/asciidoc", "adoc");
+const TYPE_RST: Type = new_type("text/x-rst", "rst");
+const TYPE_MARKDOWN: Type = new_type("text/markdown", "md");
+const TYPE_SH: Type = new_type("text/x-shellscript", "sh");
+const TYPE_BASH: Type = new_type("text/x-shellscript", "bash");
+const TYPE_ZSH: Type = new_type("text/x-shellscript", "zsh");
+const TYPE_PYTHON: Type = new_type("text/x-python", "py");
+const TYPE_PERL: Type = new_type("text/x-perl", "pl");
+const TYPE_RUBY: Type = new_type("text/x-ruby", "rb");
+const TYPE_JAVASCRIPT: Type = new_type("text/javascript", "js");
+const TYPE_TYPESCRIPT: Type = new_type("text/x-typescript", "ts");
+const TYPE_PHP: Type = new_type("application/x-php", "php");
+const TYPE_LUA: Type = new_type("text/x-lua", "lua");
+const TYPE_R: Type = new_type("text/x-r", "r");
+const TYPE_AWK: Type = new_type("text/x-awk", "awk");
+const TYPE_RUST: Type = new_type("text/x-rust", "rs");
+const TYPE_C: Type = new_type("text/x-c", "c");
+const TYPE_CPP: Type = new_type("text/x-c++", "cpp");
+const TYPE_GO: Type = new_type("text/x-go", "go");
+const TYPE_JAVA: Type = new_type("text/x-java", "java");
+const TYPE_SQL: Type = new_type("application/sql", "sql");
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Content {
+    Data(Format),
+    Json(JsonDialect),
+    Config(ConfigDialect),
+    Markup(Markup),
+    Source(Language),
+}
+
+// The decoded text, and whether it is the whole file rather than a prefix.
+fn decode(buf: &[u8]) -> Option<(String, bool)> {
+    let info = text::detect(buf)?;
+    Some((info.decode(buf), buf.len() < NUM_SIGNATURE_BYTES))
+}
+
+fn content(buf: &[u8]) -> Option<Content> {
+    let (text, complete) = decode(buf)?;
+    classify(&text, complete)
+}
+
+// A shebang outranks everything, since a script may well consist of `KEY=value` lines.
+// Data formats with a grammar come next, then markup and source code, and delimited
+// values last since a line of prose may well contain a comma.
+fn classify(text: &str, complete: bool) -> Option<Content> {
+    if let Some(language) = source::shebang(text).and_then(|s| s.language()) {
+        return Some(Content::Source(language));
+    }
+    data::identify(text, complete)
+        .map(|format| match format {
+            Format::Json => data::json_dialect(text).map_or(Content::Data(format), Content::Json),
+            _ => match config_dialect(format, text, complete) {
+                Some(info) => Content::Config(info.dialect),
+                None => Content::Data(format),
+            },
+        })
+        .or_else(|| markup::identify(text).map(Content::Markup))
+        .or_else(|| source::identify(text).map(Content::Source))
+        .or_else(|| data::delimited(text, complete).map(Content::Data))
+}
+
+fn config_dialect(format: Format, text: &str, complete: bool) -> Option<ConfigInfo> {
+    match format {
+        Format::Yaml => data::yaml_dialect(text, complete),
+        Format::Toml => data::toml_dialect(text, complete),
+        _ => None,
+    }
+}
+
+fn format(buf: &[u8]) -> Option<Format> {
+    match content(buf)? {
+        Content::Data(format) => Some(format),
+        _ => None,
+    }
+}
+
+// The type of text content: the structured format, markup or language it holds, else
+// plain text in its encoding. The content is classified once and then mapped to a type.
+// Text has no magic number, so this is only consulted once every other category has
+// failed; see `matcher::match_all`.
+pub fn identify(buf: &[u8]) -> Type<'static> {
+    let info = match text::detect(buf) {
+        Some(info) => info,
+        None => return TYPE_UNKNOWN,
+    };
+    let text = info.decode(buf);
+    match classify(&text, buf.len() < NUM_SIGNATURE_BYTES) {
+        Some(content) => content_type(content),
+        None => encoding_type(info.encoding),
+    }
+}
+
+fn encoding_type(encoding: Encoding) -> Type<'static> {
+    match encoding {
+        Encoding::Ascii => TYPE_ASCII,
+        Encoding::Utf8 => TYPE_UTF8,
+        Encoding::Utf16Le => TYPE_UTF16LE,
+        Encoding::Utf16Be => TYPE_UTF16BE,
+        Encoding::Utf32Le => TYPE_UTF32LE,
+        Encoding::Utf32Be => TYPE_UTF32BE,
+        Encoding::Latin1 => TYPE_LATIN1,
+        Encoding::Windows1252 => TYPE_WINDOWS1252,
+    }
+}
+
+fn content_type(content: Content) -> Type<'static> {
+    match content {
+        Content::Data(format) => match format {
+            Format::Json => TYPE_JSON,
+            Format::Ndjson => TYPE_NDJSON,
+            Format::Toml => TYPE_TOML,
+            Format::Ini => TYPE_INI,
+            Format::Properties => TYPE_PROPERTIES,
+            Format::Yaml => TYPE_YAML,
+            Format::Csv => TYPE_CSV,
+            Format::Tsv => TYPE_TSV,
+        },
+        Content::Json(dialect) => match dialect {
+            JsonDialect::Jupyter => TYPE_IPYNB,
+            JsonDialect::GeoJson => TYPE_GEOJSON,
+            JsonDialect::TopoJson => TYPE_TOPOJSON,
+            JsonDialect::Gltf => TYPE_GLTF,
+            JsonDialect::Har => TYPE_HAR,
+            JsonDialect::JsonLd => TYPE_JSONLD,
+            JsonDialect::PackageJson => TYPE_PACKAGE_JSON,
+            JsonDialect::PackageLock => TYPE_PACKAGE_LOCK,
+            JsonDialect::Composer => TYPE_COMPOSER,
+            JsonDialect::TerraformState => TYPE_TFSTATE,
+            JsonDialect::OpenApi => TYPE_OPENAPI_JSON,
+            JsonDialect::JsonSchema => TYPE_JSON_SCHEMA,
+            JsonDialect::Lottie => TYPE_LOTTIE,
+            JsonDialect::WebManifest => TYPE_WEB_MANIFEST,
+        },
+        Content::Config(dialect) => match dialect {
+            ConfigDialect::Kubernetes => TYPE_KUBERNETES,
+            ConfigDialect::HelmChart => TYPE_HELM_CHART,
+            ConfigDialect::DockerCompose => TYPE_DOCKER_COMPOSE,
+            ConfigDialect::GithubActions => TYPE_GITHUB_ACTIONS,
+            ConfigDialect::GitlabCi => TYPE_GITLAB_CI,
+            ConfigDialect::AnsiblePlaybook => TYPE_ANSIBLE,
+            ConfigDialect::OpenApi => TYPE_OPENAPI_YAML,
+            ConfigDialect::CondaEnvironment => TYPE_CONDA,
+            ConfigDialect::CargoManifest => TYPE_CARGO_TOML,
+            ConfigDialect::CargoLock => TYPE_CARGO_LOCK,
+            ConfigDialect::Pyproject => TYPE_PYPROJECT,
+            ConfigDialect::PoetryLock => TYPE_POETRY_LOCK,
+            ConfigDialect::UvLock => TYPE_UV_LOCK,
+        },
+        Content::Markup(markup) => match markup {
+            Markup::Html => TYPE_HTML,
+            Markup::Xhtml => TYPE_XHTML,
+            Markup::Xml(None) => TYPE_XML,
+            Markup::Xml(Some(dialect)) => xml_dialect_type(dialect),
+            Markup::Svg => TYPE_SVG,
+            Markup::Latex => TYPE_LATEX,
+            Markup::Man => TYPE_MAN,
+            Markup::Troff => TYPE_TROFF,
+            Markup::AsciiDoc => TYPE_ASCIIDOC,
+            Markup::Rst => TYPE_RST,
+            Markup::Markdown => TYPE_MARKDOWN,
+        },
+        Content::Source(language) => match language {
+            Language::Sh => TYPE_SH,
+            Language::Bash => TYPE_BASH,
+            Language::Zsh => TYPE_ZSH,
+            Language::Python => TYPE_PYTHON,
+            Language::Perl => TYPE_PERL,
+            Language::Ruby => TYPE_RUBY,
+            Language::JavaScript => TYPE_JAVASCRIPT,
+            Language::TypeScript => TYPE_TYPESCRIPT,
+            Language::Php => TYPE_PHP,
+            Language::Lua => TYPE_LUA,
+            Language::R => TYPE_R,
+            Language::Awk => TYPE_AWK,
+            Language::Rust => TYPE_RUST,
+            Language::C => TYPE_C,
+            Language::Cpp => TYPE_CPP,
+            Language::Go => TYPE_GO,
+            Language::Java => TYPE_JAVA,
+            Language::Sql => TYPE_SQL,
+        },
+    }
+}
+
+fn xml_dialect_type(dialect: XmlDialect) -> Type<'static> {
+    match dialect {
+        XmlDialect::Rss => TYPE_RSS,
+        XmlDialect::Atom => TYPE_ATOM,
+        XmlDialect::Xslt => TYPE_XSLT,
+        XmlDialect::Xsd => TYPE_XSD,
+        XmlDialect::Wsdl => TYPE_WSDL,
+        XmlDialect::Soap => TYPE_SOAP,
+        XmlDialect::Plist => TYPE_PLIST,
+        XmlDialect::Gpx => TYPE_GPX,
+        XmlDialect::Kml => TYPE_KML,
+        XmlDialect::Collada => TYPE_COLLADA,
+        XmlDialect::MathMl => TYPE_MATHML,
+        XmlDialect::DocBook => TYPE_DOCBOOK,
+        XmlDialect::Xliff => TYPE_XLIFF,
+        XmlDialect::MusicXml => TYPE_MUSICXML,
+        XmlDialect::AndroidManifest => TYPE_ANDROID_MANIFEST,
+        XmlDialect::AndroidResource => TYPE_ANDROID_RESOURCE,
+        XmlDialect::MavenPom => TYPE_POM,
+        XmlDialect::MsBuild => TYPE_MSBUILD,
+        XmlDialect::FlatText => TYPE_FODT,
+        XmlDialect::FlatSpreadsheet => TYPE_FODS,
+        XmlDialect::FlatPresentation => TYPE_FODP,
+        XmlDialect::FlatDrawing => TYPE_FODG,
+        XmlDialect::WordMl => TYPE_WORDML,
+        XmlDialect::SpreadsheetMl => TYPE_SPREADSHEETML,
+        XmlDialect::Xfdf => TYPE_XFDF,
+    }
+}
+
+// Encoding, byte order mark and line endings; None for binary data.
+pub fn text_info(buf: &[u8]) -> Option<TextInfo> {
+    text::detect(buf)
+}
+
+// The tool a YAML or TOML file configures, with a description and, for Kubernetes
+// manifests, the kind of object.
+pub fn config_info(buf: &[u8]) -> Option<ConfigInfo> {
+    match content(buf)? {
+        Content::Config(_) => {
+            let (text, complete) = decode(buf)?;
+            config_dialect(data::identify(&text, complete)?, &text, complete)
+        }
+        _ => None,
+    }
+}
+
+// The delimiter and header row of CSV or TSV content.
+pub fn csv_info(buf: &[u8]) -> Option<CsvDialect> {
+    match format(buf)? {
+        Format::Csv | Format::Tsv => {
+            let (text, complete) = decode(buf)?;
+            data::csv_dialect(&text, complete)
+        }
+        _ => None,
+    }
+}
+
+// The interpreter named by a `#!` line, resolved through `env`.
+pub fn shebang_info(buf: &[u8]) -> Option<Shebang> {
+    let (text, _) = decode(buf)?;
+    source::shebang(&text)
+}
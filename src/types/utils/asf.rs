@@ -0,0 +1,147 @@
+// Advanced Systems Format: a Header Object whose children include one Stream Properties
+// Object per stream. WMA, WMV and DVR-MS are all ASF and differ only in those streams.
+// GUIDs are stored in their little-endian (Windows) byte order.
+// ref: Advanced Systems Format (ASF) Specification, revision 01.20.05, 3.1, 3.3 and 10
+
+use super::littleendian_bytes;
+
+const HEADER: [u8; 16] = [
+    0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE, 0x6C,
+];
+pub const FILE_PROPERTIES: [u8; 16] = [
+    0xA1, 0xDC, 0xAB, 0x8C, 0x47, 0xA9, 0xCF, 0x11, 0x8E, 0xE4, 0x00, 0xC0, 0x0C, 0x20, 0x53, 0x65,
+];
+pub const STREAM_PROPERTIES: [u8; 16] = [
+    0x91, 0x07, 0xDC, 0xB7, 0xB7, 0xA9, 0xCF, 0x11, 0x8E, 0xE6, 0x00, 0xC0, 0x0C, 0x20, 0x53, 0x65,
+];
+const EXTENDED_CONTENT_DESCRIPTION: [u8; 16] = [
+    0x40, 0xA4, 0xD0, 0xD2, 0x07, 0xE3, 0xD2, 0x11, 0x97, 0xF0, 0x00, 0xA0, 0xC9, 0x5E, 0xA8, 0x50,
+];
+pub const AUDIO_MEDIA: [u8; 16] = [
+    0x40, 0x9E, 0x69, 0xF8, 0x4D, 0x5B, 0xCF, 0x11, 0xA8, 0xFD, 0x00, 0x80, 0x5F, 0x5C, 0x44, 0x2B,
+];
+pub const VIDEO_MEDIA: [u8; 16] = [
+    0xC0, 0xEF, 0x19, 0xBC, 0x4D, 0x5B, 0xCF, 0x11, 0xA8, 0xFD, 0x00, 0x80, 0x5F, 0x5C, 0x44, 0x2B,
+];
+// DVR-MS keeps its audio and video in Binary Media streams carrying DirectShow types.
+const BINARY_MEDIA: [u8; 16] = [
+    0xE2, 0x65, 0xFB, 0x3A, 0xEF, 0x47, 0xF2, 0x40, 0xAC, 0x2C, 0x70, 0xA9, 0x0D, 0x71, 0xD3, 0x43,
+];
+
+// "WM/WMRV" in UTF-16LE: the prefix of the attributes written by Windows Media Center.
+const WMRV_ATTRIBUTE: [u8; 14] = [
+    b'W', 0, b'M', 0, b'/', 0, b'W', 0, b'M', 0, b'R', 0, b'V', 0,
+];
+
+// Objects inspected inside the Header Object.
+const MAX_OBJECTS: u32 = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    // Audio streams only.
+    Wma,
+    // At least one video stream.
+    Wmv,
+    DvrMs,
+    // No stream properties within the inspected bytes, or other stream types only.
+    Asf,
+}
+
+pub struct Object<'a> {
+    pub guid: [u8; 16],
+    // Truncated when the buffer is only a prefix of the file.
+    pub body: &'a [u8],
+}
+
+// The objects nested in the Header Object.
+pub fn header_objects(buf: &[u8]) -> Option<Vec<Object<'_>>> {
+    if buf.len() < 30 || buf[..16] != HEADER {
+        return None;
+    }
+
+    let count = littleendian_bytes(&buf[24..28]).min(MAX_OBJECTS);
+    let mut objects = vec![];
+    let mut offset = 30;
+    for _ in 0..count {
+        let object = match buf.get(offset..offset + 24) {
+            Some(o) => o,
+            None => break,
+        };
+        let size = u64::from_le_bytes(object[16..24].try_into().unwrap());
+        if size < 24 {
+            break;
+        }
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|s| offset.checked_add(s))
+            .unwrap_or(usize::MAX);
+        objects.push(Object {
+            guid: object[..16].try_into().unwrap(),
+            body: &buf[offset + 24..end.min(buf.len())],
+        });
+        offset = end;
+    }
+    Some(objects)
+}
+
+pub fn identify(buf: &[u8]) -> Option<Kind> {
+    let (mut audio, mut video, mut dvr) = (false, false, false);
+    for object in header_objects(buf)? {
+        if object.guid == STREAM_PROPERTIES {
+            match object.body.get(..16) {
+                Some(t) if *t == AUDIO_MEDIA => audio = true,
+                Some(t) if *t == VIDEO_MEDIA => video = true,
+                Some(t) if *t == BINARY_MEDIA => dvr = true,
+                _ => {}
+            }
+        } else if object.guid == EXTENDED_CONTENT_DESCRIPTION {
+            dvr |= object
+                .body
+                .windows(WMRV_ATTRIBUTE.len())
+                .any(|w| w == WMRV_ATTRIBUTE);
+        }
+    }
+
+    Some(if dvr {
+        Kind::DvrMs
+    } else if video {
+        Kind::Wmv
+    } else if audio {
+        Kind::Wma
+    } else {
+        Kind::Asf
+    })
+}
+
+#[cfg(test)]
+fn header(objects: &[&[u8]]) -> Vec<u8> {
+    let body: Vec<u8> = objects.concat();
+    let mut buf = HEADER.to_vec();
+    buf.extend_from_slice(&(30 + body.len() as u64).to_le_bytes());
+    buf.extend_from_slice(&(objects.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&[1, 2]);
+    buf.extend(body);
+    buf
+}
+
+#[cfg(test)]
+fn object(guid: [u8; 16], body: &[u8]) -> Vec<u8> {
+    let mut buf = guid.to_vec();
+    buf.extend_from_slice(&(24 + body.len() as u64).to_le_bytes());
+    buf.extend_from_slice(body);
+    buf
+}
+
+#[test]
+fn test_asf_streams() {
+    let audio = object(STREAM_PROPERTIES, &[&AUDIO_MEDIA[..], &[0; 38]].concat());
+    let video = object(STREAM_PROPERTIES, &[&VIDEO_MEDIA[..], &[0; 38]].concat());
+
+    assert_eq!(Some(Kind::Wma), identify(&header(&[&audio])));
+    assert_eq!(Some(Kind::Wmv), identify(&header(&[&audio, &video])));
+    assert_eq!(Some(Kind::Asf), identify(&header(&[])));
+
+    let attributes = object(EXTENDED_CONTENT_DESCRIPTION, &WMRV_ATTRIBUTE);
+    assert_eq!(Some(Kind::DvrMs), identify(&header(&[&attributes, &audio])));
+    assert_eq!(None, identify(&STREAM_PROPERTIES));
+}
@@ -0,0 +1,272 @@
+// RFC 6381 `codecs` values, built from the decoder configuration of each track: the
+// sample entries of MP4 and QuickTime, or the CodecID and CodecPrivate of Matroska.
+// ref: https://www.rfc-editor.org/rfc/rfc6381 (3.3, ISO BMFF)
+// ref: ISO/IEC 14496-15, Annex E (avc1, hvc1)
+// ref: https://aomediacodec.github.io/av1-isobmff/#codecsparam
+// ref: https://www.webmproject.org/vp9/mp4/#codecs-parameter-string
+// ref: https://developer.mozilla.org/en-US/docs/Web/Media/Formats/codecs_parameter
+use super::{ebml, image::is_iso_bmf, isobmff};
+
+// Bytes before the child boxes of a visual sample entry, and of an audio sample entry
+// (QuickTime sound descriptions 1 and 2 add 16 and 36).
+const VISUAL_ENTRY_LEN: usize = 78;
+const AUDIO_ENTRY_LEN: usize = 28;
+
+// The codecs of all tracks, in track order.
+pub fn parse(buf: &[u8]) -> Option<Vec<String>> {
+    let codecs = match buf.get(..4)? {
+        [0x1A, 0x45, 0xDF, 0xA3] => matroska(buf)?,
+        _ if is_iso_bmf(buf) || isobmff::find(buf, &[b"moov"]).is_some() => mp4(buf)?,
+        _ => return None,
+    };
+    (!codecs.is_empty()).then_some(codecs)
+}
+
+fn mp4(buf: &[u8]) -> Option<Vec<String>> {
+    let moov = isobmff::find(buf, &[b"moov"])?;
+    let codecs = moov
+        .children()
+        .iter()
+        .filter(|b| &b.kind == b"trak")
+        .filter_map(|trak| {
+            let mdia = isobmff::find(trak.data, &[b"mdia"])?;
+            let handler = isobmff::find(mdia.data, &[b"hdlr"])?.data.get(8..12)?;
+            let stsd = isobmff::find(mdia.data, &[b"minf", b"stbl", b"stsd"])?;
+            let entry = isobmff::boxes(stsd.data.get(8..)?).into_iter().next()?;
+            sample_entry(&entry, handler)
+        })
+        .collect();
+    Some(codecs)
+}
+
+fn sample_entry(entry: &isobmff::Mp4Box, handler: &[u8]) -> Option<String> {
+    let children_at = match handler {
+        b"vide" => VISUAL_ENTRY_LEN,
+        b"soun" => {
+            let version = entry.data.get(8..10)?;
+            AUDIO_ENTRY_LEN
+                + match u16::from_be_bytes([version[0], version[1]]) {
+                    1 => 16,
+                    2 => 36,
+                    _ => 0,
+                }
+        }
+        _ => return None,
+    };
+    let children = isobmff::boxes(entry.data.get(children_at..).unwrap_or_default());
+    let child = |kind: &[u8; 4]| children.iter().find(|c| &c.kind == kind).map(|c| c.data);
+
+    let kind = std::str::from_utf8(&entry.kind).ok()?;
+    match &entry.kind {
+        b"avc1" | b"avc3" => child(b"avcC").and_then(|c| avc(kind, c)),
+        b"hvc1" | b"hev1" => child(b"hvcC").and_then(|c| hevc(kind, c)),
+        b"av01" => child(b"av1C").and_then(av1),
+        b"vp08" | b"vp09" => child(b"vpcC").and_then(|c| vpx(kind, c.get(4..)?)),
+        b"mp4a" | b"mp4v" => child(b"esds").and_then(|c| mpeg4(kind, c)),
+        b"Opus" => Some("opus".to_string()),
+        b"fLaC" => Some("flac".to_string()),
+        b"ac-3" | b"ec-3" | b"ac-4" | b"alac" | b"mha1" | b"mhm1" => Some(kind.to_string()),
+        _ => None,
+    }
+    .or_else(|| Some(kind.trim_end().to_string()))
+}
+
+fn matroska(buf: &[u8]) -> Option<Vec<String>> {
+    let doc = ebml::parse(buf)?;
+    let codecs = doc
+        .tracks?
+        .iter()
+        .filter_map(|t| {
+            let private = t.codec_private.unwrap_or_default();
+            Some(match t.codec_id? {
+                "V_MPEG4/ISO/AVC" => avc("avc1", private)?,
+                "V_MPEGH/ISO/HEVC" => hevc("hvc1", private)?,
+                "V_AV1" => av1(private)?,
+                "V_VP8" => "vp8".to_string(),
+                "V_VP9" => vp9_features(private).unwrap_or_else(|| "vp9".to_string()),
+                "A_AAC" => aac(private).unwrap_or_else(|| "mp4a.40.2".to_string()),
+                id if id.starts_with("A_AAC/") => "mp4a.40.2".to_string(),
+                "A_OPUS" => "opus".to_string(),
+                "A_VORBIS" => "vorbis".to_string(),
+                "A_FLAC" => "flac".to_string(),
+                "A_AC3" => "ac-3".to_string(),
+                "A_EAC3" => "ec-3".to_string(),
+                "A_MPEG/L3" => "mp4a.6B".to_string(),
+                _ => return None,
+            })
+        })
+        .collect();
+    Some(codecs)
+}
+
+// `avc1.PPCCLL`: profile, constraint flags and level of the AVCDecoderConfigurationRecord.
+fn avc(kind: &str, config: &[u8]) -> Option<String> {
+    let c = config.get(..4)?;
+    Some(format!("{kind}.{:02X}{:02X}{:02X}", c[1], c[2], c[3]))
+}
+
+// `hvc1.[A-C]P.C.TL.B...`: profile space and profile, the bit-reversed compatibility
+// flags, tier and level, then the constraint bytes without trailing zeros.
+fn hevc(kind: &str, config: &[u8]) -> Option<String> {
+    let c = config.get(..13)?;
+    let space = ["", "A", "B", "C"][(c[1] >> 6) as usize];
+    let tier = if c[1] & 0x20 != 0 { 'H' } else { 'L' };
+    let compatibility = u32::from_be_bytes([c[2], c[3], c[4], c[5]]).reverse_bits();
+
+    let mut ret = format!(
+        "{kind}.{space}{}.{compatibility:X}.{tier}{}",
+        c[1] & 0x1F,
+        c[12]
+    );
+    let constraints = &c[6..12];
+    let len = constraints
+        .iter()
+        .rposition(|b| *b != 0)
+        .map_or(0, |i| i + 1);
+    for b in &constraints[..len] {
+        ret.push_str(&format!(".{b:X}"));
+    }
+    Some(ret)
+}
+
+// `av01.P.LLT.DD`: profile, level, tier and bit depth of the AV1CodecConfigurationRecord.
+fn av1(config: &[u8]) -> Option<String> {
+    let c = config.get(..3)?;
+    let profile = c[1] >> 5;
+    let tier = if c[2] & 0x80 != 0 { 'H' } else { 'M' };
+    let depth = match (c[2] & 0x40 != 0, c[2] & 0x20 != 0) {
+        (true, true) if profile == 2 => 12,
+        (true, _) => 10,
+        _ => 8,
+    };
+    Some(format!(
+        "av01.{profile}.{:02}{tier}.{depth:02}",
+        c[1] & 0x1F
+    ))
+}
+
+// `vp09.PP.LL.DD`: profile, level and bit depth of a VPCodecConfigurationRecord.
+fn vpx(kind: &str, config: &[u8]) -> Option<String> {
+    let c = config.get(..3)?;
+    Some(format!("{kind}.{:02}.{:02}.{:02}", c[0], c[1], c[2] >> 4))
+}
+
+// The profile (1), level (2) and bit depth (3) features of a VP9 CodecPrivate.
+fn vp9_features(private: &[u8]) -> Option<String> {
+    let mut features = [None; 3];
+    let mut rest = private;
+    while let [id, len, tail @ ..] = rest {
+        let value = tail.get(..*len as usize)?;
+        if let (1..=3, [v]) = (*id, value) {
+            features[*id as usize - 1] = Some(*v);
+        }
+        rest = &tail[*len as usize..];
+    }
+    match features {
+        [Some(profile), Some(level), Some(depth)] => {
+            Some(format!("vp09.{profile:02}.{level:02}.{depth:02}"))
+        }
+        _ => None,
+    }
+}
+
+// The audio object type of an AudioSpecificConfig, 31 escaping to six more bits.
+fn audio_object_type(config: &[u8]) -> Option<u8> {
+    match config.first()? >> 3 {
+        31 => Some(32 + ((config[0] & 0x07) << 3 | config.get(1)? >> 5)),
+        t => Some(t),
+    }
+}
+
+// `mp4a.40.N` for AAC in Matroska, which keeps the AudioSpecificConfig as CodecPrivate.
+fn aac(config: &[u8]) -> Option<String> {
+    Some(format!("mp4a.40.{}", audio_object_type(config)?))
+}
+
+// `mp4a.OO[.N]` and `mp4v.OO[.N]`: the object type indication in hexadecimal, then the
+// audio object type or the visual profile and level.
+fn mpeg4(kind: &str, esds: &[u8]) -> Option<String> {
+    let config = isobmff::decoder_config(esds)?;
+    let info = config.specific_info.unwrap_or_default();
+    let detail = match (kind, config.object_type) {
+        ("mp4a", 0x40) => audio_object_type(info).map(|t| t.to_string()),
+        // The profile_and_level_indication of the visual object sequence header.
+        ("mp4v", 0x20) => info
+            .windows(5)
+            .find(|w| w[..4] == [0, 0, 1, 0xB0])
+            .map(|w| w[4].to_string()),
+        _ => None,
+    };
+    Some(match detail {
+        Some(d) => format!("{kind}.{:02X}.{d}", config.object_type),
+        None => format!("{kind}.{:02X}", config.object_type),
+    })
+}
+
+#[test]
+fn test_codecs_configurations() {
+    // High profile, level 3.1.
+    assert_eq!(
+        Some("avc1.64001F".to_string()),
+        avc("avc1", &[1, 0x64, 0x00, 0x1F])
+    );
+    // Main profile, main tier, level 3.1, progressive source flag.
+    let hvcc = [1, 0x01, 0x60, 0, 0, 0, 0x90, 0, 0, 0, 0, 0, 93];
+    assert_eq!(Some("hvc1.1.6.L93.90".to_string()), hevc("hvc1", &hvcc));
+    // Main profile, level 4.0, main tier, 10 bits.
+    assert_eq!(Some("av01.0.08M.10".to_string()), av1(&[0x81, 0x08, 0x4C]));
+    assert_eq!(
+        Some("vp09.00.31.08".to_string()),
+        vpx("vp09", &[0, 31, 0x80])
+    );
+    assert_eq!(Some("mp4a.40.2".to_string()), aac(&[0x12, 0x10]));
+    // USAC, written with the escape value.
+    assert_eq!(Some("mp4a.40.42".to_string()), aac(&[0xF9, 0x40]));
+    assert_eq!(None, vp9_features(&[]));
+    assert_eq!(
+        Some("vp09.02.10.10".to_string()),
+        vp9_features(&[1, 1, 2, 2, 1, 10, 3, 1, 10])
+    );
+}
+
+#[test]
+fn test_codecs_mp4() {
+    let mk = |kind: &[u8], data: &[u8]| {
+        let mut b = ((data.len() + 8) as u32).to_be_bytes().to_vec();
+        b.extend_from_slice(kind);
+        b.extend_from_slice(data);
+        b
+    };
+    let track = |handler: &[u8], entry: Vec<u8>| {
+        let hdlr = mk(b"hdlr", &[&[0; 8][..], handler, &[0; 12]].concat());
+        let stsd = mk(b"stsd", &[&[0, 0, 0, 0, 0, 0, 0, 1][..], &entry].concat());
+        let stbl = mk(b"stbl", &stsd);
+        let minf = mk(b"minf", &stbl);
+        mk(b"trak", &mk(b"mdia", &[hdlr, minf].concat()))
+    };
+
+    let avc1 = mk(
+        b"avc1",
+        &[
+            &[0; VISUAL_ENTRY_LEN][..],
+            &mk(b"avcC", &[1, 0x42, 0xC0, 0x1E]),
+        ]
+        .concat(),
+    );
+    let mut esds = vec![0, 0, 0, 0, 0x03, 0x16, 0, 1, 0, 0x04, 0x11, 0x40, 0x15];
+    esds.extend_from_slice(&[0; 11]);
+    esds.extend_from_slice(&[0x05, 0x02, 0x12, 0x10]);
+    let mp4a = mk(
+        b"mp4a",
+        &[&[0; AUDIO_ENTRY_LEN][..], &mk(b"esds", &esds)].concat(),
+    );
+    let moov = mk(
+        b"moov",
+        &[track(b"vide", avc1), track(b"soun", mp4a)].concat(),
+    );
+
+    assert_eq!(
+        Some(vec!["avc1.42C01E".to_string(), "mp4a.40.2".to_string()]),
+        parse(&moov)
+    );
+}
@@ -13,11 +13,25 @@ pub fn littleendian_bytes(buf: &[u8]) -> u32 {
     Cursor::new(buf).read_u32::<LittleEndian>().unwrap()
 }
 
+// Integers at `offset`, or None when `buf` ends first.
+pub fn be16(buf: &[u8], offset: usize) -> Option<u16> {
+    buf.get(offset..)?.read_u16::<BigEndian>().ok()
+}
+
+pub fn be32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..)?.read_u32::<BigEndian>().ok()
+}
+
+pub fn le16(buf: &[u8], offset: usize) -> Option<u16> {
+    buf.get(offset..)?.read_u16::<LittleEndian>().ok()
+}
+
+pub fn le32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..)?.read_u32::<LittleEndian>().ok()
+}
+
 pub fn bytes_to_str(buf: &[u8]) -> &str {
-    match str::from_utf8(&buf) {
-        Ok(s) => s,
-        Err(_) => "",
-    }
+    str::from_utf8(buf).unwrap_or_default()
 }
 
 // try to implement bytes.Index in Go
@@ -49,3 +63,15 @@ fn test_bytes_index() {
     assert_eq!(2, bytes_index(b"abcab", b"cab"));
     assert_eq!(u32::MAX, bytes_index(b"abc", b"cd"));
 }
+
+#[test]
+fn test_read_ints() {
+    let buf = [0x12, 0x34, 0x56, 0x78];
+    assert_eq!(Some(0x3456), be16(&buf, 1));
+    assert_eq!(Some(0x7856), le16(&buf, 2));
+    assert_eq!(Some(0x12345678), be32(&buf, 0));
+    assert_eq!(Some(0x78563412), le32(&buf, 0));
+    assert_eq!(None, be16(&buf, 3));
+    assert_eq!(None, le32(&buf, 1));
+    assert_eq!(None, be32(&buf, usize::MAX));
+}
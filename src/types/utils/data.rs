@@ -0,0 +1,944 @@
+// Structured text data recognised from its content: JSON and JSON Lines, TOML, INI,
+// Java .properties, YAML and delimiter-separated values. Formats are tried from the
+// strictest grammar to the loosest, since plain `key: value` lines are also YAML and
+// most lines of text could be a one-column CSV. Delimited values are tried separately,
+// once the caller has ruled out other kinds of text such as markup.
+// `complete` tells whether `text` holds the whole file; when it does not, the last
+// line or an unterminated JSON value may have been cut off by the read.
+// ref: https://www.rfc-editor.org/rfc/rfc8259
+// ref: https://toml.io/en/v1.0.0
+// ref: https://github.com/python/cpython/blob/main/Lib/csv.py (Sniffer)
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Toml,
+    Ini,
+    Properties,
+    Yaml,
+    Csv,
+    Tsv,
+}
+
+// JSON documents with a well-known layout, told apart by their top-level members.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonDialect {
+    Jupyter,
+    GeoJson,
+    TopoJson,
+    Gltf,
+    Har,
+    JsonLd,
+    PackageJson,
+    PackageLock,
+    Composer,
+    TerraformState,
+    // OpenAPI 3 and its predecessor, Swagger 2.
+    OpenApi,
+    JsonSchema,
+    Lottie,
+    WebManifest,
+}
+
+// YAML and TOML configuration files of well-known tools, told apart by their keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigDialect {
+    Kubernetes,
+    HelmChart,
+    DockerCompose,
+    GithubActions,
+    GitlabCi,
+    AnsiblePlaybook,
+    OpenApi,
+    CondaEnvironment,
+    CargoManifest,
+    CargoLock,
+    Pyproject,
+    PoetryLock,
+    UvLock,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigInfo {
+    pub dialect: ConfigDialect,
+    // The `kind` of a Kubernetes object, such as `Deployment`.
+    pub kind: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvDialect {
+    pub delimiter: char,
+    // The first row names the columns rather than holding data.
+    pub has_header: bool,
+}
+
+// Lines and JSON nesting inspected; deeper or longer input is judged on its prefix.
+const MAX_LINES: usize = 64;
+const MAX_DEPTH: usize = 128;
+// Top-level members collected for the JSON dialects.
+const MAX_MEMBERS: usize = 64;
+
+const GEOJSON_TYPES: [&str; 9] = [
+    "Feature",
+    "FeatureCollection",
+    "Point",
+    "MultiPoint",
+    "LineString",
+    "MultiLineString",
+    "Polygon",
+    "MultiPolygon",
+    "GeometryCollection",
+];
+const GLTF_MEMBERS: [&str; 8] = [
+    "scene",
+    "scenes",
+    "nodes",
+    "meshes",
+    "accessors",
+    "buffers",
+    "bufferViews",
+    "materials",
+];
+
+const CSV_DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+pub fn identify(text: &str, complete: bool) -> Option<Format> {
+    if let Some(json) = json(text, complete) {
+        return Some(json);
+    }
+
+    let lines = lines(text, complete);
+    if is_toml(&lines) {
+        Some(Format::Toml)
+    } else if is_ini(&lines) {
+        Some(Format::Ini)
+    } else if is_properties(&lines) {
+        Some(Format::Properties)
+    } else if is_yaml(&lines) {
+        Some(Format::Yaml)
+    } else {
+        None
+    }
+}
+
+// CSV or TSV, by the delimiter of a consistent table.
+pub fn delimited(text: &str, complete: bool) -> Option<Format> {
+    match csv_dialect(text, complete)?.delimiter {
+        '\t' => Some(Format::Tsv),
+        _ => Some(Format::Csv),
+    }
+}
+
+impl ConfigDialect {
+    pub fn description(&self) -> &'static str {
+        match self {
+            ConfigDialect::Kubernetes => "Kubernetes manifest",
+            ConfigDialect::HelmChart => "Helm chart definition",
+            ConfigDialect::DockerCompose => "Docker Compose file",
+            ConfigDialect::GithubActions => "GitHub Actions workflow",
+            ConfigDialect::GitlabCi => "GitLab CI configuration",
+            ConfigDialect::AnsiblePlaybook => "Ansible playbook",
+            ConfigDialect::OpenApi => "OpenAPI document",
+            ConfigDialect::CondaEnvironment => "Conda environment file",
+            ConfigDialect::CargoManifest => "Cargo manifest",
+            ConfigDialect::CargoLock => "Cargo lock file",
+            ConfigDialect::Pyproject => "Python project metadata",
+            ConfigDialect::PoetryLock => "Poetry lock file",
+            ConfigDialect::UvLock => "uv lock file",
+        }
+    }
+}
+
+// The first lines of `text`, without a last line that may have been cut off.
+fn lines(text: &str, complete: bool) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    if !complete && !text.ends_with('\n') {
+        lines.pop();
+    }
+    lines.truncate(MAX_LINES);
+    lines
+}
+
+// Lines that carry content: neither blank nor comments starting with one of `comments`.
+fn meaningful<'a>(lines: &[&'a str], comments: &[char]) -> Vec<&'a str> {
+    lines
+        .iter()
+        .copied()
+        .filter(|l| {
+            let t = l.trim();
+            !t.is_empty() && !t.starts_with(comments)
+        })
+        .collect()
+}
+
+enum Parse {
+    // The value ends at this byte offset.
+    Done(usize),
+    // The text ended inside the value.
+    Eof,
+    Invalid,
+}
+
+fn json(text: &str, complete: bool) -> Option<Format> {
+    let start = text.len() - text.trim_start().len();
+    if !text[start..].starts_with(['{', '[']) {
+        return None;
+    }
+
+    let end = match json_value(text.as_bytes(), start, 0) {
+        Parse::Done(end) => end,
+        // A large document cut off by the read.
+        Parse::Eof if !complete => return Some(Format::Json),
+        _ => return None,
+    };
+    let rest = &text[end..];
+    if rest.trim().is_empty() {
+        return Some(Format::Json);
+    }
+
+    // JSON Lines: one object or array per line.
+    if text[start..end].contains('\n')
+        || !rest.trim_start_matches([' ', '\t', '\r']).starts_with('\n')
+    {
+        return None;
+    }
+    let lines = lines(text, complete);
+    let values = meaningful(&lines, &[]);
+    let all_values = values.iter().all(|line| {
+        let line = line.trim();
+        line.starts_with(['{', '['])
+            && matches!(json_value(line.as_bytes(), 0, 0), Parse::Done(end) if end == line.len())
+    });
+    (values.len() >= 2 && all_values).then_some(Format::Ndjson)
+}
+
+// The dialect of a JSON object, from the members found before the end of `text`. Only
+// the top level is collected; nested values are skipped by the validating parser, so a
+// large document costs no more than the prefix that was read.
+pub fn json_dialect(text: &str) -> Option<JsonDialect> {
+    let members = json_members(text);
+    let has = |key: &str| members.iter().any(|(k, _)| k == key);
+    let string = |key: &str| {
+        members
+            .iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, v)| v.as_deref())
+    };
+
+    let dialect = if has("nbformat") || (has("cells") && text.contains("\"cell_type\"")) {
+        JsonDialect::Jupyter
+    } else if string("type") == Some("Topology") {
+        JsonDialect::TopoJson
+    } else if string("type").is_some_and(|t| GEOJSON_TYPES.contains(&t))
+        && ["features", "geometry", "coordinates", "geometries"]
+            .iter()
+            .any(|k| has(k))
+    {
+        JsonDialect::GeoJson
+    } else if has("asset") && GLTF_MEMBERS.iter().any(|k| has(k)) {
+        JsonDialect::Gltf
+    } else if members.len() == 1 && has("log") && text.contains("\"creator\"") {
+        JsonDialect::Har
+    } else if has("terraform_version") {
+        JsonDialect::TerraformState
+    } else if has("lockfileVersion") {
+        JsonDialect::PackageLock
+    } else if ["require", "require-dev", "autoload"]
+        .iter()
+        .any(|k| has(k))
+    {
+        JsonDialect::Composer
+    } else if string("openapi").is_some_and(|v| v.starts_with("3."))
+        || string("swagger") == Some("2.0")
+    {
+        JsonDialect::OpenApi
+    } else if ["fr", "ip", "op", "layers"].iter().all(|k| has(k)) {
+        JsonDialect::Lottie
+    } else if has("start_url") || (has("short_name") && (has("icons") || has("display"))) {
+        JsonDialect::WebManifest
+    } else if has("name")
+        && ["dependencies", "devDependencies", "scripts", "main", "bin"]
+            .iter()
+            .any(|k| has(k))
+    {
+        JsonDialect::PackageJson
+    } else if has("@context") {
+        JsonDialect::JsonLd
+    } else if string("$schema").is_some_and(|s| s.contains("json-schema.org/")) {
+        JsonDialect::JsonSchema
+    } else {
+        return None;
+    };
+    Some(dialect)
+}
+
+// The keys of the top-level object, each with its value when that is a string.
+fn json_members(text: &str) -> Vec<(String, Option<String>)> {
+    let buf = text.as_bytes();
+    let mut members = vec![];
+    let mut offset = skip_ws(buf, 0);
+    if buf.get(offset) != Some(&b'{') {
+        return members;
+    }
+    offset += 1;
+
+    while members.len() < MAX_MEMBERS {
+        offset = skip_ws(buf, offset);
+        if buf.get(offset) != Some(&b'"') {
+            break;
+        }
+        let key_end = match json_string(buf, offset + 1) {
+            Parse::Done(end) => end,
+            _ => break,
+        };
+        let key = text[offset + 1..key_end - 1].to_string();
+        offset = skip_ws(buf, key_end);
+        if buf.get(offset) != Some(&b':') {
+            break;
+        }
+        offset = skip_ws(buf, offset + 1);
+
+        let value_end = match json_value(buf, offset, 1) {
+            Parse::Done(end) => end,
+            _ => {
+                // The value runs past the prefix; the key is still known.
+                members.push((key, None));
+                break;
+            }
+        };
+        let value = (buf[offset] == b'"').then(|| text[offset + 1..value_end - 1].to_string());
+        members.push((key, value));
+
+        offset = skip_ws(buf, value_end);
+        if buf.get(offset) != Some(&b',') {
+            break;
+        }
+        offset += 1;
+    }
+    members
+}
+
+fn json_value(buf: &[u8], offset: usize, depth: usize) -> Parse {
+    let offset = skip_ws(buf, offset);
+    if depth > MAX_DEPTH {
+        return Parse::Invalid;
+    }
+    match buf.get(offset) {
+        None => Parse::Eof,
+        Some(b'{') => json_container(buf, offset + 1, depth, b'}', true),
+        Some(b'[') => json_container(buf, offset + 1, depth, b']', false),
+        Some(b'"') => json_string(buf, offset + 1),
+        Some(b't') => json_literal(buf, offset, b"true"),
+        Some(b'f') => json_literal(buf, offset, b"false"),
+        Some(b'n') => json_literal(buf, offset, b"null"),
+        Some(b'-' | b'0'..=b'9') => json_number(buf, offset),
+        _ => Parse::Invalid,
+    }
+}
+
+fn json_container(buf: &[u8], offset: usize, depth: usize, close: u8, object: bool) -> Parse {
+    let mut offset = skip_ws(buf, offset);
+    if buf.get(offset) == Some(&close) {
+        return Parse::Done(offset + 1);
+    }
+
+    loop {
+        if object {
+            offset = skip_ws(buf, offset);
+            match buf.get(offset) {
+                None => return Parse::Eof,
+                Some(b'"') => {}
+                _ => return Parse::Invalid,
+            }
+            offset = match json_string(buf, offset + 1) {
+                Parse::Done(end) => skip_ws(buf, end),
+                other => return other,
+            };
+            match buf.get(offset) {
+                None => return Parse::Eof,
+                Some(b':') => offset += 1,
+                _ => return Parse::Invalid,
+            }
+        }
+
+        offset = match json_value(buf, offset, depth + 1) {
+            Parse::Done(end) => skip_ws(buf, end),
+            other => return other,
+        };
+        match buf.get(offset) {
+            None => return Parse::Eof,
+            Some(b',') => offset += 1,
+            Some(c) if *c == close => return Parse::Done(offset + 1),
+            _ => return Parse::Invalid,
+        }
+    }
+}
+
+// `offset` is just past the opening quote.
+fn json_string(buf: &[u8], mut offset: usize) -> Parse {
+    while let Some(c) = buf.get(offset) {
+        match c {
+            b'"' => return Parse::Done(offset + 1),
+            b'\\' => offset += 2,
+            0..=0x1F => return Parse::Invalid,
+            _ => offset += 1,
+        }
+    }
+    Parse::Eof
+}
+
+fn json_literal(buf: &[u8], offset: usize, literal: &[u8]) -> Parse {
+    let rest = &buf[offset..];
+    if rest.starts_with(literal) {
+        Parse::Done(offset + literal.len())
+    } else if literal.starts_with(rest) {
+        Parse::Eof
+    } else {
+        Parse::Invalid
+    }
+}
+
+fn json_number(buf: &[u8], offset: usize) -> Parse {
+    let end = buf[offset..]
+        .iter()
+        .position(|c| !matches!(c, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+        .map_or(buf.len(), |n| offset + n);
+    if end == buf.len() {
+        return Parse::Eof;
+    }
+    match std::str::from_utf8(&buf[offset..end]).map(str::parse::<f64>) {
+        Ok(Ok(_)) => Parse::Done(end),
+        _ => Parse::Invalid,
+    }
+}
+
+fn skip_ws(buf: &[u8], offset: usize) -> usize {
+    buf[offset.min(buf.len())..]
+        .iter()
+        .position(|c| !matches!(c, b' ' | b'\t' | b'\r' | b'\n'))
+        .map_or(buf.len(), |n| offset + n)
+}
+
+// `[name]` or, for TOML, `[[name]]`.
+fn is_section(line: &str) -> bool {
+    let line = line.trim();
+    line.len() > 2 && line.starts_with('[') && line.ends_with(']') && !line.contains(['=', '"'])
+}
+
+// `key = value` with a key free of spaces (or quoted, for TOML).
+fn assignment(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    let bare = key
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    let quoted = key.len() > 1 && key.starts_with('"') && key.ends_with('"');
+    (!key.is_empty() && (bare || quoted)).then_some((key, value.trim()))
+}
+
+// A TOML value at the start of `value`: a string, number, boolean, date, array or
+// inline table, optionally followed by a comment.
+fn is_toml_value(value: &str) -> bool {
+    let scalar = value.split(" #").next().unwrap_or_default().trim();
+    if scalar.starts_with(['"', '\'', '[', '{']) {
+        return true;
+    }
+    matches!(scalar, "true" | "false" | "inf" | "nan" | "+inf" | "-inf")
+        || scalar.replace('_', "").parse::<f64>().is_ok()
+        || ["0x", "0o", "0b"].iter().any(|p| scalar.starts_with(p))
+        || is_toml_date(scalar)
+}
+
+fn is_toml_date(value: &str) -> bool {
+    let b = value.as_bytes();
+    b.len() >= 8
+        && ((b[4] == b'-' && b[7] == b'-' && b[..4].iter().all(u8::is_ascii_digit))
+            || (b[2] == b':' && b[5] == b':' && b[..2].iter().all(u8::is_ascii_digit)))
+}
+
+fn is_toml(lines: &[&str]) -> bool {
+    let lines = meaningful(lines, &['#']);
+    let mut assignments = 0;
+    let mut in_array = 0_i32;
+    for line in &lines {
+        // Lines inside a multi-line array.
+        if in_array > 0 {
+            in_array += line.matches('[').count() as i32 - line.matches(']').count() as i32;
+            continue;
+        }
+        if is_section(line) {
+            continue;
+        }
+        match assignment(line) {
+            Some((_, value)) if is_toml_value(value) => {
+                assignments += 1;
+                if value.starts_with('[') {
+                    in_array =
+                        value.matches('[').count() as i32 - value.matches(']').count() as i32;
+                }
+            }
+            _ => return false,
+        }
+    }
+    assignments > 0
+}
+
+fn is_ini(lines: &[&str]) -> bool {
+    let lines = meaningful(lines, &[';', '#']);
+    let sections = lines.iter().filter(|l| is_section(l)).count();
+    let assignments = lines.iter().filter(|l| l.contains('=')).count();
+    sections > 0
+        && assignments > 0
+        && lines.first().is_some_and(|l| is_section(l))
+        && lines
+            .iter()
+            .all(|l| is_section(l) || l.split_once('=').is_some_and(|(k, _)| !k.trim().is_empty()))
+}
+
+// `key=value` or `key: value` pairs without sections; a trailing backslash continues
+// the value on the next line. At least one `=` is required to tell it from YAML.
+fn is_properties(lines: &[&str]) -> bool {
+    let lines = meaningful(lines, &['#', '!']);
+    let mut continued = false;
+    let mut pairs = 0;
+    let mut equals = 0;
+    for line in &lines {
+        let was_continued = continued;
+        continued = line.ends_with('\\');
+        if was_continued {
+            continue;
+        }
+        let line = line.trim_start();
+        let sep = match line.find(['=', ':']) {
+            Some(i) => i,
+            None => return false,
+        };
+        let key = line[..sep].trim_end();
+        if key.is_empty() || key.contains(char::is_whitespace) || key.contains(['[', ']', ',']) {
+            return false;
+        }
+        if line.as_bytes()[sep] == b'=' {
+            equals += 1;
+        }
+        pairs += 1;
+    }
+    pairs >= 2 && equals > 0
+}
+
+// A block of `key: value` mappings and `- item` sequences, optionally between document
+// markers. Every line must fit, so Markdown front matter followed by prose is not YAML.
+fn is_yaml(lines: &[&str]) -> bool {
+    let lines = meaningful(lines, &['#']);
+    let mut marker = false;
+    let mut mappings = 0;
+    let mut items = 0;
+    for line in &lines {
+        let indented = line.starts_with([' ', '\t']);
+        let t = line.trim();
+        if t.starts_with("%YAML") || t == "---" || t.starts_with("--- ") || t == "..." {
+            marker = true;
+            continue;
+        }
+        if t.starts_with("- ") || t == "-" {
+            items += 1;
+            continue;
+        }
+        match yaml_key(t) {
+            Some(_) => mappings += 1,
+            // Continuation of a multi-line scalar.
+            None if indented => {}
+            None => return false,
+        }
+    }
+    mappings >= 2 || (mappings == 1 && lines.len() > 1) || (marker && mappings + items > 0)
+}
+
+// The key of a `key: value` or `key:` line.
+fn yaml_key(line: &str) -> Option<&str> {
+    let (key, rest) = line.split_once(':')?;
+    let key = key.trim_end();
+    let plain = !key.is_empty()
+        && !key.starts_with(['{', '[', '"', '\''])
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ' | '/'));
+    let quoted = key.len() > 1
+        && ((key.starts_with('"') && key.ends_with('"'))
+            || (key.starts_with('\'') && key.ends_with('\'')));
+    ((plain || quoted) && (rest.is_empty() || rest.starts_with(' '))).then_some(key)
+}
+
+// The dialect of a YAML document, from its top-level keys. Only the first document of
+// a stream is inspected, and a top-level sequence is judged by the keys of its items.
+pub fn yaml_dialect(text: &str, complete: bool) -> Option<ConfigInfo> {
+    let lines = lines(text, complete);
+    let lines = meaningful(&lines, &['#']);
+    let mut keys: Vec<(&str, &str)> = vec![];
+    let mut item_keys: Vec<&str> = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let t = line.trim_end();
+        if t.starts_with("%YAML") || t == "---" || t == "..." {
+            if i > 0 && (!keys.is_empty() || !item_keys.is_empty()) {
+                break;
+            }
+            continue;
+        }
+        if let Some(item) = t.strip_prefix("- ") {
+            item_keys.extend(yaml_key(item));
+        } else if let Some(nested) = t.strip_prefix("  ") {
+            if !nested.starts_with(' ') && !item_keys.is_empty() {
+                item_keys.extend(yaml_key(nested));
+            }
+        } else if let Some(key) = yaml_key(t) {
+            let value = t[key.len() + 1..].trim().trim_matches(['"', '\'']);
+            keys.push((key, value));
+        }
+    }
+
+    let has = |key: &str| keys.iter().any(|(k, _)| *k == key);
+    let value = |key: &str| keys.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+    let indented = |key: &str| lines.iter().any(|l| l.starts_with(' ') && l.trim() == key);
+
+    let dialect = if has("apiVersion") && value("kind").is_some_and(|k| !k.is_empty()) {
+        return Some(ConfigInfo {
+            dialect: ConfigDialect::Kubernetes,
+            kind: value("kind").map(str::to_string),
+        });
+    } else if matches!(value("apiVersion"), Some("v1" | "v2")) && has("name") && has("version") {
+        ConfigDialect::HelmChart
+    } else if value("openapi").is_some_and(|v| v.starts_with("3."))
+        || value("swagger") == Some("2.0")
+    {
+        ConfigDialect::OpenApi
+    } else if has("jobs") && (has("on") || has("\"on\"")) {
+        ConfigDialect::GithubActions
+    } else if has("services") && !has("jobs") && !has("stages") {
+        ConfigDialect::DockerCompose
+    } else if has("stages") || (indented("script:") && !has("jobs")) {
+        ConfigDialect::GitlabCi
+    } else if item_keys.contains(&"hosts") || item_keys.contains(&"import_playbook") {
+        ConfigDialect::AnsiblePlaybook
+    } else if has("dependencies")
+        && keys
+            .iter()
+            .all(|(k, _)| ["name", "channels", "dependencies", "prefix", "variables"].contains(k))
+    {
+        ConfigDialect::CondaEnvironment
+    } else {
+        return None;
+    };
+    Some(ConfigInfo {
+        dialect,
+        kind: None,
+    })
+}
+
+// The dialect of a TOML document, from its table headers and top-level keys. Headers
+// of arrays of tables (`[[package]]`) are kept apart from those of tables (`[package]`).
+pub fn toml_dialect(text: &str, complete: bool) -> Option<ConfigInfo> {
+    let lines = lines(text, complete);
+    let mut tables: Vec<&str> = vec![];
+    let mut arrays: Vec<&str> = vec![];
+    let mut top: Vec<&str> = vec![];
+    for line in meaningful(&lines, &['#']) {
+        let t = line.trim();
+        if !is_section(t) {
+            if tables.is_empty() && arrays.is_empty() {
+                top.extend(assignment(t).map(|(key, _)| key));
+            }
+        } else if let Some(name) = t.strip_prefix("[[").and_then(|t| t.strip_suffix("]]")) {
+            arrays.push(name.trim());
+        } else {
+            tables.push(t.trim_matches(['[', ']']).trim());
+        }
+    }
+
+    let table = |name: &str| tables.contains(&name);
+    let array = |name: &str| arrays.contains(&name);
+    let generated_by = |tool: &str| {
+        lines
+            .iter()
+            .take(4)
+            .any(|l| l.starts_with('#') && l.contains(&format!("@generated by {}", tool)))
+    };
+
+    let dialect = if generated_by("Cargo") {
+        ConfigDialect::CargoLock
+    } else if generated_by("Poetry") || (array("package") && table("metadata")) {
+        ConfigDialect::PoetryLock
+    } else if array("package") && top.contains(&"requires-python") {
+        ConfigDialect::UvLock
+    } else if array("package") && top.contains(&"version") {
+        ConfigDialect::CargoLock
+    } else if table("project")
+        || table("build-system")
+        || tables.iter().any(|t| t.starts_with("tool."))
+    {
+        ConfigDialect::Pyproject
+    } else if table("package") || table("workspace") {
+        ConfigDialect::CargoManifest
+    } else {
+        return None;
+    };
+    Some(ConfigInfo {
+        dialect,
+        kind: None,
+    })
+}
+
+// The delimiter that splits every row into the same number of fields, and whether the
+// first row looks like a header: a column whose values share a type (number) or a
+// length that the first cell does not have votes for a header. Two rows of two fields
+// are common in prose ("Hello, world"), so such a table also needs a column of numbers
+// under a text header.
+pub fn csv_dialect(text: &str, complete: bool) -> Option<CsvDialect> {
+    let lines = lines(text, complete);
+    let rows: Vec<&str> = lines.into_iter().filter(|l| !l.trim().is_empty()).collect();
+    if rows.len() < 2 {
+        return None;
+    }
+
+    let delimiter = CSV_DELIMITERS.into_iter().find(|d| {
+        let counts: Vec<usize> = rows.iter().map(|r| split_row(r, *d).len()).collect();
+        counts[0] > 1 && counts.iter().all(|c| *c == counts[0])
+    })?;
+
+    let table: Vec<Vec<String>> = rows.iter().map(|r| split_row(r, delimiter)).collect();
+    let mut votes = 0_i32;
+    let mut typed = false;
+    for column in 0..table[0].len() {
+        let header = &table[0][column];
+        let values: Vec<&String> = table[1..].iter().map(|r| &r[column]).collect();
+        let numeric = |v: &String| v.trim().parse::<f64>().is_ok();
+        if values.iter().all(|v| numeric(v)) {
+            if numeric(header) {
+                votes -= 1;
+            } else {
+                votes += 1;
+                typed = true;
+            }
+        } else if values.iter().all(|v| v.len() == values[0].len()) {
+            votes += if header.len() == values[0].len() {
+                -1
+            } else {
+                1
+            };
+        }
+    }
+    if rows.len() < 3 && table[0].len() < 3 && !typed {
+        return None;
+    }
+
+    Some(CsvDialect {
+        delimiter,
+        has_header: votes > 0,
+    })
+}
+
+// Fields of a row, honouring double-quoted fields.
+fn split_row(row: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+#[test]
+fn test_data_json() {
+    assert_eq!(
+        Some(Format::Json),
+        identify(
+            "{\"a\": [1, 2.5e3, true, null], \"b\": {\"c\": \"\\\"\"}}\n",
+            true
+        )
+    );
+    assert_eq!(
+        Some(Format::Ndjson),
+        identify("{\"id\": 1}\n{\"id\": 2}\n[3]\n", true)
+    );
+    // Cut off by the read, or malformed.
+    assert_eq!(Some(Format::Json), identify("[{\"a\": \"long", false));
+    assert_eq!(None, json("[{\"a\": \"long", true));
+    assert_eq!(None, json("{\"a\" 1}", true));
+    assert_eq!(None, json("{\"a\": 1} trailing", true));
+}
+
+#[test]
+fn test_data_json_dialects() {
+    assert_eq!(
+        Some(JsonDialect::Jupyter),
+        json_dialect("{\"cells\": [{\"cell_type\": \"code\", \"source\": [\"print(1)\"")
+    );
+    assert_eq!(
+        Some(JsonDialect::GeoJson),
+        json_dialect("{\"type\": \"FeatureCollection\", \"features\": []}")
+    );
+    assert_eq!(
+        Some(JsonDialect::Gltf),
+        json_dialect("{\"asset\": {\"version\": \"2.0\"}, \"scenes\": [{\"nodes\": [0]}]}")
+    );
+    assert_eq!(
+        Some(JsonDialect::OpenApi),
+        json_dialect("{\"openapi\": \"3.1.0\", \"info\": {\"title\": \"x\"}}")
+    );
+    assert_eq!(
+        Some(JsonDialect::PackageJson),
+        json_dialect("{\"name\": \"demo\", \"version\": \"1.0.0\", \"scripts\": {}}")
+    );
+    assert_eq!(
+        Some(JsonDialect::PackageLock),
+        json_dialect("{\"name\": \"demo\", \"lockfileVersion\": 3}")
+    );
+    assert_eq!(
+        Some(JsonDialect::JsonSchema),
+        json_dialect(
+            "{\"$schema\": \"https://json-schema.org/draft/2020-12/schema\", \"type\": \"object\"}"
+        )
+    );
+    assert_eq!(
+        Some(JsonDialect::Lottie),
+        json_dialect("{\"v\": \"5.7.4\", \"fr\": 30, \"ip\": 0, \"op\": 60, \"w\": 512, \"h\": 512, \"layers\": []}")
+    );
+    assert_eq!(None, json_dialect("{\"type\": \"Point\"}"));
+    assert_eq!(None, json_dialect("[{\"name\": \"x\", \"version\": 1}]"));
+
+    let members = json_members("{\"a\": {\"b\": [1, 2]}, \"c\": \"d\", \"e\": [\"cut");
+    assert_eq!(
+        vec![
+            ("a".to_string(), None),
+            ("c".to_string(), Some("d".to_string())),
+            ("e".to_string(), None)
+        ],
+        members
+    );
+}
+
+#[test]
+fn test_data_config() {
+    let toml = "# comment\ntitle = \"TOML\"\n\n[owner]\nname = 'Tom'\ndob = 1979-05-27T07:32:00Z\nports = [\n  8000,\n  8001,\n]\n";
+    assert_eq!(Some(Format::Toml), identify(toml, true));
+
+    let ini = "; comment\n[section]\nkey=value with spaces\npath = C:\\Windows\n";
+    assert_eq!(Some(Format::Ini), identify(ini, true));
+
+    let properties = "# comment\napp.name=Demo\napp.greeting: Hello \\\n    world\n";
+    assert_eq!(Some(Format::Properties), identify(properties, true));
+
+    let yaml = "name: demo\nitems:\n  - one\n  - two\nnested:\n  key: value\n";
+    assert_eq!(Some(Format::Yaml), identify(yaml, true));
+    assert_eq!(Some(Format::Yaml), identify("---\n- one\n- two\n", true));
+    assert_eq!(
+        None,
+        identify("---\ntitle: Post\n---\nHello there.\n", true)
+    );
+
+    assert_eq!(
+        None,
+        identify("Just some prose.\nNothing structured here.\n", true)
+    );
+}
+
+#[test]
+fn test_data_config_dialects() {
+    let yaml = |text: &str| yaml_dialect(text, true).map(|info| info.dialect);
+    let deployment = yaml_dialect(
+        "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: web\n---\nkind: Service\n",
+        true,
+    )
+    .unwrap();
+    assert_eq!(ConfigDialect::Kubernetes, deployment.dialect);
+    assert_eq!(Some("Deployment".to_string()), deployment.kind);
+
+    assert_eq!(
+        Some(ConfigDialect::HelmChart),
+        yaml("apiVersion: v2\nname: web\nversion: 0.1.0\ntype: application\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::DockerCompose),
+        yaml("services:\n  web:\n    image: nginx\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::GithubActions),
+        yaml("name: CI\non: [push]\njobs:\n  test:\n    runs-on: ubuntu-latest\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::GitlabCi),
+        yaml("stages:\n  - test\ntest:\n  stage: test\n  script:\n    - make\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::AnsiblePlaybook),
+        yaml("---\n- name: Configure\n  hosts: all\n  tasks:\n    - ping:\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::CondaEnvironment),
+        yaml("name: env\nchannels:\n  - conda-forge\ndependencies:\n  - numpy\n")
+    );
+    assert_eq!(None, yaml("name: demo\nitems:\n  - one\n"));
+
+    let toml = |text: &str| toml_dialect(text, true).map(|info| info.dialect);
+    assert_eq!(
+        Some(ConfigDialect::CargoManifest),
+        toml("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::CargoLock),
+        toml("# This file is automatically @generated by Cargo.\n# It is not intended for manual editing.\nversion = 4\n\n[[package]]\nname = \"demo\"\n")
+    );
+    // Without the comment, and with only path and git dependencies.
+    assert_eq!(
+        Some(ConfigDialect::CargoLock),
+        toml("version = 3\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[[package]]\nname = \"dep\"\nsource = \"git+https://example.com/dep#0123abc\"\n")
+    );
+    assert_eq!(None, toml("[[package]]\nname = \"demo\"\n"));
+    assert_eq!(
+        Some(ConfigDialect::Pyproject),
+        toml("[build-system]\nrequires = [\"hatchling\"]\n\n[project]\nname = \"demo\"\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::PoetryLock),
+        toml("[[package]]\nname = \"six\"\n\n[metadata]\nlock-version = \"2.0\"\n")
+    );
+    assert_eq!(
+        Some(ConfigDialect::UvLock),
+        toml("version = 1\nrequires-python = \">=3.12\"\n\n[[package]]\nname = \"six\"\n")
+    );
+    assert_eq!(None, toml("title = \"x\"\n[owner]\nname = \"y\"\n"));
+}
+
+#[test]
+fn test_data_csv() {
+    let csv = "name,age,city\n\"Smith, J\",42,Paris\nDoe,7,Rome\n";
+    assert_eq!(Some(Format::Csv), delimited(csv, true));
+    assert_eq!(
+        Some(CsvDialect {
+            delimiter: ',',
+            has_header: true
+        }),
+        csv_dialect(csv, true)
+    );
+
+    let tsv = "1\t2\t3\n4\t5\t6\n";
+    assert_eq!(Some(Format::Tsv), delimited(tsv, true));
+    assert!(!csv_dialect(tsv, true).unwrap().has_header);
+
+    assert_eq!(';', csv_dialect("a;b\n1;2\n", true).unwrap().delimiter);
+    assert_eq!(None, csv_dialect("a,b\n1,2,3\n", true));
+    assert_eq!(None, delimited("Hello, world\nGoodbye, world\n", true));
+    assert_eq!(None, delimited("Hi, world\nGoodbye, planet\n", true));
+    assert_eq!(
+        Some(Format::Csv),
+        delimited("Hello, world\nGoodbye, world\nSee you, world\n", true)
+    );
+}
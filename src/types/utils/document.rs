@@ -126,5 +126,5 @@ pub fn check_odf(buf: &[u8], mimetype: &str) -> bool {
         return false;
     }
     // Finally check the file name and contents
-    return buf[30..38] == *b"mimetype" && buf[38..38 + mimetype.len()] == *mimetype.as_bytes();
+    buf[30..38] == *b"mimetype" && buf[38..38 + mimetype.len()] == *mimetype.as_bytes()
 }
@@ -0,0 +1,336 @@
+// Matroska and WebM are EBML documents: an EBML header element naming the DocType,
+// followed by a Segment whose Tracks element tells what kind of streams it carries.
+// ref: https://www.rfc-editor.org/rfc/rfc8794 (EBML)
+// ref: https://www.rfc-editor.org/rfc/rfc9559 (Matroska)
+use super::bytes_to_str;
+use std::time::Duration;
+
+const ID_EBML: u32 = 0x1A45DFA3;
+const ID_EBML_READ_VERSION: u32 = 0x42F7;
+const ID_DOC_TYPE: u32 = 0x4282;
+const ID_DOC_TYPE_VERSION: u32 = 0x4287;
+const ID_DOC_TYPE_READ_VERSION: u32 = 0x4285;
+const ID_SEGMENT: u32 = 0x18538067;
+const ID_INFO: u32 = 0x1549A966;
+const ID_TIMESTAMP_SCALE: u32 = 0x2AD7B1;
+const ID_DURATION: u32 = 0x4489;
+const ID_TRACKS: u32 = 0x1654AE6B;
+const ID_TRACK_ENTRY: u32 = 0xAE;
+const ID_TRACK_TYPE: u32 = 0x83;
+const ID_CODEC_ID: u32 = 0x86;
+const ID_CODEC_PRIVATE: u32 = 0x63A2;
+const ID_LANGUAGE: u32 = 0x22B59C;
+const ID_LANGUAGE_BCP47: u32 = 0x22B59D;
+const ID_DEFAULT_DURATION: u32 = 0x23E383;
+const ID_VIDEO: u32 = 0xE0;
+const ID_PIXEL_WIDTH: u32 = 0xB0;
+const ID_PIXEL_HEIGHT: u32 = 0xBA;
+const ID_STEREO_MODE: u32 = 0x53B8;
+
+// Nanoseconds per Duration unit unless TimestampScale says otherwise.
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+
+pub const TRACK_TYPE_VIDEO: u64 = 1;
+pub const TRACK_TYPE_AUDIO: u64 = 2;
+pub const TRACK_TYPE_SUBTITLE: u64 = 0x11;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Video,
+    // Video with a non-mono StereoMode, i.e. `.mk3d`.
+    Stereo3d,
+    Audio,
+    Subtitles,
+    // Tracks were not found within the buffer.
+    Unknown,
+}
+
+pub struct Track<'a> {
+    pub track_type: u64,
+    pub stereo_mode: u64,
+    pub codec_id: Option<&'a str>,
+    // Codec setup data, e.g. an `avcC` record for V_MPEG4/ISO/AVC.
+    pub codec_private: Option<&'a [u8]>,
+    // BCP 47 if given, otherwise ISO 639-2, which defaults to English.
+    pub language: &'a str,
+    // Nanoseconds per frame.
+    pub default_duration: Option<u64>,
+    pub pixel_width: Option<u64>,
+    pub pixel_height: Option<u64>,
+}
+
+pub struct Document<'a> {
+    pub doc_type: &'a str,
+    pub duration: Option<Duration>,
+    pub tracks: Option<Vec<Track<'a>>>,
+}
+
+struct Element<'a> {
+    id: u32,
+    data: &'a [u8],
+}
+
+impl Document<'_> {
+    pub fn variant(&self) -> Variant {
+        let tracks = match &self.tracks {
+            Some(tracks) if !tracks.is_empty() => tracks,
+            _ => return Variant::Unknown,
+        };
+
+        let has = |t| tracks.iter().any(|track| track.track_type == t);
+        if has(TRACK_TYPE_VIDEO) {
+            let stereo = tracks
+                .iter()
+                .any(|t| t.track_type == TRACK_TYPE_VIDEO && t.stereo_mode != 0);
+            if stereo {
+                Variant::Stereo3d
+            } else {
+                Variant::Video
+            }
+        } else if has(TRACK_TYPE_AUDIO) {
+            Variant::Audio
+        } else if has(TRACK_TYPE_SUBTITLE) {
+            Variant::Subtitles
+        } else {
+            Variant::Unknown
+        }
+    }
+}
+
+pub fn parse(buf: &[u8]) -> Option<Document<'_>> {
+    let top = elements(buf);
+    let header = top.first().filter(|e| e.id == ID_EBML)?;
+    let fields = elements(header.data);
+    let find = |id| fields.iter().find(|e| e.id == id).map(|e| e.data);
+
+    // Readers must refuse anything newer than EBML version 1.
+    if find(ID_EBML_READ_VERSION).map_or(1, read_uint) > 1 {
+        return None;
+    }
+
+    let doc_type = bytes_to_str(find(ID_DOC_TYPE)?).trim_end_matches('\0');
+    if doc_type.is_empty() || !doc_type.bytes().all(|b| b.is_ascii_graphic()) {
+        return None;
+    }
+
+    let version = find(ID_DOC_TYPE_VERSION).map_or(1, read_uint);
+    let read_version = find(ID_DOC_TYPE_READ_VERSION).map_or(1, read_uint);
+    if version == 0 || read_version > version {
+        return None;
+    }
+
+    let segment = top
+        .iter()
+        .find(|e| e.id == ID_SEGMENT)
+        .map_or(vec![], |segment| elements(segment.data));
+    let tracks = segment
+        .iter()
+        .find(|e| e.id == ID_TRACKS)
+        .map(|tracks| parse_tracks(tracks.data));
+    let duration = segment
+        .iter()
+        .find(|e| e.id == ID_INFO)
+        .and_then(|info| parse_duration(info.data));
+
+    Some(Document {
+        doc_type,
+        duration,
+        tracks,
+    })
+}
+
+pub fn identify(buf: &[u8]) -> Option<(&str, Variant)> {
+    parse(buf).map(|doc| (doc.doc_type, doc.variant()))
+}
+
+// The Duration of the Info element, a float in units of TimestampScale nanoseconds.
+fn parse_duration(buf: &[u8]) -> Option<Duration> {
+    let fields = elements(buf);
+    let find = |id| fields.iter().find(|e| e.id == id).map(|e| e.data);
+    let scale = find(ID_TIMESTAMP_SCALE).map_or(DEFAULT_TIMESTAMP_SCALE, read_uint);
+    let duration = match find(ID_DURATION)? {
+        d if d.len() == 4 => f32::from_be_bytes(d.try_into().ok()?) as f64,
+        d if d.len() == 8 => f64::from_be_bytes(d.try_into().ok()?),
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(duration * scale as f64 / 1e9).ok()
+}
+
+fn parse_tracks(buf: &[u8]) -> Vec<Track<'_>> {
+    elements(buf)
+        .into_iter()
+        .filter(|e| e.id == ID_TRACK_ENTRY)
+        .map(|entry| {
+            let fields = elements(entry.data);
+            let video = fields
+                .iter()
+                .find(|e| e.id == ID_VIDEO)
+                .map_or(vec![], |video| elements(video.data));
+            let string = |id| find(&fields, id).map(|d| bytes_to_str(d).trim_end_matches('\0'));
+
+            Track {
+                track_type: find(&fields, ID_TRACK_TYPE).map_or(0, read_uint),
+                stereo_mode: find(&video, ID_STEREO_MODE).map_or(0, read_uint),
+                codec_id: string(ID_CODEC_ID),
+                codec_private: find(&fields, ID_CODEC_PRIVATE),
+                language: string(ID_LANGUAGE_BCP47)
+                    .or(string(ID_LANGUAGE))
+                    .unwrap_or("eng"),
+                default_duration: find(&fields, ID_DEFAULT_DURATION).map(read_uint),
+                pixel_width: find(&video, ID_PIXEL_WIDTH).map(read_uint),
+                pixel_height: find(&video, ID_PIXEL_HEIGHT).map(read_uint),
+            }
+        })
+        .collect()
+}
+
+fn find<'a>(elements: &[Element<'a>], id: u32) -> Option<&'a [u8]> {
+    elements.iter().find(|e| e.id == id).map(|e| e.data)
+}
+
+// Split `buf` into consecutive elements, stopping at the first malformed one.
+// Elements of unknown size, or running past the buffer, are cut at its end.
+fn elements(buf: &[u8]) -> Vec<Element<'_>> {
+    let mut ret = vec![];
+    let mut offset = 0;
+
+    while offset < buf.len() {
+        let (id, id_len) = match read_vint(&buf[offset..], true) {
+            Some(v) if v.1 <= 4 => v,
+            _ => break,
+        };
+        let (size, size_len) = match read_vint(&buf[offset + id_len..], false) {
+            Some(v) => v,
+            None => break,
+        };
+
+        let start = offset + id_len + size_len;
+        let unknown_size = size == (1_u64 << (7 * size_len)) - 1;
+        let end = if unknown_size {
+            buf.len()
+        } else {
+            start.saturating_add(size as usize).min(buf.len())
+        };
+
+        ret.push(Element {
+            id: id as u32,
+            data: &buf[start..end],
+        });
+        offset = end;
+    }
+
+    ret
+}
+
+// Read a variable-length integer; element IDs keep their length marker.
+fn read_vint(buf: &[u8], keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *buf.first()?;
+    if first == 0 {
+        return None;
+    }
+
+    let len = first.leading_zeros() as usize + 1;
+    if buf.len() < len {
+        return None;
+    }
+
+    let mut value = if keep_marker {
+        first as u64
+    } else {
+        first as u64 & (0xFF >> len)
+    };
+    for b in &buf[1..len] {
+        value = value << 8 | *b as u64;
+    }
+
+    Some((value, len))
+}
+
+fn read_uint(buf: &[u8]) -> u64 {
+    buf.iter().take(8).fold(0, |acc, b| acc << 8 | *b as u64)
+}
+
+#[cfg(test)]
+fn element(id: u32, data: &[u8]) -> Vec<u8> {
+    let mut ret: Vec<u8> = id
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect();
+    ret.push(0x01);
+    ret.extend_from_slice(&(data.len() as u64).to_be_bytes()[1..]);
+    ret.extend_from_slice(data);
+    ret
+}
+
+#[cfg(test)]
+fn document(doc_type: &str, tracks: &[Vec<u8>]) -> Vec<u8> {
+    let mut header = element(ID_EBML_READ_VERSION, &[1]);
+    header.extend(element(ID_DOC_TYPE, doc_type.as_bytes()));
+    header.extend(element(ID_DOC_TYPE_VERSION, &[4]));
+    header.extend(element(ID_DOC_TYPE_READ_VERSION, &[2]));
+
+    let mut ret = element(ID_EBML, &header);
+    if !tracks.is_empty() {
+        let entries: Vec<u8> = tracks
+            .iter()
+            .flat_map(|t| element(ID_TRACK_ENTRY, t))
+            .collect();
+        ret.extend(element(ID_SEGMENT, &element(ID_TRACKS, &entries)));
+    }
+    ret
+}
+
+#[test]
+fn test_ebml_doc_type() {
+    let doc = document("webm", &[]);
+    let parsed = parse(&doc).unwrap();
+    assert_eq!("webm", parsed.doc_type);
+    assert_eq!(Variant::Unknown, parsed.variant());
+
+    // A DocType string alone is not an EBML header.
+    let mut fake = vec![0x1A, 0x45, 0xDF, 0xA3, 0x42, 0x82];
+    fake.extend_from_slice(b"\x88matroska");
+    assert!(parse(&fake).is_none());
+    assert!(parse(b"matroska").is_none());
+}
+
+#[test]
+fn test_ebml_tracks() {
+    let video = element(ID_TRACK_TYPE, &[1]);
+    let audio = element(ID_TRACK_TYPE, &[2]);
+    let subtitle = element(ID_TRACK_TYPE, &[0x11]);
+    let mut stereo = video.clone();
+    stereo.extend(element(ID_VIDEO, &element(ID_STEREO_MODE, &[1])));
+
+    let variant = |tracks: &[Vec<u8>]| parse(&document("matroska", tracks)).unwrap().variant();
+    assert_eq!(Variant::Video, variant(&[audio.clone(), video]));
+    assert_eq!(Variant::Audio, variant(&[audio.clone(), subtitle.clone()]));
+    assert_eq!(Variant::Subtitles, variant(&[subtitle]));
+    assert_eq!(Variant::Stereo3d, variant(&[stereo, audio]));
+}
+
+#[test]
+fn test_ebml_info() {
+    let mut track = element(ID_TRACK_TYPE, &[1]);
+    track.extend(element(ID_CODEC_ID, b"V_VP9"));
+    track.extend(element(ID_LANGUAGE, b"fre"));
+    track.extend(element(ID_DEFAULT_DURATION, &40_000_000_u32.to_be_bytes()));
+    track.extend(element(ID_VIDEO, &element(ID_PIXEL_WIDTH, &[0x07, 0x80])));
+    let tracks = element(ID_TRACKS, &element(ID_TRACK_ENTRY, &track));
+
+    // 2500 units of the default 1 ms scale.
+    let info = element(ID_INFO, &element(ID_DURATION, &2500.0_f64.to_be_bytes()));
+    let mut doc = document("webm", &[]);
+    doc.extend(element(ID_SEGMENT, &[info, tracks].concat()));
+
+    let parsed = parse(&doc).unwrap();
+    assert_eq!(Some(Duration::from_millis(2500)), parsed.duration);
+    let track = &parsed.tracks.as_ref().unwrap()[0];
+    assert_eq!(Some("V_VP9"), track.codec_id);
+    assert_eq!("fre", track.language);
+    assert_eq!(
+        (Some(40_000_000), Some(1920)),
+        (track.default_duration, track.pixel_width)
+    );
+}
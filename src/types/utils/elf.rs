@@ -0,0 +1,338 @@
+// ELF header analysis: `e_ident` gives class, data encoding and OS ABI, `e_type` and
+// the program headers tell executables, PIEs and shared objects apart.
+// ref: https://refspecs.linuxfoundation.org/elf/gabi4+/ch4.eheader.html
+// ref: https://refspecs.linuxfoundation.org/elf/gabi4+/ch5.pheader.html
+
+const ET_REL: u16 = 1;
+const ET_EXEC: u16 = 2;
+const ET_DYN: u16 = 3;
+const ET_CORE: u16 = 4;
+
+const PT_DYNAMIC: u32 = 2;
+const PT_INTERP: u32 = 3;
+
+const DT_NULL: u64 = 0;
+const DT_FLAGS_1: u64 = 0x6FFFFFFB;
+const DF_1_PIE: u64 = 0x08000000;
+
+// Tables larger than these are treated as corrupt.
+const MAX_PHNUM: usize = 256;
+const MAX_DYN_ENTRIES: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElfKind {
+    Executable,
+    // Position-independent executable; `e_type` is ET_DYN like a shared object.
+    Pie,
+    SharedObject,
+    Relocatable,
+    Core,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElfInfo {
+    pub kind: ElfKind,
+    // 32 or 64.
+    pub class: u8,
+    pub big_endian: bool,
+    // `e_ident[EI_OSABI]`.
+    pub os_abi: u8,
+    // `e_machine`.
+    pub machine: u16,
+    // Path from the PT_INTERP segment, e.g. `/lib64/ld-linux-x86-64.so.2`.
+    pub interpreter: Option<String>,
+    // Needs the dynamic linker: has an interpreter or is a shared object.
+    pub dynamic: bool,
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    big_endian: bool,
+    is_64: bool,
+}
+
+struct ProgramHeader {
+    p_type: u32,
+    offset: u64,
+    filesz: u64,
+}
+
+impl ElfInfo {
+    pub fn machine_name(&self) -> &'static str {
+        match self.machine {
+            0x02 => "sparc",
+            0x03 => "x86",
+            0x08 => "mips",
+            0x14 => "powerpc",
+            0x15 => "powerpc64",
+            0x16 => "s390",
+            0x28 => "arm",
+            0x2B => "sparcv9",
+            0x32 => "ia64",
+            0x3E => "x86-64",
+            0xB7 => "aarch64",
+            0xF3 => "riscv",
+            0xF7 => "bpf",
+            0x102 => "loongarch",
+            _ => "unknown",
+        }
+    }
+
+    pub fn os_abi_name(&self) -> &'static str {
+        match self.os_abi {
+            0 => "sysv",
+            1 => "hpux",
+            2 => "netbsd",
+            3 => "linux",
+            6 => "solaris",
+            7 => "aix",
+            8 => "irix",
+            9 => "freebsd",
+            12 => "openbsd",
+            97 => "arm",
+            255 => "standalone",
+            _ => "unknown",
+        }
+    }
+}
+
+impl Reader<'_> {
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let b = self.buf.get(offset..offset.checked_add(2)?)?;
+        let b = [b[0], b[1]];
+        Some(if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let b = self.buf.get(offset..offset.checked_add(4)?)?;
+        let b = [b[0], b[1], b[2], b[3]];
+        Some(if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+
+    fn u64(&self, offset: usize) -> Option<u64> {
+        let next = offset.checked_add(4)?;
+        let (hi, lo) = if self.big_endian {
+            (self.u32(offset)?, self.u32(next)?)
+        } else {
+            (self.u32(next)?, self.u32(offset)?)
+        };
+        Some((hi as u64) << 32 | lo as u64)
+    }
+
+    // A native-width word: 4 bytes for ELFCLASS32, 8 for ELFCLASS64.
+    fn word(&self, offset: usize) -> Option<u64> {
+        if self.is_64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    fn program_headers(&self) -> Vec<ProgramHeader> {
+        let (phoff, phentsize, phnum) = if self.is_64 {
+            (self.u64(32), self.u16(54), self.u16(56))
+        } else {
+            (self.u32(28).map(u64::from), self.u16(42), self.u16(44))
+        };
+        let (phoff, phentsize, phnum) = match (phoff, phentsize, phnum) {
+            (Some(o), Some(s), Some(n)) if s > 0 && (n as usize) <= MAX_PHNUM => {
+                (o as usize, s as usize, n as usize)
+            }
+            _ => return vec![],
+        };
+
+        (0..phnum)
+            .map_while(|i| {
+                let at = phoff.checked_add(i.checked_mul(phentsize)?)?;
+                let field = |n: usize| at.checked_add(n);
+                let (offset, filesz) = if self.is_64 {
+                    (self.u64(field(8)?)?, self.u64(field(32)?)?)
+                } else {
+                    (self.u32(field(4)?)? as u64, self.u32(field(16)?)? as u64)
+                };
+                Some(ProgramHeader {
+                    p_type: self.u32(at)?,
+                    offset,
+                    filesz,
+                })
+            })
+            .collect()
+    }
+
+    fn segment(&self, ph: &ProgramHeader) -> Option<&[u8]> {
+        let start = usize::try_from(ph.offset).ok()?;
+        let end = start.checked_add(usize::try_from(ph.filesz).ok()?)?;
+        self.buf.get(start..end)
+    }
+
+    // Whether the dynamic section sets DF_1_PIE in DT_FLAGS_1.
+    fn has_pie_flag(&self, dynamic: &ProgramHeader) -> bool {
+        let start = match usize::try_from(dynamic.offset) {
+            Ok(start) => start,
+            Err(_) => return false,
+        };
+        let entsize = if self.is_64 { 16 } else { 8 };
+        let count = (dynamic.filesz as usize / entsize).min(MAX_DYN_ENTRIES);
+
+        for i in 0..count {
+            let entry = start
+                .checked_add(i * entsize)
+                .and_then(|at| Some((self.word(at)?, self.word(at.checked_add(entsize / 2)?)?)));
+            let (tag, value) = match entry {
+                Some(entry) => entry,
+                None => return false,
+            };
+            match tag {
+                DT_NULL => return false,
+                DT_FLAGS_1 => return value & DF_1_PIE != 0,
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+pub fn parse(buf: &[u8]) -> Option<ElfInfo> {
+    if buf.len() < 52 || buf[..4] != [0x7F, b'E', b'L', b'F'] || buf[6] != 1 {
+        return None;
+    }
+
+    let is_64 = match buf[4] {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let big_endian = match buf[5] {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let reader = Reader {
+        buf,
+        big_endian,
+        is_64,
+    };
+
+    let e_type = reader.u16(16)?;
+    let machine = reader.u16(18)?;
+    let headers = match e_type {
+        ET_EXEC | ET_DYN => reader.program_headers(),
+        _ => vec![],
+    };
+
+    let interpreter = headers
+        .iter()
+        .find(|ph| ph.p_type == PT_INTERP)
+        .and_then(|ph| reader.segment(ph))
+        .map(|b| {
+            let end = b.iter().position(|c| *c == 0).unwrap_or(b.len());
+            String::from_utf8_lossy(&b[..end]).into_owned()
+        });
+
+    let kind = match e_type {
+        ET_REL => ElfKind::Relocatable,
+        ET_EXEC => ElfKind::Executable,
+        ET_CORE => ElfKind::Core,
+        ET_DYN => {
+            // DF_1_PIE is authoritative; without it (or when the dynamic section lies past
+            // the buffer) fall back to the interpreter, which shared objects do not request.
+            let pie_flag = headers
+                .iter()
+                .find(|ph| ph.p_type == PT_DYNAMIC)
+                .is_some_and(|ph| reader.has_pie_flag(ph));
+            if pie_flag || interpreter.is_some() {
+                ElfKind::Pie
+            } else {
+                ElfKind::SharedObject
+            }
+        }
+        _ => ElfKind::Unknown,
+    };
+
+    Some(ElfInfo {
+        kind,
+        class: if is_64 { 64 } else { 32 },
+        big_endian,
+        os_abi: buf[7],
+        machine,
+        dynamic: interpreter.is_some() || kind == ElfKind::SharedObject,
+        interpreter,
+    })
+}
+
+#[cfg(test)]
+fn build_elf64(e_type: u16, interp: Option<&[u8]>) -> Vec<u8> {
+    let mut buf = vec![0_u8; 64];
+    buf[..8].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 3]);
+    buf[16..18].copy_from_slice(&e_type.to_le_bytes());
+    buf[18..20].copy_from_slice(&0x3E_u16.to_le_bytes());
+
+    if let Some(interp) = interp {
+        buf[32..40].copy_from_slice(&64_u64.to_le_bytes());
+        buf[54..56].copy_from_slice(&56_u16.to_le_bytes());
+        buf[56..58].copy_from_slice(&1_u16.to_le_bytes());
+
+        let mut ph = [0_u8; 56];
+        ph[..4].copy_from_slice(&PT_INTERP.to_le_bytes());
+        ph[8..16].copy_from_slice(&120_u64.to_le_bytes());
+        ph[32..40].copy_from_slice(&(interp.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&ph);
+        buf.extend_from_slice(interp);
+    }
+    buf
+}
+
+#[test]
+fn test_elf_kinds() {
+    let pie = parse(&build_elf64(ET_DYN, Some(b"/lib64/ld-linux-x86-64.so.2\0"))).unwrap();
+    assert_eq!(ElfKind::Pie, pie.kind);
+    assert_eq!(64, pie.class);
+    assert!(!pie.big_endian);
+    assert_eq!("x86-64", pie.machine_name());
+    assert_eq!("linux", pie.os_abi_name());
+    assert_eq!(
+        Some("/lib64/ld-linux-x86-64.so.2"),
+        pie.interpreter.as_deref()
+    );
+    assert!(pie.dynamic);
+
+    let so = parse(&build_elf64(ET_DYN, None)).unwrap();
+    assert_eq!(ElfKind::SharedObject, so.kind);
+    assert!(so.dynamic);
+
+    let exe = parse(&build_elf64(ET_EXEC, None)).unwrap();
+    assert_eq!(ElfKind::Executable, exe.kind);
+    assert!(!exe.dynamic);
+
+    assert_eq!(
+        ElfKind::Core,
+        parse(&build_elf64(ET_CORE, None)).unwrap().kind
+    );
+    assert_eq!(
+        ElfKind::Relocatable,
+        parse(&build_elf64(ET_REL, None)).unwrap().kind
+    );
+
+    let mut bad = build_elf64(ET_EXEC, None);
+    bad[4] = 3;
+    assert!(parse(&bad).is_none());
+
+    // Program header and dynamic section offsets at the end of the address space.
+    let mut far = build_elf64(ET_DYN, Some(b"/lib/ld.so\0"));
+    far[32..40].copy_from_slice(&(u64::MAX - 3).to_le_bytes());
+    assert_eq!(ElfKind::SharedObject, parse(&far).unwrap().kind);
+    let mut far = build_elf64(ET_DYN, Some(b"/lib/ld.so\0"));
+    far[64..68].copy_from_slice(&PT_DYNAMIC.to_le_bytes());
+    far[72..80].copy_from_slice(&(u64::MAX - 3).to_le_bytes());
+    far[96..104].copy_from_slice(&32_u64.to_le_bytes());
+    assert_eq!(ElfKind::SharedObject, parse(&far).unwrap().kind);
+}
@@ -0,0 +1,554 @@
+// Font containers and the OpenType tables naming and classifying a font: `name` for the
+// family and subfamily, `OS/2` for the weight, the outline tables and `fvar` for
+// variation axes.
+// ref: OpenType 1.9.1, "The OpenType font file" (table directory, collections), "name",
+//      "OS/2", "fvar"
+// ref: Apple TrueType Reference Manual, ch. 6, "Font files" (`true` and `typ1` versions)
+// ref: https://www.w3.org/TR/WOFF/ (3, 4 and 5); https://www.w3.org/TR/WOFF2/ (4 and 5)
+// ref: Adobe Type 1 Font Format, 2.1 and 7.2 (PFB segments); Adobe TN 5005 (BDF 2.1)
+// ref: Inside Macintosh: More Macintosh Toolbox, 1-121 (resource file format)
+// ref: https://fontforge.org/docs/techref/pcf-format.html
+use super::{be16, be32, bytes_index, compare_bytes, gzip::inflate_zlib_prefix, le32};
+use std::borrow::Cow;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outlines {
+    // Quadratic outlines in `glyf`.
+    TrueType,
+    // PostScript outlines in `CFF `, or in `CFF2` for variable fonts.
+    Cff,
+    Cff2,
+    // PostScript Type 1 programs, on their own or in a Mac `typ1` sfnt.
+    Type1,
+    // Bitmaps only: BDF, PCF and sfnts with `EBDT`, `CBDT`, `bdat` or `sbix` strikes.
+    Bitmap,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FontInfo {
+    // The typographic family and subfamily (name IDs 16 and 17) when present, otherwise
+    // the legacy ones (1 and 2), e.g. "Source Sans 3" and "Semibold Italic".
+    pub family: Option<String>,
+    pub subfamily: Option<String>,
+    // `usWeightClass`, 100 (thin) to 900 (black).
+    pub weight: Option<u16>,
+    pub outlines: Option<Outlines>,
+    // An `fvar` table, with its axes such as "wght" and "wdth".
+    pub variable: bool,
+    pub axes: Vec<String>,
+    // The fonts of a collection or a resource file, described by the first; 1 otherwise.
+    pub fonts: usize,
+}
+
+const MAX_TABLES: usize = 256;
+const MAX_TABLE_BYTES: usize = 1 << 20;
+const MAX_COLLECTION_FONTS: usize = 1 << 12;
+// A Mac resource file holds its data from offset 256, after the header and room for
+// application data.
+const RESOURCE_DATA_OFFSET: usize = 0x100;
+const FONT_RESOURCES: [&[u8; 4]; 5] = [b"sfnt", b"FOND", b"NFNT", b"FONT", b"POST"];
+// The sfnt versions: TrueType (1.0 and Apple's `true`), CFF (`OTTO`) and Apple's
+// sfnt-wrapped Type 1 (`typ1`).
+const SFNT_VERSIONS: [&[u8; 4]; 4] = [b"\x00\x01\x00\x00", b"true", b"OTTO", b"typ1"];
+// The tables read by `describe`; the others only count by their tags.
+const DESCRIBED_TABLES: [&[u8; 4]; 3] = [b"name", b"OS/2", b"fvar"];
+
+// Indices 0 to 62 of the WOFF2 known table tags; 63 means the tag follows.
+const WOFF2_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+pub fn is_sfnt_version(buf: &[u8], version: &[u8; 4]) -> bool {
+    compare_bytes(buf, version, 0) && sfnt_tags(buf).is_some()
+}
+
+// The tags of a table directory at the start of `buf`. Tags are printable ASCII, and
+// TrueType and CFF fonts have a `head` table, or `bhed` when bitmap-only.
+fn sfnt_tags(buf: &[u8]) -> Option<Vec<[u8; 4]>> {
+    let n = be16(buf, 4)? as usize;
+    let records = buf.get(12..12 + 16 * n)?;
+    if n == 0 || n > MAX_TABLES {
+        return None;
+    }
+    let tags: Vec<[u8; 4]> = records
+        .chunks_exact(16)
+        .map(|r| [r[0], r[1], r[2], r[3]])
+        .collect();
+    if !tags.iter().flatten().all(|b| (0x20..=0x7E).contains(b)) {
+        return None;
+    }
+    let needs_head = !buf.starts_with(b"typ1");
+    (!needs_head || tags.iter().any(|t| t == b"head" || t == b"bhed")).then_some(tags)
+}
+
+// `ttcf`, version 1.0 or 2.0, and at least one font.
+pub fn is_collection(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"ttcf", 0)
+        && matches!(buf.get(4..8), Some([0, 1 | 2, 0, 0]))
+        && be32(buf, 8).is_some_and(|n| (1..=MAX_COLLECTION_FONTS).contains(&(n as usize)))
+}
+
+// WOFF and WOFF2 wrap TrueType or CFF fonts; only WOFF2 wraps collections.
+pub fn is_woff(buf: &[u8], signature: &[u8; 4]) -> bool {
+    let flavors: &[&[u8; 4]] = match signature {
+        b"wOF2" => &[b"\x00\x01\x00\x00", b"OTTO", b"true", b"ttcf"],
+        _ => &[b"\x00\x01\x00\x00", b"OTTO", b"true"],
+    };
+    compare_bytes(buf, signature, 0) && flavors.iter().any(|f| compare_bytes(buf, *f, 4))
+}
+
+// A Type 1 font program: `%!PS-AdobeFont-1.x` or `%!FontType1-1.x`.
+pub fn is_type1(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"%!PS-AdobeFont-1", 0) || compare_bytes(buf, b"%!FontType1-1", 0)
+}
+
+// A PFB file: segments with a 6-byte header, the first holding the cleartext program.
+pub fn is_pfb(buf: &[u8]) -> bool {
+    compare_bytes(buf, &[0x80, 0x01], 0) && buf.len() > 6 && is_type1(&buf[6..])
+}
+
+// A resource file with font resources: a dfont, or a font suitcase whose resource fork
+// was saved as a file. The map is at the end, so a file larger than the inspected bytes
+// is accepted when its first resource is an sfnt.
+pub fn is_resource_font(buf: &[u8]) -> bool {
+    let field = |at| be32(buf, at).map_or(0, |v| v as usize);
+    let (data, map, data_len, map_len) = (field(0), field(4), field(8), field(12));
+    if data != RESOURCE_DATA_OFFSET
+        || RESOURCE_DATA_OFFSET.checked_add(data_len) != Some(map)
+        || map_len < 30
+    {
+        return false;
+    }
+    match resource_types(buf) {
+        Some(types) => types.iter().any(|(t, _, _)| FONT_RESOURCES.contains(&t)),
+        None => buf
+            .get(RESOURCE_DATA_OFFSET + 4..)
+            .is_some_and(|sfnt| SFNT_VERSIONS.iter().any(|v| is_sfnt_version(sfnt, v))),
+    }
+}
+
+// `STARTFONT 2.1`.
+pub fn is_bdf(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"STARTFONT ", 0) && buf.get(10).is_some_and(u8::is_ascii_digit)
+}
+
+// `\x01fcp` and a table count; the nine table types are each present at most once.
+pub fn is_pcf(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"\x01fcp", 0) && le32(buf, 4).is_some_and(|n| (1..=9).contains(&n))
+}
+
+// The entries of the type list of a resource map held by `buf`: the type, the number
+// of resources and the offset of their reference list.
+fn resource_types(buf: &[u8]) -> Option<Vec<([u8; 4], usize, usize)>> {
+    let map_start = be32(buf, 4)? as usize;
+    let map = buf.get(map_start..map_start.checked_add(be32(buf, 12)? as usize)?)?;
+    let list_start = be16(map, 24)? as usize;
+    let list = map.get(list_start..)?;
+    let count = be16(list, 0)? as usize + 1;
+    let entries = list.get(2..2 + 8 * count)?;
+    entries
+        .chunks_exact(8)
+        .map(|e| {
+            let refs = list_start + be16(e, 6)? as usize;
+            Some((
+                [e[0], e[1], e[2], e[3]],
+                be16(e, 4)? as usize + 1,
+                map_start + refs,
+            ))
+        })
+        .collect()
+}
+
+pub fn parse(buf: &[u8]) -> Option<FontInfo> {
+    match buf.get(..4)? {
+        b"ttcf" if is_collection(buf) => {
+            let fonts = be32(buf, 8)? as usize;
+            let first = be32(buf, 12)? as usize;
+            let mut info = sfnt(buf, first)?;
+            info.fonts = fonts;
+            Some(info)
+        }
+        b"wOFF" => woff(buf),
+        b"wOF2" => woff2(buf),
+        b"STAR" if is_bdf(buf) => Some(bdf(buf)),
+        b"\x01fcp" if is_pcf(buf) => Some(FontInfo {
+            outlines: Some(Outlines::Bitmap),
+            fonts: 1,
+            ..Default::default()
+        }),
+        _ if is_type1(buf) => Some(type1(buf)),
+        _ if is_pfb(buf) => {
+            let len = le32(buf, 2)? as usize;
+            Some(type1(&buf[6..buf.len().min(len.saturating_add(6))]))
+        }
+        _ if is_resource_font(buf) => resource_font(buf),
+        _ => sfnt(buf, 0),
+    }
+}
+
+// A table, borrowed or inflated from a WOFF file.
+type Table<'a> = ([u8; 4], Option<Cow<'a, [u8]>>);
+
+// An sfnt at `offset`, which is not 0 inside a collection, whose table offsets count from
+// the start of `buf`.
+fn sfnt(buf: &[u8], offset: usize) -> Option<FontInfo> {
+    let font = buf.get(offset..)?;
+    if !SFNT_VERSIONS.iter().any(|v| compare_bytes(font, *v, 0)) {
+        return None;
+    }
+    let tags = sfnt_tags(font)?;
+    let tables = tags
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let record = &font[12 + 16 * i..];
+            let data = be32(record, 8)
+                .zip(be32(record, 12))
+                .and_then(|(start, len)| {
+                    let start = start as usize;
+                    buf.get(start..start.saturating_add(len as usize))
+                });
+            (*tag, data.map(Cow::from))
+        })
+        .collect();
+    Some(describe(tables))
+}
+
+fn woff(buf: &[u8]) -> Option<FontInfo> {
+    let n = be16(buf, 12)? as usize;
+    let entries = buf.get(44..44 + 20 * n.min(MAX_TABLES))?;
+    let tables = entries
+        .chunks_exact(20)
+        .map(|e| {
+            let tag = [e[0], e[1], e[2], e[3]];
+            let data = DESCRIBED_TABLES
+                .contains(&&tag)
+                .then(|| woff_table(buf, e))
+                .flatten();
+            (tag, data)
+        })
+        .collect();
+    Some(describe(tables))
+}
+
+fn woff_table<'a>(buf: &'a [u8], entry: &[u8]) -> Option<Cow<'a, [u8]>> {
+    let field = |at| be32(entry, at).map(|v| v as usize);
+    let (start, comp_len, orig_len) = (field(4)?, field(8)?, field(12)?);
+    let data = buf.get(start..start.checked_add(comp_len)?)?;
+    // Tables that did not shrink are stored as they are.
+    if comp_len < orig_len {
+        inflate_zlib_prefix(data, orig_len.min(MAX_TABLE_BYTES)).map(Cow::from)
+    } else {
+        Some(Cow::from(data))
+    }
+}
+
+// The tables of a WOFF2 file are Brotli compressed together, so only the directory is
+// read: the outlines, the axes' presence and the number of fonts, but no names.
+fn woff2(buf: &[u8]) -> Option<FontInfo> {
+    let n = (be16(buf, 12)? as usize).min(MAX_TABLES);
+    let mut at = 48;
+    let mut tables = vec![];
+    for _ in 0..n {
+        let flags = *buf.get(at)?;
+        at += 1;
+        let tag = match WOFF2_TAGS.get((flags & 0x3F) as usize) {
+            Some(tag) => **tag,
+            None => {
+                at += 4;
+                buf.get(at - 4..at)?.try_into().unwrap()
+            }
+        };
+        at = base128_end(buf, at)?;
+        // glyf and loca are transformed by version 0, the others by any other version.
+        let version = flags >> 6;
+        if (version == 0) == matches!(&tag, b"glyf" | b"loca") {
+            at = base128_end(buf, at)?;
+        }
+        tables.push((tag, None));
+    }
+
+    let mut info = describe(tables);
+    if compare_bytes(buf, b"ttcf", 4) {
+        // The collection header: its version, then the number of fonts as a 255UInt16.
+        info.fonts = match *buf.get(at + 4)? {
+            253 => be16(buf, at + 5)? as usize,
+            254 => *buf.get(at + 5)? as usize + 506,
+            255 => *buf.get(at + 5)? as usize + 253,
+            n => n as usize,
+        };
+    }
+    Some(info)
+}
+
+// The end of a UIntBase128, at most 5 bytes with the high bit set on all but the last.
+fn base128_end(buf: &[u8], at: usize) -> Option<usize> {
+    let len = buf.get(at..)?.iter().take(5).position(|b| b & 0x80 == 0)?;
+    Some(at + len + 1)
+}
+
+fn describe(tables: Vec<Table>) -> FontInfo {
+    let has = |tag: &[u8; 4]| tables.iter().any(|(t, _)| t == tag);
+    let data = |tag: &[u8; 4]| {
+        tables
+            .iter()
+            .find(|(t, _)| t == tag)
+            .and_then(|(_, d)| d.as_deref())
+    };
+
+    let outlines = if has(b"glyf") {
+        Some(Outlines::TrueType)
+    } else if has(b"CFF2") {
+        Some(Outlines::Cff2)
+    } else if has(b"CFF ") {
+        Some(Outlines::Cff)
+    } else if has(b"TYP1") || has(b"CID ") {
+        Some(Outlines::Type1)
+    } else if [b"EBDT", b"CBDT", b"bdat", b"sbix"].iter().any(|t| has(t)) {
+        Some(Outlines::Bitmap)
+    } else {
+        None
+    };
+    let name = |ids: [u16; 2]| {
+        let table = data(b"name")?;
+        ids.iter().find_map(|id| name_record(table, *id))
+    };
+
+    FontInfo {
+        family: name([16, 1]),
+        subfamily: name([17, 2]),
+        weight: data(b"OS/2").and_then(|os2| be16(os2, 4)),
+        outlines,
+        variable: has(b"fvar"),
+        axes: data(b"fvar").map(fvar_axes).unwrap_or_default(),
+        fonts: 1,
+    }
+}
+
+// The best string for a name ID: Windows Unicode in US English, then any language,
+// then Unicode, then Mac Roman, of which only ASCII is decoded.
+fn name_record(table: &[u8], id: u16) -> Option<String> {
+    let count = be16(table, 2)? as usize;
+    let storage = be16(table, 4)? as usize;
+    let records = table.get(6..6 + 12 * count).unwrap_or_default();
+    let (_, platform, bytes) = records
+        .chunks_exact(12)
+        .filter(|r| be16(r, 6) == Some(id))
+        .filter_map(|r| {
+            let (platform, encoding, language) = (be16(r, 0)?, be16(r, 2)?, be16(r, 4)?);
+            let rank = match (platform, encoding, language) {
+                (3, 1 | 10, 0x409) => 0,
+                (3, 1 | 10, _) => 1,
+                (0, _, _) => 2,
+                (1, 0, _) => 3,
+                _ => return None,
+            };
+            let start = storage + be16(r, 10)? as usize;
+            Some((
+                rank,
+                platform,
+                table.get(start..start + be16(r, 8)? as usize)?,
+            ))
+        })
+        .min_by_key(|(rank, _, _)| *rank)?;
+
+    let s = if platform == 1 {
+        bytes
+            .iter()
+            .map(|b| if b.is_ascii() { *b as char } else { '\u{FFFD}' })
+            .collect()
+    } else {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    let s = s.trim_end_matches('\0').trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+fn fvar_axes(fvar: &[u8]) -> Vec<String> {
+    let field = |at| be16(fvar, at).map(usize::from);
+    let (Some(start), Some(count), Some(size)) = (field(4), field(8), field(10)) else {
+        return vec![];
+    };
+    (0..count)
+        .map_while(|i| fvar.get(start + i * size..start + i * size + 4))
+        .map(|tag| String::from_utf8_lossy(tag).trim_end().to_string())
+        .collect()
+}
+
+// The cleartext part of a Type 1 program names the family and the weight, which stands
+// in for the subfamily.
+fn type1(buf: &[u8]) -> FontInfo {
+    // `bytes_index` returns `u32::MAX` when nothing is found.
+    let find = |buf: &[u8], subs: &[u8]| match bytes_index(buf, subs) {
+        u32::MAX => None,
+        at => Some(at as usize),
+    };
+    let cleartext = &buf[..find(buf, b"eexec").unwrap_or(buf.len())];
+    let string = |key: &[u8]| {
+        let rest = &cleartext[find(cleartext, key)? + key.len()..];
+        let rest = &rest[find(rest, b"(")? + 1..];
+        let end = find(rest, b")")?;
+        let s = String::from_utf8_lossy(rest.get(..end)?).trim().to_string();
+        (!s.is_empty()).then_some(s)
+    };
+    FontInfo {
+        family: string(b"/FamilyName"),
+        subfamily: string(b"/Weight"),
+        outlines: Some(Outlines::Type1),
+        fonts: 1,
+        ..Default::default()
+    }
+}
+
+// BDF properties: FAMILY_NAME and WEIGHT_NAME, standing in for the subfamily.
+fn bdf(buf: &[u8]) -> FontInfo {
+    let text = String::from_utf8_lossy(buf);
+    let property = |key: &str| {
+        let value = text
+            .lines()
+            .take_while(|l| !l.starts_with("ENDPROPERTIES"))
+            .find_map(|l| l.strip_prefix(key)?.strip_prefix(' '))?;
+        let value = value.trim().trim_matches('"').trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+    FontInfo {
+        family: property("FAMILY_NAME"),
+        subfamily: property("WEIGHT_NAME"),
+        outlines: Some(Outlines::Bitmap),
+        fonts: 1,
+        ..Default::default()
+    }
+}
+
+// The first `sfnt` resource of a dfont. Suitcases of bitmap or Type 1 fonts only report
+// their outlines.
+fn resource_font(buf: &[u8]) -> Option<FontInfo> {
+    let types = resource_types(buf)?;
+    if let Some((_, count, refs)) = types.iter().find(|(t, _, _)| t == b"sfnt") {
+        // The reference holds the offset of the data, after its length, from 256.
+        let offset = RESOURCE_DATA_OFFSET + (be32(buf, refs + 4)? as usize & 0xFF_FFFF) + 4;
+        let len = be32(buf, offset - 4)? as usize;
+        // Unlike in collections, table offsets count from the start of the resource.
+        let mut info = sfnt(buf.get(offset..offset.checked_add(len)?)?, 0)?;
+        info.fonts = *count;
+        return Some(info);
+    }
+    let outlines = if types.iter().any(|(t, _, _)| t == b"POST") {
+        Outlines::Type1
+    } else {
+        Outlines::Bitmap
+    };
+    Some(FontInfo {
+        outlines: Some(outlines),
+        fonts: types
+            .iter()
+            .filter(|(t, _, _)| t == b"FOND")
+            .map(|(_, n, _)| n)
+            .sum(),
+        ..Default::default()
+    })
+}
+
+// An sfnt with the given tables, in order.
+#[cfg(test)]
+fn build_sfnt(version: &[u8; 4], tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut buf = version.to_vec();
+    buf.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&[0; 6]);
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in tables {
+        buf.extend_from_slice(*tag);
+        buf.extend_from_slice(&[0; 4]);
+        buf.extend_from_slice(&(offset as u32).to_be_bytes());
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len();
+    }
+    for (_, data) in tables {
+        buf.extend_from_slice(data);
+    }
+    buf
+}
+
+#[test]
+fn test_font_sfnt() {
+    // A Mac Roman family name and a Windows subfamily.
+    let mut name = vec![0, 0, 0, 2, 0, 30];
+    name.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 1, 0, 4, 0, 0]);
+    name.extend_from_slice(&[0, 3, 0, 1, 0x04, 0x09, 0, 2, 0, 4, 0, 4]);
+    name.extend_from_slice(b"Demo\0B\0d");
+    let mut os2 = vec![0; 78];
+    os2[4..6].copy_from_slice(&700_u16.to_be_bytes());
+    let mut fvar = vec![0, 1, 0, 0, 0, 16, 0, 2, 0, 2, 0, 20, 0, 0, 0, 0];
+    fvar.extend_from_slice(b"wght");
+    fvar.extend_from_slice(&[0; 16]);
+    fvar.extend_from_slice(b"opsz");
+    fvar.extend_from_slice(&[0; 16]);
+
+    let buf = build_sfnt(
+        b"OTTO",
+        &[
+            (b"CFF2", vec![0; 4]),
+            (b"OS/2", os2),
+            (b"fvar", fvar),
+            (b"head", vec![0; 54]),
+            (b"name", name),
+        ],
+    );
+    assert!(is_sfnt_version(&buf, b"OTTO"));
+    let info = parse(&buf).unwrap();
+    assert_eq!(Some("Demo"), info.family.as_deref());
+    assert_eq!(Some("Bd"), info.subfamily.as_deref());
+    assert_eq!(
+        (Some(700), Some(Outlines::Cff2)),
+        (info.weight, info.outlines)
+    );
+    assert!(info.variable);
+    assert_eq!(vec!["wght", "opsz"], info.axes);
+
+    // Anything starting with `00 01 00 00 00` used to be a TrueType font.
+    assert!(!is_sfnt_version(
+        b"\x00\x01\x00\x00\x00\x00\x00\x00",
+        b"\x00\x01\x00\x00"
+    ));
+    assert!(!is_sfnt_version(
+        &build_sfnt(b"\x00\x01\x00\x00", &[(b"glyf", vec![0; 4])]),
+        b"\x00\x01\x00\x00"
+    ));
+    assert!(is_sfnt_version(
+        &build_sfnt(b"typ1", &[(b"TYP1", vec![0; 4])]),
+        b"typ1"
+    ));
+}
+
+#[test]
+fn test_font_text() {
+    let pfa = b"%!PS-AdobeFont-1.0: Demo-Bold 001.000\n\
+        /FontInfo 8 dict dup begin\n/FamilyName (Demo) readonly def\n\
+        /Weight (Bold) readonly def\nend readonly def\ncurrentfile eexec\n/Weight (X)";
+    let info = parse(pfa).unwrap();
+    assert_eq!(
+        (Some("Demo"), Some("Bold")),
+        (info.family.as_deref(), info.subfamily.as_deref())
+    );
+    assert!(!is_type1(b"%!PS-Adobe-3.0\n"));
+    let unnamed = b"%!PS-AdobeFont-1.0: Demo\n/FamilyName /Demo def\n/Weight (Bold";
+    let info = parse(unnamed).unwrap();
+    assert_eq!((None, None), (info.family, info.subfamily));
+
+    let bdf = b"STARTFONT 2.1\nFONT -misc-demo-medium-r-normal--8-80-75-75-c-50-iso10646-1\n\
+        STARTPROPERTIES 2\nFAMILY_NAME \"Demo\"\nWEIGHT_NAME \"Medium\"\nENDPROPERTIES\n";
+    let info = parse(bdf).unwrap();
+    assert_eq!(Some("Demo"), info.family.as_deref());
+    assert_eq!(Some("Medium"), info.subfamily.as_deref());
+    assert_eq!(Some(Outlines::Bitmap), info.outlines);
+}
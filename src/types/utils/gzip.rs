@@ -0,0 +1,91 @@
+// The start of a gzip member's uncompressed data, for formats that are gzip-compressed
+// as a whole, such as SVGZ. The header's optional fields are skipped and the deflate
+// stream is inflated until the output limit or the end of the input. zlib streams, as
+// found inside PNG chunks, are inflated the same way.
+// ref: https://www.rfc-editor.org/rfc/rfc1952, 2.3
+// ref: https://www.rfc-editor.org/rfc/rfc1950
+use miniz_oxide::inflate::{
+    core::{decompress, inflate_flags, DecompressorOxide},
+    TINFLStatus,
+};
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+pub fn is_gzip(buf: &[u8]) -> bool {
+    buf.len() >= 10 && buf[..3] == [0x1F, 0x8B, 0x08] && buf[3] & 0xE0 == 0
+}
+
+// Up to `limit` bytes of uncompressed data, or None when nothing could be inflated.
+pub fn inflate_prefix(buf: &[u8], limit: usize) -> Option<Vec<u8>> {
+    inflate(buf.get(data_offset(buf)?..)?, limit, 0)
+}
+
+// Like `inflate_prefix`, for a zlib stream.
+pub fn inflate_zlib_prefix(buf: &[u8], limit: usize) -> Option<Vec<u8>> {
+    inflate(buf, limit, inflate_flags::TINFL_FLAG_PARSE_ZLIB_HEADER)
+}
+
+fn inflate(data: &[u8], limit: usize, flags: u32) -> Option<Vec<u8>> {
+    let mut out = vec![0; limit];
+    let flags = flags
+        | inflate_flags::TINFL_FLAG_HAS_MORE_INPUT
+        | inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+    let (status, _, written) = decompress(&mut DecompressorOxide::new(), data, &mut out, 0, flags);
+    match status {
+        TINFLStatus::Done | TINFLStatus::NeedsMoreInput | TINFLStatus::HasMoreOutput
+            if written > 0 =>
+        {
+            out.truncate(written);
+            Some(out)
+        }
+        _ => None,
+    }
+}
+
+fn data_offset(buf: &[u8]) -> Option<usize> {
+    if !is_gzip(buf) {
+        return None;
+    }
+    let flags = buf[3];
+    let mut offset = 10;
+    if flags & FEXTRA != 0 {
+        let xlen = buf.get(offset..offset + 2)?;
+        offset += 2 + u16::from_le_bytes([xlen[0], xlen[1]]) as usize;
+    }
+    for field in [FNAME, FCOMMENT] {
+        if flags & field != 0 {
+            offset += buf.get(offset..)?.iter().position(|b| *b == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        offset += 2;
+    }
+    Some(offset)
+}
+
+#[test]
+fn test_gzip_inflate() {
+    // `printf 'hello, hello, hello\n' | gzip -n`, with FNAME "h" added.
+    let mut gz = vec![0x1F, 0x8B, 0x08, FNAME, 0, 0, 0, 0, 0, 0x03, b'h', 0];
+    gz.extend_from_slice(&[
+        0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0xC8, 0x40, 0xA2, 0xB8, 0x00, 0xE7, 0x42, 0x6E,
+        0x52, 0x14, 0, 0, 0,
+    ]);
+    assert_eq!(
+        Some(b"hello, hello, hello\n".to_vec()),
+        inflate_prefix(&gz, 64)
+    );
+    assert_eq!(Some(b"hello".to_vec()), inflate_prefix(&gz, 5));
+    assert_eq!(None, inflate_prefix(&gz[..12], 64));
+    assert_eq!(None, inflate_prefix(b"\x1F\x8B\x09\0\0\0\0\0\0\x03", 64));
+
+    // The same deflate data behind a zlib header.
+    let zlib = [&[0x78, 0x9C][..], &gz[12..gz.len() - 8]].concat();
+    assert_eq!(
+        Some(b"hello, hello, hello\n".to_vec()),
+        inflate_zlib_prefix(&zlib, 64)
+    );
+}
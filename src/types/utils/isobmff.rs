@@ -0,0 +1,151 @@
+// Boxes of the ISO base media file format: a 32-bit size (1 for a 64-bit size after the
+// type, 0 for "to the end"), a four-character type and the payload. Full boxes start
+// their payload with a version byte and 24 bits of flags.
+// ref: ISO/IEC 14496-12, 4.2
+// ref: ISO/IEC 14496-1, 7.2.6 (ES_Descriptor and DecoderConfigDescriptor in `esds`)
+
+pub struct Mp4Box<'a> {
+    pub kind: [u8; 4],
+    // Truncated when the buffer is only a prefix of the file.
+    pub data: &'a [u8],
+}
+
+impl<'a> Mp4Box<'a> {
+    pub fn children(&self) -> Vec<Mp4Box<'a>> {
+        boxes(self.data)
+    }
+}
+
+// The sibling boxes in `buf`; the last one may be cut short by the end of the buffer.
+pub fn boxes(buf: &[u8]) -> Vec<Mp4Box<'_>> {
+    let mut ret = vec![];
+    let mut offset = 0;
+    while let Some(header) = buf.get(offset..offset + 8) {
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let mut kind = [0_u8; 4];
+        kind.copy_from_slice(&header[4..8]);
+
+        let (header_len, size) = match size {
+            0 => (8, (buf.len() - offset) as u64),
+            1 => match buf.get(offset + 8..offset + 16) {
+                Some(large) => (16, u64::from_be_bytes(large.try_into().unwrap())),
+                None => break,
+            },
+            _ => (8, size),
+        };
+        if size < header_len {
+            break;
+        }
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|s| offset.checked_add(s))
+            .unwrap_or(usize::MAX);
+        ret.push(Mp4Box {
+            kind,
+            data: &buf[offset + header_len as usize..end.min(buf.len())],
+        });
+        offset = end;
+    }
+    ret
+}
+
+// The first box at the end of a path of box types, e.g. `[b"moov", b"mvhd"]`. `meta`
+// is entered as a full box.
+pub fn find<'a>(buf: &'a [u8], path: &[&[u8; 4]]) -> Option<Mp4Box<'a>> {
+    let (first, rest) = path.split_first()?;
+    let found = boxes(buf).into_iter().find(|b| &b.kind == *first)?;
+    if rest.is_empty() {
+        return Some(found);
+    }
+    let data = if &found.kind == b"meta" {
+        found.data.get(4..)?
+    } else {
+        found.data
+    };
+    find(data, rest)
+}
+
+// The DecoderConfigDescriptor of an `esds` box.
+pub struct DecoderConfig<'a> {
+    // 0x40 for MPEG-4 Audio, 0x20 for MPEG-4 Visual, 0x6B for MP3...
+    pub object_type: u8,
+    pub avg_bitrate: u32,
+    // The DecoderSpecificInfo, e.g. an AudioSpecificConfig.
+    pub specific_info: Option<&'a [u8]>,
+}
+
+// A descriptor with the given tag: its payload, cut short by the end of `buf`, and the
+// bytes after it. The size takes one to four bytes of 7 bits each.
+fn descriptor(buf: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    if *buf.first()? != tag {
+        return None;
+    }
+    let mut size = 0_usize;
+    let mut offset = 1;
+    loop {
+        let b = *buf.get(offset)?;
+        size = size << 7 | (b & 0x7F) as usize;
+        offset += 1;
+        if b & 0x80 == 0 || offset == 5 {
+            break;
+        }
+    }
+    let end = offset.saturating_add(size).min(buf.len());
+    Some((&buf[offset..end], &buf[end..]))
+}
+
+pub fn decoder_config(esds: &[u8]) -> Option<DecoderConfig<'_>> {
+    let (es, _) = descriptor(esds.get(4..)?, 0x03)?;
+    let flags = *es.get(2)?;
+    let mut offset = 3;
+    if flags & 0x80 != 0 {
+        offset += 2;
+    }
+    if flags & 0x40 != 0 {
+        offset += 1 + *es.get(offset)? as usize;
+    }
+    if flags & 0x20 != 0 {
+        offset += 2;
+    }
+
+    let (config, _) = descriptor(es.get(offset..)?, 0x04)?;
+    let fixed = config.get(..13)?;
+    Some(DecoderConfig {
+        object_type: fixed[0],
+        avg_bitrate: u32::from_be_bytes([fixed[9], fixed[10], fixed[11], fixed[12]]),
+        specific_info: descriptor(&config[13..], 0x05).map(|(info, _)| info),
+    })
+}
+
+#[test]
+fn test_isobmff_boxes() {
+    let mut buf = vec![0, 0, 0, 16];
+    buf.extend_from_slice(b"moov");
+    buf.extend_from_slice(&[0, 0, 0, 8]);
+    buf.extend_from_slice(b"mvhd");
+    buf.extend_from_slice(&[0, 0, 0, 1]);
+    buf.extend_from_slice(b"mdat");
+    buf.extend_from_slice(&24_u64.to_be_bytes());
+    buf.extend_from_slice(b"payload!");
+
+    let top = boxes(&buf);
+    assert_eq!(2, top.len());
+    assert_eq!(b"mdat", &top[1].kind);
+    assert_eq!(b"payload!", top[1].data);
+    assert!(find(&buf, &[b"moov", b"mvhd"]).is_some());
+    assert!(find(&buf, &[b"moov", b"trak"]).is_none());
+    // Cut off inside the `mdat` payload.
+    assert_eq!(b"pay", boxes(&buf[..35])[1].data);
+}
+
+#[test]
+fn test_isobmff_esds() {
+    // ES_Descriptor > DecoderConfigDescriptor (AAC, 128 kbit/s) > AudioSpecificConfig (LC).
+    let mut esds = vec![0, 0, 0, 0, 0x03, 0x80, 0x80, 0x80, 0x19, 0, 1, 0];
+    esds.extend_from_slice(&[0x04, 0x11, 0x40, 0x15, 0, 0, 0, 0, 0, 0, 0]);
+    esds.extend_from_slice(&128000_u32.to_be_bytes());
+    esds.extend_from_slice(&[0x05, 0x02, 0x12, 0x10]);
+    let config = decoder_config(&esds).unwrap();
+    assert_eq!((0x40, 128000), (config.object_type, config.avg_bitrate));
+    assert_eq!(Some(&[0x12, 0x10][..]), config.specific_info);
+}
@@ -0,0 +1,219 @@
+// Mach-O thin images and universal ("fat") binaries.
+// `CA FE BA BE` is shared with Java class files; a fat header stores the number of
+// architectures where a class file stores its minor/major version, and Java major
+// versions start at 45, so a small count means Mach-O.
+// ref: https://github.com/apple-oss-distributions/xnu/blob/main/EXTERNAL_HEADERS/mach-o/loader.h
+// ref: https://github.com/apple-oss-distributions/xnu/blob/main/EXTERNAL_HEADERS/mach-o/fat.h
+use super::{bigendian_bytes, littleendian_bytes};
+
+const MH_MAGIC: u32 = 0xFEEDFACE;
+const MH_MAGIC_64: u32 = 0xFEEDFACF;
+const MH_CIGAM: u32 = 0xCEFAEDFE;
+const MH_CIGAM_64: u32 = 0xCFFAEDFE;
+const FAT_MAGIC: u32 = 0xCAFEBABE;
+const FAT_MAGIC_64: u32 = 0xCAFEBABF;
+
+// Apple's own tools reject fat headers listing this many architectures or more.
+const MAX_FAT_ARCHS: u32 = 20;
+
+pub const MH_OBJECT: u32 = 1;
+pub const MH_EXECUTE: u32 = 2;
+pub const MH_DYLIB: u32 = 6;
+pub const MH_BUNDLE: u32 = 8;
+pub const MH_DSYM: u32 = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachOArch {
+    pub cputype: u32,
+    pub cpusubtype: u32,
+    // File offset of the image; 0 for thin files.
+    pub offset: u64,
+    // `filetype` of the image, when its header lies within the inspected bytes.
+    pub filetype: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachOInfo {
+    pub fat: bool,
+    pub archs: Vec<MachOArch>,
+}
+
+impl MachOArch {
+    pub fn cpu_name(&self) -> &'static str {
+        match (self.cputype, self.cpusubtype & 0x00FF_FFFF) {
+            (0x0000_0007, _) => "i386",
+            (0x0100_0007, 8) => "x86_64h",
+            (0x0100_0007, _) => "x86_64",
+            (0x0000_000C, 9) => "armv7",
+            (0x0000_000C, 11) => "armv7s",
+            (0x0000_000C, _) => "arm",
+            (0x0100_000C, 2) => "arm64e",
+            (0x0100_000C, _) => "arm64",
+            (0x0200_000C, _) => "arm64_32",
+            (0x0000_0012, _) => "ppc",
+            (0x0100_0012, _) => "ppc64",
+            _ => "unknown",
+        }
+    }
+}
+
+impl MachOInfo {
+    // The filetype of the first image whose header could be read.
+    pub fn filetype(&self) -> Option<u32> {
+        self.archs.iter().find_map(|a| a.filetype)
+    }
+
+    pub fn filetype_name(&self) -> &'static str {
+        match self.filetype() {
+            Some(MH_OBJECT) => "object",
+            Some(MH_EXECUTE) => "execute",
+            Some(3) => "fvmlib",
+            Some(4) => "core",
+            Some(5) => "preload",
+            Some(MH_DYLIB) => "dylib",
+            Some(7) => "dylinker",
+            Some(MH_BUNDLE) => "bundle",
+            Some(9) => "dylib-stub",
+            Some(MH_DSYM) => "dsym",
+            Some(11) => "kext-bundle",
+            Some(12) => "fileset",
+            _ => "unknown",
+        }
+    }
+}
+
+pub fn parse(buf: &[u8]) -> Option<MachOInfo> {
+    if buf.len() < 8 {
+        return None;
+    }
+
+    match bigendian_bytes(&buf[..4]) {
+        FAT_MAGIC => parse_fat(buf, false),
+        FAT_MAGIC_64 => parse_fat(buf, true),
+        _ => parse_thin(buf).map(|arch| MachOInfo {
+            fat: false,
+            archs: vec![arch],
+        }),
+    }
+}
+
+fn parse_thin(buf: &[u8]) -> Option<MachOArch> {
+    if buf.len() < 28 {
+        return None;
+    }
+
+    let read: fn(&[u8]) -> u32 = match bigendian_bytes(&buf[..4]) {
+        MH_MAGIC | MH_MAGIC_64 => bigendian_bytes,
+        MH_CIGAM | MH_CIGAM_64 => littleendian_bytes,
+        _ => return None,
+    };
+
+    Some(MachOArch {
+        cputype: read(&buf[4..8]),
+        cpusubtype: read(&buf[8..12]),
+        offset: 0,
+        filetype: Some(read(&buf[12..16])),
+    })
+}
+
+fn parse_fat(buf: &[u8], is_64: bool) -> Option<MachOInfo> {
+    let nfat_arch = bigendian_bytes(&buf[4..8]);
+    if nfat_arch == 0 || nfat_arch >= MAX_FAT_ARCHS {
+        return None;
+    }
+
+    let entry_size = if is_64 { 32 } else { 20 };
+    let mut archs = vec![];
+    for i in 0..nfat_arch as usize {
+        let at = 8 + i * entry_size;
+        let entry = buf.get(at..at + entry_size)?;
+        let offset = if is_64 {
+            (bigendian_bytes(&entry[8..12]) as u64) << 32 | bigendian_bytes(&entry[12..16]) as u64
+        } else {
+            bigendian_bytes(&entry[8..12]) as u64
+        };
+        // Images follow the header, never overlap it.
+        if offset < (8 + nfat_arch as usize * entry_size) as u64 {
+            return None;
+        }
+
+        let filetype = usize::try_from(offset)
+            .ok()
+            .and_then(|o| buf.get(o..))
+            .and_then(parse_thin)
+            .and_then(|a| a.filetype);
+        archs.push(MachOArch {
+            cputype: bigendian_bytes(&entry[..4]),
+            cpusubtype: bigendian_bytes(&entry[4..8]),
+            offset,
+            filetype,
+        });
+    }
+
+    Some(MachOInfo { fat: true, archs })
+}
+
+// A Java class file: `CA FE BA BE`, then minor and major version.
+pub fn is_java_class(buf: &[u8]) -> bool {
+    buf.len() > 9
+        && bigendian_bytes(&buf[..4]) == FAT_MAGIC
+        && u16::from_be_bytes([buf[6], buf[7]]) >= 45
+        // constant_pool_count
+        && u16::from_be_bytes([buf[8], buf[9]]) > 0
+}
+
+#[cfg(test)]
+fn thin_header(magic: [u8; 4], cputype: u32, filetype: u32) -> Vec<u8> {
+    let mut buf = magic.to_vec();
+    buf.extend_from_slice(&cputype.to_le_bytes());
+    buf.extend_from_slice(&0_u32.to_le_bytes());
+    buf.extend_from_slice(&filetype.to_le_bytes());
+    buf.extend_from_slice(&[0; 16]);
+    buf
+}
+
+#[test]
+fn test_macho_thin() {
+    let dylib = parse(&thin_header(
+        [0xCF, 0xFA, 0xED, 0xFE],
+        0x0100_000C,
+        MH_DYLIB,
+    ))
+    .unwrap();
+    assert!(!dylib.fat);
+    assert_eq!(Some(MH_DYLIB), dylib.filetype());
+    assert_eq!("dylib", dylib.filetype_name());
+    assert_eq!("arm64", dylib.archs[0].cpu_name());
+}
+
+#[test]
+fn test_macho_fat_vs_java() {
+    let mut fat = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2];
+    for (cputype, offset) in [(0x0100_0007_u32, 0x40_u32), (0x0100_000C, 0x4000)] {
+        fat.extend_from_slice(&cputype.to_be_bytes());
+        fat.extend_from_slice(&3_u32.to_be_bytes());
+        fat.extend_from_slice(&offset.to_be_bytes());
+        fat.extend_from_slice(&0x1000_u32.to_be_bytes());
+        fat.extend_from_slice(&14_u32.to_be_bytes());
+    }
+    fat.resize(0x40, 0);
+    fat.extend(thin_header(
+        [0xCF, 0xFA, 0xED, 0xFE],
+        0x0100_0007,
+        MH_EXECUTE,
+    ));
+
+    let info = parse(&fat).unwrap();
+    assert!(info.fat);
+    assert_eq!(2, info.archs.len());
+    assert_eq!("x86_64", info.archs[0].cpu_name());
+    assert_eq!(Some(MH_EXECUTE), info.archs[0].filetype);
+    // The second image starts past the buffer.
+    assert_eq!(None, info.archs[1].filetype);
+    assert!(!is_java_class(&fat));
+
+    // Java 8: minor 0, major 52.
+    let class = [0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52, 0, 0x1D];
+    assert!(parse(&class).is_none());
+    assert!(is_java_class(&class));
+}
@@ -0,0 +1,718 @@
+// Markup languages recognised from decoded text. Tag-based documents are told apart by
+// their prolog and root element: an XML declaration, a doctype, then the first element
+// and its namespace. Lightweight markup has no such anchor, so TeX and troff are found
+// by their distinctive commands and AsciiDoc, reStructuredText and Markdown by counting
+// the kinds of construct that occur; a single stray `#` or `*` decides nothing.
+// ref: https://mimesniff.spec.whatwg.org/#identifying-a-resource-with-an-unknown-mime-type
+// ref: https://www.w3.org/TR/xml/#sec-prolog-dtd
+// ref: https://man7.org/linux/man-pages/man7/groff_man.7.html
+use super::{gzip, text};
+use crate::constants::NUM_SIGNATURE_BYTES;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Markup {
+    Html,
+    Xhtml,
+    // XML, and the vocabulary named by its root element when it is a known one.
+    Xml(Option<XmlDialect>),
+    Svg,
+    Latex,
+    // troff using the man or mdoc macros.
+    Man,
+    Troff,
+    AsciiDoc,
+    Rst,
+    Markdown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XmlDialect {
+    Rss,
+    Atom,
+    Xslt,
+    Xsd,
+    Wsdl,
+    Soap,
+    Plist,
+    Gpx,
+    Kml,
+    Collada,
+    MathMl,
+    DocBook,
+    Xliff,
+    MusicXml,
+    AndroidManifest,
+    // Any other Android resource: layouts, drawables, menus.
+    AndroidResource,
+    MavenPom,
+    MsBuild,
+    // OpenDocument flat XML, by the `office:mimetype` of the document.
+    FlatText,
+    FlatSpreadsheet,
+    FlatPresentation,
+    FlatDrawing,
+    // Microsoft Office 2003 XML.
+    WordMl,
+    SpreadsheetMl,
+    // PDF form data.
+    Xfdf,
+}
+
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+pub const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+// Tags that open an HTML document or fragment, from the WHATWG sniffing algorithm.
+const HTML_TAGS: [&str; 17] = [
+    "html", "head", "body", "script", "iframe", "h1", "div", "font", "table", "a", "style",
+    "title", "b", "br", "p", "meta", "link",
+];
+
+// Lines inspected by the line-based heuristics.
+const MAX_LINES: usize = 200;
+
+// The document type declaration, XML declaration and root element of a tagged document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prolog<'a> {
+    pub declaration: bool,
+    // The text between `<!DOCTYPE` and the closing `>`.
+    pub doctype: Option<&'a str>,
+    // None when the root element is missing or cut off.
+    pub root: Option<Element<'a>>,
+    // The text from the root element onwards.
+    pub body: &'a str,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Element<'a> {
+    // The qualified name, prefix included.
+    pub name: &'a str,
+    pub attributes: Vec<(&'a str, &'a str)>,
+    pub self_closing: bool,
+}
+
+impl<'a> Element<'a> {
+    // The name without its namespace prefix.
+    pub fn local_name(&self) -> &'a str {
+        self.name.rsplit(':').next().unwrap_or(self.name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| *v)
+    }
+
+    // The namespace URI of the element itself, declared on the element.
+    pub fn namespace(&self) -> Option<&'a str> {
+        match self.name.split_once(':') {
+            Some((prefix, _)) => self.attribute(&format!("xmlns:{}", prefix)),
+            None => self.attribute("xmlns"),
+        }
+    }
+}
+
+pub fn identify(text: &str) -> Option<Markup> {
+    let text = text.trim_start_matches('\u{FEFF}').trim_start();
+    if text.starts_with('<') {
+        return tagged(text);
+    }
+
+    let lines: Vec<&str> = text.lines().take(MAX_LINES).collect();
+    if is_latex(&lines) {
+        Some(Markup::Latex)
+    } else if let Some(troff) = troff(&lines) {
+        Some(troff)
+    } else if lines.first().is_some_and(|l| is_asciidoc_title(l)) {
+        Some(Markup::AsciiDoc)
+    } else if is_rst(&lines) {
+        // Before AsciiDoc: reST field lists look like attribute entries.
+        Some(Markup::Rst)
+    } else if is_asciidoc(&lines) {
+        Some(Markup::AsciiDoc)
+    } else if is_markdown(&lines) {
+        Some(Markup::Markdown)
+    } else {
+        None
+    }
+}
+
+// SVG in any text encoding. SVG is an image, so unlike other markup it is matched
+// among the magic numbers rather than as a text fallback.
+pub fn is_svg(buf: &[u8]) -> bool {
+    buf.contains(&b'<')
+        && text::detect(buf).is_some_and(|info| identify(&info.decode(buf)) == Some(Markup::Svg))
+}
+
+// Gzip-compressed SVG, judged on the start of the inflated document.
+pub fn is_svgz(buf: &[u8]) -> bool {
+    gzip::inflate_prefix(buf, NUM_SIGNATURE_BYTES).is_some_and(|svg| is_svg(&svg))
+}
+
+// Skips the XML declaration, processing instructions, comments and the doctype, then
+// reads the start tag of the root element. None when the text is not tagged at all.
+pub fn prolog(text: &str) -> Option<Prolog<'_>> {
+    let mut rest = text.trim_start_matches('\u{FEFF}').trim_start();
+    let mut declaration = false;
+    let mut doctype = None;
+
+    loop {
+        if rest.starts_with("<?") {
+            declaration |= rest.starts_with("<?xml") && rest[5..].starts_with(char::is_whitespace);
+            rest = &rest[rest.find("?>")? + 2..];
+        } else if rest.starts_with("<!--") {
+            rest = &rest[rest.find("-->")? + 3..];
+        } else if starts_with_ignore_case(rest, "<!DOCTYPE") {
+            let end = doctype_end(rest)?;
+            doctype = Some(rest[9..end].trim());
+            rest = &rest[end + 1..];
+        } else {
+            break;
+        }
+        rest = rest.trim_start();
+    }
+
+    if !rest.starts_with('<') && !declaration && doctype.is_none() {
+        return None;
+    }
+    Some(Prolog {
+        declaration,
+        doctype,
+        root: element(rest),
+        body: rest,
+    })
+}
+
+// The offset of the `>` that closes a doctype, past any internal subset in brackets.
+fn doctype_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, '>') if depth <= 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// A start tag with its attributes; None when `text` does not open with a complete one.
+fn element(text: &str) -> Option<Element<'_>> {
+    let rest = text.strip_prefix('<')?;
+    let name_len = rest
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(rest.len());
+    let name = &rest[..name_len];
+    if !name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+    {
+        return None;
+    }
+
+    let mut attributes = vec![];
+    let mut rest = &rest[name_len..];
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with('>') {
+            return Some(Element {
+                name,
+                attributes,
+                self_closing: false,
+            });
+        }
+        if rest.starts_with("/>") {
+            return Some(Element {
+                name,
+                attributes,
+                self_closing: true,
+            });
+        }
+
+        let key_len = rest.find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))?;
+        let key = &rest[..key_len];
+        if key.is_empty() {
+            return None;
+        }
+        rest = rest[key_len..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let quote = after.chars().next().filter(|c| matches!(c, '"' | '\''));
+                let (value, end) = match quote {
+                    Some(q) => {
+                        let len = after[1..].find(q)?;
+                        (&after[1..1 + len], len + 2)
+                    }
+                    // HTML allows unquoted values.
+                    None => {
+                        let len = after.find(|c: char| c.is_whitespace() || c == '>')?;
+                        (&after[..len], len)
+                    }
+                };
+                rest = &after[end..];
+                value
+            }
+            None => "",
+        };
+        attributes.push((key, value));
+    }
+}
+
+fn tagged(text: &str) -> Option<Markup> {
+    let prolog = prolog(text)?;
+
+    if let Some(doctype) = prolog.doctype {
+        if starts_with_ignore_case(doctype, "html") {
+            let xhtml = doctype.contains("XHTML") || prolog.declaration;
+            return Some(if xhtml { Markup::Xhtml } else { Markup::Html });
+        }
+    }
+
+    let root = match prolog.root {
+        Some(root) => root,
+        None => {
+            let dialect = prolog.doctype.and_then(doctype_dialect);
+            return (prolog.declaration || dialect.is_some()).then_some(Markup::Xml(dialect));
+        }
+    };
+    let namespace = root.namespace();
+    if root.local_name() == "svg" || namespace == Some(SVG_NAMESPACE) {
+        return Some(Markup::Svg);
+    }
+    if root.local_name().eq_ignore_ascii_case("html") {
+        let xhtml = namespace == Some(XHTML_NAMESPACE) || prolog.declaration;
+        return Some(if xhtml { Markup::Xhtml } else { Markup::Html });
+    }
+    if let Some(dialect) = dialect(&root).or_else(|| prolog.doctype.and_then(doctype_dialect)) {
+        return Some(Markup::Xml(Some(dialect)));
+    }
+    if prolog.declaration || namespace.is_some() {
+        return Some(Markup::Xml(None));
+    }
+    if HTML_TAGS.iter().any(|t| root.name.eq_ignore_ascii_case(t)) {
+        return Some(Markup::Html);
+    }
+
+    // An undeclared XML document: a root element that is closed again.
+    let closed = root.self_closing || prolog.body.contains(&format!("</{}>", root.name));
+    closed.then_some(Markup::Xml(None))
+}
+
+// The vocabulary of a document by its root element: the namespace where the vocabulary
+// has one, otherwise the element name and a telling attribute.
+fn dialect(root: &Element) -> Option<XmlDialect> {
+    let namespace = root.namespace().unwrap_or_default();
+    let dialect = match (root.local_name(), namespace) {
+        ("rss", _) => XmlDialect::Rss,
+        // RSS 1.0 is RDF with RSS as the default namespace.
+        ("RDF", _) if root.attribute("xmlns") == Some("http://purl.org/rss/1.0/") => {
+            XmlDialect::Rss
+        }
+        ("feed", "http://www.w3.org/2005/Atom") => XmlDialect::Atom,
+        ("stylesheet" | "transform", "http://www.w3.org/1999/XSL/Transform") => XmlDialect::Xslt,
+        ("schema", "http://www.w3.org/2001/XMLSchema") => XmlDialect::Xsd,
+        ("definitions", "http://schemas.xmlsoap.org/wsdl/") => XmlDialect::Wsdl,
+        ("description", "http://www.w3.org/ns/wsdl") => XmlDialect::Wsdl,
+        (
+            "Envelope",
+            "http://schemas.xmlsoap.org/soap/envelope/" | "http://www.w3.org/2003/05/soap-envelope",
+        ) => XmlDialect::Soap,
+        ("plist", _) => XmlDialect::Plist,
+        ("gpx", ns) if ns.starts_with("http://www.topografix.com/GPX/") => XmlDialect::Gpx,
+        ("kml", ns)
+            if ns.starts_with("http://www.opengis.net/kml/")
+                || ns.starts_with("http://earth.google.com/kml/") =>
+        {
+            XmlDialect::Kml
+        }
+        ("COLLADA", _) => XmlDialect::Collada,
+        ("math", "http://www.w3.org/1998/Math/MathML") => XmlDialect::MathMl,
+        (_, "http://docbook.org/ns/docbook") => XmlDialect::DocBook,
+        ("xliff", _) => XmlDialect::Xliff,
+        ("score-partwise" | "score-timewise", _) => XmlDialect::MusicXml,
+        ("project", "http://maven.apache.org/POM/4.0.0") => XmlDialect::MavenPom,
+        ("Project", "http://schemas.microsoft.com/developer/msbuild/2003") => XmlDialect::MsBuild,
+        // SDK-style projects drop the namespace.
+        ("Project", "") if root.attribute("Sdk").is_some() => XmlDialect::MsBuild,
+        ("document", "urn:oasis:names:tc:opendocument:xmlns:office:1.0") => {
+            let mimetype = root.attribute(&format!("{}:mimetype", prefix(root.name)?))?;
+            match mimetype.strip_prefix("application/vnd.oasis.opendocument.")? {
+                "text" => XmlDialect::FlatText,
+                "spreadsheet" => XmlDialect::FlatSpreadsheet,
+                "presentation" => XmlDialect::FlatPresentation,
+                "graphics" => XmlDialect::FlatDrawing,
+                _ => return None,
+            }
+        }
+        ("wordDocument", "http://schemas.microsoft.com/office/word/2003/wordml") => {
+            XmlDialect::WordMl
+        }
+        ("Workbook", "urn:schemas-microsoft-com:office:spreadsheet") => XmlDialect::SpreadsheetMl,
+        ("xfdf", "http://ns.adobe.com/xfdf/") => XmlDialect::Xfdf,
+        ("manifest", _) if is_android(root) => XmlDialect::AndroidManifest,
+        _ if is_android(root) => XmlDialect::AndroidResource,
+        _ => return None,
+    };
+    Some(dialect)
+}
+
+fn prefix(name: &str) -> Option<&str> {
+    name.split_once(':').map(|(prefix, _)| prefix)
+}
+
+fn is_android(root: &Element) -> bool {
+    root.attribute("xmlns:android") == Some("http://schemas.android.com/apk/res/android")
+}
+
+// Vocabularies declared by their public identifier when the root element is cut off.
+fn doctype_dialect(doctype: &str) -> Option<XmlDialect> {
+    if doctype.contains("-//Apple//DTD PLIST") || doctype.contains("-//Apple Computer//DTD PLIST") {
+        Some(XmlDialect::Plist)
+    } else if doctype.contains("DocBook") {
+        Some(XmlDialect::DocBook)
+    } else {
+        None
+    }
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+}
+
+// `\documentclass` (or the LaTeX 2.09 `\documentstyle`) as the first command, or the
+// start of the document body.
+fn is_latex(lines: &[&str]) -> bool {
+    let mut commands = lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('%'));
+    let first = commands.next().unwrap_or_default();
+    [r"\documentclass", r"\documentstyle"]
+        .iter()
+        .any(|c| first.starts_with(c))
+        || lines.iter().any(|l| l.trim() == r"\begin{document}")
+}
+
+// A troff request or macro call: a control character, then a short name.
+fn is_request(line: &str) -> bool {
+    let name = match line.strip_prefix(['.', '\'']) {
+        Some(rest) => rest.split(' ').next().unwrap_or_default(),
+        None => return false,
+    };
+    (1..=3).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn is_troff_comment(line: &str) -> bool {
+    line.starts_with(".\\\"") || line.starts_with("'\\\"") || line.starts_with("\\\"")
+}
+
+fn troff(lines: &[&str]) -> Option<Markup> {
+    let first = lines
+        .iter()
+        .find(|l| !l.trim().is_empty() && !is_troff_comment(l))?;
+    let commented = is_troff_comment(lines.first()?);
+    if !is_request(first) && !commented {
+        return None;
+    }
+
+    let man = lines.iter().any(|l| {
+        // .TH for man, .Dd/.Dt for mdoc.
+        l.starts_with(".TH ") || l.starts_with(".Dd ") || l.starts_with(".Dt ")
+    });
+    let requests = lines.iter().filter(|l| is_request(l)).count();
+    if man {
+        Some(Markup::Man)
+    } else if requests >= 3 {
+        Some(Markup::Troff)
+    } else {
+        None
+    }
+}
+
+// `= Document Title`
+fn is_asciidoc_title(line: &str) -> bool {
+    line.strip_prefix("= ")
+        .is_some_and(|t| t.starts_with(|c: char| !c.is_whitespace()))
+}
+
+fn is_asciidoc(lines: &[&str]) -> bool {
+    let section = lines.iter().any(|l| {
+        let level = l.chars().take_while(|c| *c == '=').count();
+        (2..=6).contains(&level) && l[level..].starts_with(' ')
+    });
+    // `:toc:` or `:source-highlighter: rouge`
+    let attribute = lines.iter().any(|l| {
+        l.strip_prefix(':')
+            .and_then(|r| r.split_once(':'))
+            .is_some_and(|(name, value)| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '!'))
+                    && (value.is_empty() || value.starts_with(' '))
+            })
+    });
+    // `[source,rust]` or `[NOTE]`
+    let block = lines.iter().any(|l| {
+        l.len() > 2
+            && l.starts_with('[')
+            && l.ends_with(']')
+            && l[1..].starts_with(|c: char| c.is_alphabetic() || c == '.' || c == '#')
+    });
+    let delimiter = lines
+        .iter()
+        .any(|l| ["----", "====", "....", "****", "____"].contains(l));
+    let r#macro = lines.iter().any(|l| {
+        ["image::", "include::", "xref:", "link:", "ifdef::"]
+            .iter()
+            .any(|m| l.contains(m))
+    });
+
+    [section, attribute, block, delimiter, r#macro]
+        .iter()
+        .filter(|k| **k)
+        .count()
+        >= 2
+}
+
+// A line made of one punctuation character repeated at least as long as `title`.
+fn is_adornment(line: &str, title: &str) -> Option<char> {
+    let c = line.chars().next()?;
+    let len = line.chars().count();
+    (c.is_ascii_punctuation()
+        && len >= 3
+        && line.chars().all(|x| x == c)
+        && !title.trim().is_empty()
+        && len >= title.trim_end().chars().count())
+    .then_some(c)
+}
+
+fn is_rst(lines: &[&str]) -> bool {
+    let directive = lines.iter().any(|l| {
+        l.strip_prefix(".. ").is_some_and(|r| {
+            r.starts_with('_')
+                || r.starts_with('|')
+                || r.split_once("::").is_some_and(|(name, _)| {
+                    !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_alphanumeric() || matches!(c, '-' | ':'))
+                })
+        })
+    });
+    if directive {
+        return true;
+    }
+
+    // Markdown shares the `=` and `-` underlines, so they count only with an overline.
+    let title = lines.windows(2).enumerate().any(|(i, w)| {
+        is_adornment(w[1], w[0]).is_some_and(|c| {
+            !matches!(c, '=' | '-')
+                || (i > 0 && lines[i - 1] == w[1] && !w[0].starts_with(char::is_whitespace))
+        })
+    });
+    let literal = lines
+        .iter()
+        .any(|l| l.trim_end().ends_with("::") && l.trim().len() > 2);
+    let inline = lines.iter().any(|l| l.contains("``"));
+    let role = lines.iter().any(|l| {
+        l.match_indices(":`").any(|(i, _)| {
+            let before = &l[..i];
+            before
+                .rfind(':')
+                .is_some_and(|s| s + 1 < i && before[s + 1..].chars().all(char::is_alphanumeric))
+        })
+    });
+
+    [title, literal, inline, role]
+        .iter()
+        .filter(|k| **k)
+        .count()
+        >= 2
+}
+
+fn is_markdown(lines: &[&str]) -> bool {
+    let heading = lines.iter().any(|l| {
+        let level = l.chars().take_while(|c| *c == '#').count();
+        (1..=6).contains(&level) && l[level..].starts_with(' ') && l.len() > level + 1
+    });
+    let setext = lines.windows(2).any(|w| {
+        is_adornment(w[1], w[0]).is_some_and(|c| c == '=' || c == '-')
+            && !w[0].starts_with(char::is_whitespace)
+    });
+    let fence = lines
+        .iter()
+        .any(|l| l.starts_with("```") || l.starts_with("~~~"));
+    let list = lines.iter().any(|l| {
+        let t = l.trim_start();
+        let digits = t.chars().take_while(char::is_ascii_digit).count();
+        let rest = match digits {
+            0 => t.strip_prefix(['-', '*', '+']),
+            _ => t[digits..].strip_prefix(['.', ')']),
+        };
+        rest.is_some_and(|r| r.starts_with(' ') && !r.trim().is_empty())
+    });
+    let link = lines
+        .iter()
+        .any(|l| l.contains("](") || (l.starts_with('[') && l.contains("]: ")));
+    let emphasis = lines
+        .iter()
+        .any(|l| l.match_indices("**").count() >= 2 || l.match_indices("__").count() >= 2);
+    let code = lines.iter().any(|l| {
+        let ticks = l.matches('`').count();
+        ticks >= 2 && ticks % 2 == 0 && !l.contains("``")
+    });
+    let quote = lines.iter().any(|l| l.starts_with("> "));
+    let table = lines.iter().any(|l| {
+        let t = l.trim().trim_matches('|');
+        t.contains("---")
+            && l.contains('|')
+            && t.chars().all(|c| matches!(c, '-' | ':' | '|' | ' '))
+    });
+
+    [
+        heading, setext, fence, list, link, emphasis, code, quote, table,
+    ]
+    .iter()
+    .filter(|k| **k)
+    .count()
+        >= 2
+}
+
+#[test]
+fn test_markup_tagged() {
+    assert_eq!(
+        Some(Markup::Html),
+        identify("<!DOCTYPE html>\n<html lang=en><head>")
+    );
+    assert_eq!(
+        Some(Markup::Html),
+        identify("<!-- page -->\n<DIV class=\"x\">text</DIV>")
+    );
+    assert_eq!(
+        Some(Markup::Xhtml),
+        identify("<?xml version=\"1.0\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\">")
+    );
+    assert_eq!(
+        Some(Markup::Xhtml),
+        identify("<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" \"x.dtd\">\n<html>")
+    );
+    assert_eq!(
+        Some(Markup::Svg),
+        identify(
+            "<?xml version=\"1.0\"?>\n<!DOCTYPE svg [\n<!ENTITY a \"b\">\n]>\n<svg width=\"1\">"
+        )
+    );
+    assert_eq!(
+        Some(Markup::Svg),
+        identify("<s:svg xmlns:s=\"http://www.w3.org/2000/svg\"/>")
+    );
+    assert_eq!(
+        Some(Markup::Xml(None)),
+        identify("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<note><to>A</to></note>")
+    );
+    assert_eq!(
+        Some(Markup::Xml(None)),
+        identify("<note>\n  <to>A</to>\n</note>\n")
+    );
+    assert_eq!(None, identify("<not a tag"));
+    assert_eq!(None, identify("< 3 is less than"));
+
+    let prolog = prolog("<?xml version='1.0'?><rss version='2.0'>").unwrap();
+    let root = prolog.root.unwrap();
+    assert_eq!("rss", root.local_name());
+    assert_eq!(Some("2.0"), root.attribute("version"));
+}
+
+#[test]
+fn test_markup_xml_dialects() {
+    let dialect = |text: &str| match identify(text) {
+        Some(Markup::Xml(dialect)) => dialect,
+        _ => None,
+    };
+    assert_eq!(
+        Some(XmlDialect::Rss),
+        dialect("<?xml version=\"1.0\"?><rss version=\"2.0\"><channel>")
+    );
+    assert_eq!(
+        Some(XmlDialect::Atom),
+        dialect("<feed xmlns=\"http://www.w3.org/2005/Atom\">")
+    );
+    assert_eq!(
+        Some(XmlDialect::Xslt),
+        dialect(
+            "<xsl:stylesheet version=\"1.0\" xmlns:xsl=\"http://www.w3.org/1999/XSL/Transform\">"
+        )
+    );
+    assert_eq!(
+        Some(XmlDialect::Soap),
+        dialect("<soap:Envelope xmlns:soap=\"http://www.w3.org/2003/05/soap-envelope\">")
+    );
+    assert_eq!(
+        Some(XmlDialect::Plist),
+        dialect("<?xml version=\"1.0\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<pl")
+    );
+    assert_eq!(
+        Some(XmlDialect::MsBuild),
+        dialect("<Project Sdk=\"Microsoft.NET.Sdk\">\n</Project>")
+    );
+    assert_eq!(
+        Some(XmlDialect::FlatText),
+        dialect("<office:document xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" office:mimetype=\"application/vnd.oasis.opendocument.text\">")
+    );
+    assert_eq!(
+        Some(XmlDialect::AndroidResource),
+        dialect("<LinearLayout xmlns:android=\"http://schemas.android.com/apk/res/android\" android:orientation=\"vertical\">")
+    );
+    assert_eq!(
+        Some(XmlDialect::AndroidManifest),
+        dialect("<manifest xmlns:android=\"http://schemas.android.com/apk/res/android\" package=\"a.b\">")
+    );
+    assert_eq!(None, dialect("<feed><entry/></feed>"));
+}
+
+#[test]
+fn test_markup_lightweight() {
+    let latex = "% preamble\n\\documentclass[a4paper]{article}\n\\begin{document}\nHi\n";
+    assert_eq!(Some(Markup::Latex), identify(latex));
+
+    let man = ".\\\" Manual page\n.TH LS 1 \"2024\" \"GNU\"\n.SH NAME\nls \\- list\n";
+    assert_eq!(Some(Markup::Man), identify(man));
+    assert_eq!(
+        Some(Markup::Troff),
+        identify(".ft B\nBold\n.ft R\n.sp 2\n.ce\nCentred\n")
+    );
+
+    let adoc =
+        "= Guide\nAuthor Name\n:toc:\n\n== Intro\n\n[source,rust]\n----\nfn main() {}\n----\n";
+    assert_eq!(Some(Markup::AsciiDoc), identify(adoc));
+    assert_eq!(
+        Some(Markup::AsciiDoc),
+        identify("Text\n\n== Section\n\n[NOTE]\nA note.\n")
+    );
+
+    let rst = "=====\nTitle\n=====\n\nUse ``code`` here::\n\n    indented\n";
+    assert_eq!(Some(Markup::Rst), identify(rst));
+    assert_eq!(
+        Some(Markup::Rst),
+        identify("Intro\n\n.. code-block:: python\n\n   pass\n")
+    );
+
+    let md = "# Title\n\nSome `code` and a [link](https://example.com).\n\n- item\n";
+    assert_eq!(Some(Markup::Markdown), identify(md));
+    assert_eq!(
+        Some(Markup::Markdown),
+        identify("Title\n=====\n\n```\ncode\n```\n")
+    );
+
+    assert_eq!(None, identify("Just some prose.\n# not a heading alone\n"));
+    assert_eq!(None, identify("Hello, world.\nSee you.\n"));
+}
@@ -0,0 +1,648 @@
+// Camera and color metadata embedded in images: the EXIF block (a TIFF structure with
+// IFD0, the EXIF IFD and the GPS IFD), the XMP packet and the ICC profile. JPEG keeps
+// them in APP1 and APP2 segments, TIFF in IFD0 tags, PNG in `eXIf`, `iTXt` and `iCCP`
+// chunks, and HEIF and AVIF in `Exif` and `mime` items and the `colr` property.
+// ref: CIPA DC-008-2023 (Exif 3.0), 4.5.4 (APP1) and 4.6.6 (GPS Info IFD)
+// ref: https://developer.adobe.com/xmp/docs/XMPSpecifications/ (Part 3, 1.1)
+// ref: ICC.1:2022, 7.2 (header), 7.3 (tag table), 10.13 (desc), 10.15 (mluc)
+// ref: https://www.w3.org/TR/png-3/#eXIf and #11iCCP and #11iTXt
+// ref: ISO/IEC 23008-12, A.2.1 (Exif items); ISO/IEC 14496-12, 8.11.3 (iloc), 8.11.6 (iinf)
+use super::{
+    be16, be32,
+    gzip::inflate_zlib_prefix,
+    image::is_iso_bmf,
+    isobmff,
+    tiff::{self, Entry, Tiff},
+};
+use std::borrow::Cow;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    // As in EXIF: 1 is upright, 3 upside down, 6 and 8 need a quarter turn clockwise and
+    // counterclockwise, and 2, 4, 5 and 7 are mirrored.
+    pub orientation: Option<u16>,
+    // ISO 8601, with the UTC offset when the file records one.
+    pub capture_time: Option<String>,
+    pub gps: Option<GpsPosition>,
+    // The description of the embedded ICC profile, e.g. "sRGB IEC61966-2.1".
+    pub icc_profile: Option<String>,
+    // Which of the blocks are present, whether or not any field could be read.
+    pub exif: bool,
+    pub xmp: bool,
+    pub icc: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpsPosition {
+    // Degrees, negative to the south and west.
+    pub latitude: f64,
+    pub longitude: f64,
+    // Meters above sea level.
+    pub altitude: Option<f64>,
+}
+
+// The raw blocks of one file, before any of them is decoded.
+#[derive(Default)]
+struct Blocks<'a> {
+    // A TIFF structure, without the `Exif\0\0` header.
+    exif: Option<&'a [u8]>,
+    xmp: Option<Cow<'a, [u8]>>,
+    icc: Option<Cow<'a, [u8]>>,
+}
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const ICC_HEADER: &[u8] = b"ICC_PROFILE\0";
+const XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+const XMP_CONTENT_TYPE: &[u8] = b"application/rdf+xml";
+
+// Inflated from PNG chunks; the tags this module reads come first in practice.
+const MAX_INFLATED_BYTES: usize = 64 << 10;
+const MAX_ICC_TAGS: usize = 64;
+
+const GPS_LATITUDE_REF: u16 = 0x0001;
+const GPS_LATITUDE: u16 = 0x0002;
+const GPS_LONGITUDE_REF: u16 = 0x0003;
+const GPS_LONGITUDE: u16 = 0x0004;
+const GPS_ALTITUDE_REF: u16 = 0x0005;
+const GPS_ALTITUDE: u16 = 0x0006;
+
+pub fn parse(buf: &[u8]) -> Option<Metadata> {
+    match buf.get(..4)? {
+        [0xFF, 0xD8, 0xFF, _] => Some(jpeg(buf)),
+        [0x89, b'P', b'N', b'G'] => Some(png(buf)),
+        b"II*\0" | b"MM\0*" => tiff_image(buf),
+        _ if is_iso_bmf(buf) => heif(buf),
+        _ => None,
+    }
+}
+
+fn decode(blocks: &Blocks) -> Metadata {
+    let mut meta = Metadata {
+        exif: blocks.exif.is_some(),
+        xmp: blocks.xmp.is_some(),
+        icc: blocks.icc.is_some(),
+        icc_profile: blocks.icc.as_deref().and_then(icc_description),
+        ..Default::default()
+    };
+    // EXIF values win over the XMP copies of them.
+    if let Some(xmp) = &blocks.xmp {
+        apply_xmp(&mut meta, &String::from_utf8_lossy(xmp));
+    }
+    if let Some(exif) = blocks.exif {
+        apply_exif(&mut meta, exif);
+    }
+    meta
+}
+
+fn jpeg(buf: &[u8]) -> Metadata {
+    let mut blocks = Blocks::default();
+    // A profile too large for one segment is split into numbered chunks.
+    let mut icc_chunks = vec![];
+
+    let mut offset = 2;
+    while let Some(marker) = buf.get(offset..offset + 4) {
+        match marker {
+            [0xFF, 0xFF, ..] => {
+                offset += 1;
+                continue;
+            }
+            [0xFF, 0x01 | 0xD0..=0xD8, ..] => {
+                offset += 2;
+                continue;
+            }
+            [0xFF, 0xD9 | 0xDA, ..] => break,
+            [0xFF, ..] => {}
+            _ => break,
+        }
+        let Some(len) = be16(marker, 2).map(usize::from) else {
+            break;
+        };
+        let data = match buf.get(offset + 4..offset + 2 + len.max(2)) {
+            Some(d) => d,
+            None => break,
+        };
+        match marker[1] {
+            0xE1 if data.starts_with(EXIF_HEADER) && blocks.exif.is_none() => {
+                blocks.exif = Some(&data[EXIF_HEADER.len()..]);
+            }
+            0xE1 if data.starts_with(XMP_HEADER) && blocks.xmp.is_none() => {
+                blocks.xmp = Some(Cow::Borrowed(&data[XMP_HEADER.len()..]));
+            }
+            0xE2 if data.starts_with(ICC_HEADER) && data.len() > ICC_HEADER.len() + 2 => {
+                let n = ICC_HEADER.len();
+                icc_chunks.push((data[n], &data[n + 2..]));
+            }
+            _ => {}
+        }
+        offset += 2 + len;
+    }
+
+    icc_chunks.sort_by_key(|(seq, _)| *seq);
+    if !icc_chunks.is_empty() {
+        let icc = icc_chunks.into_iter().flat_map(|(_, d)| d).copied();
+        blocks.icc = Some(Cow::Owned(icc.collect()));
+    }
+    decode(&blocks)
+}
+
+fn png(buf: &[u8]) -> Metadata {
+    let mut blocks = Blocks::default();
+    let mut offset = 8;
+    while let Some(header) = buf.get(offset..offset + 8) {
+        let Some(len) = be32(header, 0).map(|len| len as usize) else {
+            break;
+        };
+        let data = match buf.get(offset + 8..).and_then(|d| d.get(..len)) {
+            Some(d) => d,
+            None => break,
+        };
+        match &header[4..8] {
+            b"eXIf" => blocks.exif = Some(data.strip_prefix(EXIF_HEADER).unwrap_or(data)),
+            // The profile name, then the compression method and the zlib stream.
+            b"iCCP" => {
+                let profile = data
+                    .iter()
+                    .position(|b| *b == 0)
+                    .and_then(|i| data.get(i + 2..));
+                blocks.icc = Some(Cow::Owned(
+                    profile
+                        .and_then(|p| inflate_zlib_prefix(p, MAX_INFLATED_BYTES))
+                        .unwrap_or_default(),
+                ));
+            }
+            // The keyword, the compression flag and method, the language tag and the
+            // translated keyword, then the text.
+            b"iTXt" if data.starts_with(XMP_KEYWORD) && data.get(XMP_KEYWORD.len()) == Some(&0) => {
+                let mut rest = &data[XMP_KEYWORD.len() + 1..];
+                let compressed = rest.first() == Some(&1);
+                rest = rest.get(2..).unwrap_or_default();
+                for _ in 0..2 {
+                    let end = rest
+                        .iter()
+                        .position(|b| *b == 0)
+                        .map_or(rest.len(), |i| i + 1);
+                    rest = &rest[end..];
+                }
+                blocks.xmp = Some(if compressed {
+                    Cow::Owned(inflate_zlib_prefix(rest, MAX_INFLATED_BYTES).unwrap_or_default())
+                } else {
+                    Cow::Borrowed(rest)
+                });
+            }
+            b"IEND" => break,
+            _ => {}
+        }
+        offset += 12 + len;
+    }
+    decode(&blocks)
+}
+
+fn tiff_image(buf: &[u8]) -> Option<Metadata> {
+    let tiff = Tiff::parse(buf)?;
+    let (ifd0, _) = tiff.ifd(tiff.ifd0)?;
+    let bytes = |tag| tiff::find(&ifd0, tag).and_then(|e| tiff.bytes(e));
+    let blocks = Blocks {
+        exif: Some(buf),
+        xmp: bytes(tiff::TAG_XMP).map(Cow::Borrowed),
+        icc: bytes(tiff::TAG_ICC_PROFILE).map(Cow::Borrowed),
+    };
+
+    let mut meta = decode(&blocks);
+    // IFD0 is the image itself; only the private IFDs count as an EXIF block.
+    meta.exif = [tiff::TAG_EXIF_IFD, tiff::TAG_GPS_IFD]
+        .iter()
+        .any(|tag| tiff::find(&ifd0, *tag).is_some());
+    Some(meta)
+}
+
+fn heif(buf: &[u8]) -> Option<Metadata> {
+    isobmff::find(buf, &[b"meta"])?;
+    let items = items(buf);
+    let data = |kind: &[u8; 4], content_type: Option<&[u8]>| {
+        let (id, _, _) = items
+            .iter()
+            .find(|(_, k, c)| k == kind && content_type.is_none_or(|t| c.starts_with(t)))?;
+        item_data(buf, *id)
+    };
+
+    // `colr` starts with the colour type: `prof` and `rICC` are followed by a profile.
+    let properties = isobmff::find(buf, &[b"meta", b"iprp", b"ipco"]).map(|p| p.children());
+    let icc = properties
+        .unwrap_or_default()
+        .iter()
+        .filter(|p| &p.kind == b"colr")
+        .find_map(|p| match p.data.get(..4)? {
+            b"prof" | b"rICC" => Some(Cow::Borrowed(&p.data[4..])),
+            _ => None,
+        });
+    let blocks = Blocks {
+        exif: data(b"Exif", None).and_then(exif_item_tiff),
+        xmp: data(b"mime", Some(XMP_CONTENT_TYPE)).map(Cow::Borrowed),
+        icc,
+    };
+
+    // Items stored past the end of `buf` are still reported as present.
+    let mut ret = decode(&blocks);
+    ret.exif = items.iter().any(|(_, kind, _)| kind == b"Exif");
+    ret.xmp = items
+        .iter()
+        .any(|(_, kind, c)| kind == b"mime" && c.starts_with(XMP_CONTENT_TYPE));
+    Some(ret)
+}
+
+// The ID, type and content type of each item listed by `iinf`.
+fn items(buf: &[u8]) -> Vec<(u32, [u8; 4], &[u8])> {
+    let iinf = match isobmff::find(buf, &[b"meta", b"iinf"]) {
+        Some(b) => b,
+        None => return vec![],
+    };
+    let entries_at = if iinf.data.first() == Some(&0) { 6 } else { 8 };
+    let entries = isobmff::boxes(iinf.data.get(entries_at..).unwrap_or_default());
+    entries
+        .iter()
+        .filter(|b| &b.kind == b"infe")
+        .filter_map(|infe| {
+            // Versions 2 and 3 differ in the width of the item ID.
+            let (id, rest) = match infe.data.first()? {
+                2 => (be16(infe.data, 4)?.into(), infe.data.get(8..)?),
+                3 => (be32(infe.data, 4)?, infe.data.get(10..)?),
+                _ => return None,
+            };
+            let kind: [u8; 4] = rest.get(..4)?.try_into().ok()?;
+            // The item name, then the content type of `mime` items.
+            let content_type = rest[4..].split(|b| *b == 0).nth(1);
+            Some((id, kind, content_type.unwrap_or_default()))
+        })
+        .collect()
+}
+
+// The construction method, offset and length of the first extent of an item, as
+// listed by `iloc`.
+fn item_location(buf: &[u8], id: u32) -> Option<(u16, u64, u64)> {
+    let iloc = isobmff::find(buf, &[b"meta", b"iloc"])?.data;
+    let version = *iloc.first()?;
+    let sizes = iloc.get(4..6)?;
+    let (offset_size, length_size) = ((sizes[0] >> 4) as usize, (sizes[0] & 0x0F) as usize);
+    let (base_size, index_size) = (
+        (sizes[1] >> 4) as usize,
+        if version > 0 {
+            (sizes[1] & 0x0F) as usize
+        } else {
+            0
+        },
+    );
+
+    let mut at = 6;
+    let mut read = |n: usize| -> Option<u64> {
+        let b = iloc.get(at..at + n)?;
+        at += n;
+        Some(b.iter().fold(0, |v, b| v << 8 | *b as u64))
+    };
+    let count = read(if version < 2 { 2 } else { 4 })?;
+    for _ in 0..count {
+        let item = read(if version < 2 { 2 } else { 4 })?;
+        let method = if version > 0 {
+            read(2)? as u16 & 0x0F
+        } else {
+            0
+        };
+        read(2)?;
+        let base = read(base_size)?;
+        let extents = read(2)?;
+        let mut first = None;
+        for _ in 0..extents {
+            read(index_size)?;
+            let extent = (read(offset_size)?, read(length_size)?);
+            first = first.or(Some(extent));
+        }
+        if item == id as u64 {
+            let (offset, length) = first?;
+            return Some((method, base.checked_add(offset)?, length));
+        }
+    }
+    None
+}
+
+// An item stored in the file (construction method 0) or in `idat` (1).
+fn item_data(buf: &[u8], id: u32) -> Option<&[u8]> {
+    let (method, offset, length) = item_location(buf, id)?;
+    let data = match method {
+        0 => buf,
+        1 => isobmff::find(buf, &[b"meta", b"idat"])?.data,
+        _ => return None,
+    };
+    let start = usize::try_from(offset).ok()?;
+    data.get(start..start.checked_add(usize::try_from(length).ok()?)?)
+}
+
+// An Exif item starts with the offset of the TIFF header from the end of that field.
+fn exif_item_tiff(item: &[u8]) -> Option<&[u8]> {
+    item.get((be32(item, 0)? as usize).checked_add(4)?..)
+}
+
+// The file range of the Exif item of a HEIF file, for files that keep it past `buf`.
+pub fn exif_item_extent(buf: &[u8]) -> Option<(u64, u64)> {
+    let (id, _, _) = items(buf).into_iter().find(|(_, k, _)| k == b"Exif")?;
+    match item_location(buf, id)? {
+        (0, offset, length) => Some((offset, length)),
+        _ => None,
+    }
+}
+
+// Reads an Exif item fetched from the range given by `exif_item_extent`.
+pub fn apply_exif_item(meta: &mut Metadata, item: &[u8]) {
+    if let Some(tiff) = exif_item_tiff(item) {
+        meta.exif = true;
+        apply_exif(meta, tiff);
+    }
+}
+
+// Sets the fields found in an EXIF block, keeping the others.
+fn apply_exif(meta: &mut Metadata, buf: &[u8]) -> Option<()> {
+    let tiff = Tiff::parse(buf)?;
+    let (ifd0, _) = tiff.ifd(tiff.ifd0)?;
+    let sub_ifd = |tag| {
+        let offset = tiff.value(tiff::find(&ifd0, tag)?)?;
+        tiff.ifd(offset).map(|(entries, _)| entries)
+    };
+    let exif = sub_ifd(tiff::TAG_EXIF_IFD).unwrap_or_default();
+    let ascii = |entries: &[Entry], tag| {
+        let s = tiff.ascii(tiff::find(entries, tag)?)?;
+        (!s.is_empty()).then(|| s.to_string())
+    };
+
+    meta.make = ascii(&ifd0, tiff::TAG_MAKE).or(meta.make.take());
+    meta.model = ascii(&ifd0, tiff::TAG_MODEL).or(meta.model.take());
+    meta.orientation = tiff::find(&ifd0, tiff::TAG_ORIENTATION)
+        .and_then(|e| tiff.value(e))
+        .and_then(|v| u16::try_from(v).ok())
+        .filter(|v| (1..=8).contains(v))
+        .or(meta.orientation);
+    let original = ascii(&exif, tiff::TAG_DATE_TIME_ORIGINAL).and_then(|t| {
+        let offset = ascii(&exif, tiff::TAG_OFFSET_TIME_ORIGINAL);
+        exif_time(&t, offset.as_deref())
+    });
+    meta.capture_time = original
+        .or_else(|| exif_time(&ascii(&ifd0, tiff::TAG_DATE_TIME)?, None))
+        .or(meta.capture_time.take());
+    meta.gps = sub_ifd(tiff::TAG_GPS_IFD)
+        .and_then(|gps| gps_position(&tiff, &gps))
+        .or(meta.gps);
+    Some(())
+}
+
+// `YYYY:MM:DD HH:MM:SS` as ISO 8601. Unknown dates are written with blanks or zeros.
+fn exif_time(time: &str, offset: Option<&str>) -> Option<String> {
+    let b = time.as_bytes();
+    if b.len() < 19 || b[4] != b':' || b[7] != b':' || !b[..4].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    if &time[..4] == "0000" {
+        return None;
+    }
+    let offset = offset.filter(|o| o.len() == 6).unwrap_or_default();
+    Some(format!(
+        "{}-{}-{}T{}{offset}",
+        &time[..4],
+        &time[5..7],
+        &time[8..10],
+        &time[11..19]
+    ))
+}
+
+fn gps_position(tiff: &Tiff, gps: &[Entry]) -> Option<GpsPosition> {
+    let coordinate = |tag, ref_tag, negative| {
+        let dms = tiff.rationals(tiff::find(gps, tag)?);
+        let part = |i: usize| dms.get(i).copied().filter(|v| v.is_finite()).unwrap_or(0.0);
+        let degrees = dms.first().filter(|v| v.is_finite())? + part(1) / 60.0 + part(2) / 3600.0;
+        let reference = tiff::find(gps, ref_tag).and_then(|e| tiff.ascii(e));
+        Some(if reference == Some(negative) {
+            -degrees
+        } else {
+            degrees
+        })
+    };
+    let below_sea_level = tiff::find(gps, GPS_ALTITUDE_REF)
+        .and_then(|e| tiff.bytes(e))
+        .is_some_and(|b| b.first() == Some(&1));
+    let altitude = tiff::find(gps, GPS_ALTITUDE)
+        .and_then(|e| tiff.rationals(e).first().copied())
+        .filter(|v| v.is_finite())
+        .map(|v| if below_sea_level { -v } else { v });
+
+    Some(GpsPosition {
+        latitude: coordinate(GPS_LATITUDE, GPS_LATITUDE_REF, "S")?,
+        longitude: coordinate(GPS_LONGITUDE, GPS_LONGITUDE_REF, "W")?,
+        altitude,
+    })
+}
+
+// Fills the fields still unset from the XMP copies of the EXIF tags.
+fn apply_xmp(meta: &mut Metadata, xmp: &str) {
+    let make = xmp_property(xmp, "tiff:Make");
+    meta.make = meta.make.take().or(make.map(str::to_string));
+    let model = xmp_property(xmp, "tiff:Model");
+    meta.model = meta.model.take().or(model.map(str::to_string));
+    let orientation = xmp_property(xmp, "tiff:Orientation").and_then(|o| o.parse().ok());
+    meta.orientation = meta
+        .orientation
+        .or(orientation.filter(|o| (1..=8).contains(o)));
+    let time = [
+        "exif:DateTimeOriginal",
+        "xmp:CreateDate",
+        "photoshop:DateCreated",
+    ]
+    .iter()
+    .find_map(|name| xmp_property(xmp, name));
+    meta.capture_time = meta.capture_time.take().or(time.map(str::to_string));
+
+    let coordinate = |name| xmp_coordinate(xmp_property(xmp, name)?);
+    if let (None, Some(latitude), Some(longitude)) = (
+        meta.gps,
+        coordinate("exif:GPSLatitude"),
+        coordinate("exif:GPSLongitude"),
+    ) {
+        let below_sea_level = xmp_property(xmp, "exif:GPSAltitudeRef") == Some("1");
+        let altitude = xmp_property(xmp, "exif:GPSAltitude")
+            .and_then(xmp_rational)
+            .map(|v| if below_sea_level { -v } else { v });
+        meta.gps = Some(GpsPosition {
+            latitude,
+            longitude,
+            altitude,
+        });
+    }
+}
+
+// A simple property, written either as an attribute or as an element.
+pub fn xmp_property<'a>(xmp: &'a str, name: &str) -> Option<&'a str> {
+    let value = if let Some(i) = xmp.find(&format!("{name}=")) {
+        let rest = &xmp[i + name.len() + 1..];
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let rest = &rest[1..];
+        &rest[..rest.find(quote)?]
+    } else {
+        let open = format!("<{name}>");
+        let rest = &xmp[xmp.find(&open)? + open.len()..];
+        &rest[..rest.find('<')?]
+    };
+    Some(value.trim()).filter(|v| !v.is_empty())
+}
+
+// `DDD,MM,SSk` or `DDD,MM.mmk`, k being N, S, E or W.
+fn xmp_coordinate(value: &str) -> Option<f64> {
+    let (value, sign) = match value.strip_suffix(&['S', 'W'][..]) {
+        Some(v) => (v, -1.0),
+        None => (value.strip_suffix(&['N', 'E'][..])?, 1.0),
+    };
+    let mut degrees = 0.0;
+    for (i, part) in value.split(',').enumerate().take(3) {
+        degrees += part.trim().parse::<f64>().ok()? / 60_f64.powi(i as i32);
+    }
+    Some(sign * degrees)
+}
+
+fn xmp_rational(value: &str) -> Option<f64> {
+    let v = match value.split_once('/') {
+        Some((n, d)) => n.trim().parse::<f64>().ok()? / d.trim().parse::<f64>().ok()?,
+        None => value.parse().ok()?,
+    };
+    v.is_finite().then_some(v)
+}
+
+// The `desc` tag of an ICC profile: ASCII text in version 2 profiles, localized UTF-16
+// strings (`mluc`) in version 4, of which the first is taken.
+fn icc_description(icc: &[u8]) -> Option<String> {
+    let count = be32(icc, 128)? as usize;
+    let tag = (0..count.min(MAX_ICC_TAGS))
+        .filter_map(|i| icc.get(132 + i * 12..144 + i * 12))
+        .find(|t| &t[..4] == b"desc")?;
+    let start = be32(tag, 4)? as usize;
+    let data = icc.get(start..start.checked_add(be32(tag, 8)? as usize)?)?;
+
+    let text = match data.get(..4)? {
+        b"desc" => {
+            let len = be32(data, 8)? as usize;
+            let ascii = data.get(12..)?.get(..len)?;
+            String::from_utf8_lossy(ascii).into_owned()
+        }
+        b"mluc" => {
+            // The first record: language and country, length and offset.
+            let (len, at) = (be32(data, 20)? as usize, be32(data, 24)? as usize);
+            let utf16: Vec<u16> = data
+                .get(at..)?
+                .get(..len)?
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&utf16)
+        }
+        _ => return None,
+    };
+    let text = text.trim_end_matches('\0').trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+// A big-endian EXIF block with IFD0, an EXIF IFD and a GPS IFD.
+#[cfg(test)]
+fn build_exif() -> Vec<u8> {
+    let mut b = b"MM\0\x2A\0\0\0\x08".to_vec();
+    let entry = |b: &mut Vec<u8>, tag: u16, kind: u16, count: u32, value: u32| {
+        b.extend_from_slice(&tag.to_be_bytes());
+        b.extend_from_slice(&kind.to_be_bytes());
+        b.extend_from_slice(&count.to_be_bytes());
+        b.extend_from_slice(&value.to_be_bytes());
+    };
+    // IFD0 at 8 with 4 entries ends at 62; the data follows.
+    b.extend_from_slice(&4_u16.to_be_bytes());
+    entry(&mut b, tiff::TAG_MAKE, 2, 6, 62);
+    entry(&mut b, tiff::TAG_ORIENTATION, 3, 1, 6 << 16);
+    entry(&mut b, tiff::TAG_EXIF_IFD, 4, 1, 68);
+    entry(&mut b, tiff::TAG_GPS_IFD, 4, 1, 126);
+    b.extend_from_slice(&[0; 4]);
+    b.extend_from_slice(b"Canon\0");
+    // The EXIF IFD at 68 with 2 entries ends at 98.
+    b.extend_from_slice(&2_u16.to_be_bytes());
+    entry(&mut b, tiff::TAG_DATE_TIME_ORIGINAL, 2, 20, 98);
+    entry(&mut b, tiff::TAG_OFFSET_TIME_ORIGINAL, 2, 7, 118);
+    b.extend_from_slice(&[0; 4]);
+    b.extend_from_slice(b"2024:03:09 14:05:00\0+09:00\0\0");
+    // The GPS IFD at 126 with 4 entries ends at 180.
+    b.extend_from_slice(&4_u16.to_be_bytes());
+    entry(
+        &mut b,
+        GPS_LATITUDE_REF,
+        2,
+        2,
+        u32::from_be_bytes(*b"S\0\0\0"),
+    );
+    entry(&mut b, GPS_LATITUDE, 5, 3, 180);
+    entry(
+        &mut b,
+        GPS_LONGITUDE_REF,
+        2,
+        2,
+        u32::from_be_bytes(*b"E\0\0\0"),
+    );
+    entry(&mut b, GPS_LONGITUDE, 5, 3, 204);
+    b.extend_from_slice(&[0; 4]);
+    for v in [33, 1, 52, 1, 30, 1, 151, 1, 12, 1, 0, 0] {
+        b.extend_from_slice(&(v as u32).to_be_bytes());
+    }
+    b
+}
+
+#[test]
+fn test_metadata_exif_jpeg() {
+    let exif = build_exif();
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend_from_slice(&((exif.len() + 8) as u16).to_be_bytes());
+    jpeg.extend_from_slice(EXIF_HEADER);
+    jpeg.extend_from_slice(&exif);
+    jpeg.extend_from_slice(&[0xFF, 0xDA, 0, 2]);
+
+    let meta = parse(&jpeg).unwrap();
+    assert!(meta.exif && !meta.xmp && !meta.icc);
+    assert_eq!(Some("Canon"), meta.make.as_deref());
+    assert_eq!(Some(6), meta.orientation);
+    assert_eq!(
+        Some("2024-03-09T14:05:00+09:00"),
+        meta.capture_time.as_deref()
+    );
+    let gps = meta.gps.unwrap();
+    let round = |v: f64| (v * 1e6).round() / 1e6;
+    // Zero denominators are read as zero.
+    assert_eq!(
+        (-33.875, 151.2),
+        (round(gps.latitude), round(gps.longitude))
+    );
+    assert_eq!(None, gps.altitude);
+}
+
+#[test]
+fn test_metadata_xmp_icc() {
+    let xmp = r#"<rdf:Description tiff:Make="Apple" tiff:Orientation="3"
+        exif:GPSLatitude="48,51.5N" exif:GPSLongitude="2,21,0W">
+        <xmp:CreateDate>2021-06-01T10:00:00</xmp:CreateDate></rdf:Description>"#;
+    let mut meta = Metadata::default();
+    apply_xmp(&mut meta, xmp);
+    assert_eq!(Some("Apple"), meta.make.as_deref());
+    assert_eq!(Some(3), meta.orientation);
+    assert_eq!(Some("2021-06-01T10:00:00"), meta.capture_time.as_deref());
+    let gps = meta.gps.unwrap();
+    let round = |v: f64| (v * 1e6).round() / 1e6;
+    assert_eq!(
+        (48.858333, -2.35),
+        (round(gps.latitude), round(gps.longitude))
+    );
+
+    // A version 2 profile whose only tag is `desc`.
+    let mut icc = vec![0; 128];
+    icc.extend_from_slice(&[0, 0, 0, 1]);
+    icc.extend_from_slice(b"desc\0\0\0\x90\0\0\0\x16");
+    icc.extend_from_slice(b"desc\0\0\0\0\0\0\0\x0AsRGB v2\0\0\0");
+    assert_eq!(Some("sRGB v2".to_string()), icc_description(&icc));
+}
@@ -1,5 +1,33 @@
+pub mod asf;
+pub mod codecs;
 mod common;
+pub mod data;
 pub mod document;
+pub mod ebml;
+pub mod elf;
+pub mod font;
+pub mod gzip;
 pub mod image;
+pub mod isobmff;
+pub mod macho;
+pub mod markup;
+pub mod metadata;
+pub mod movie;
+pub mod mpeg;
+pub mod mpegts;
+pub mod ogg;
+pub mod pdf;
+pub mod pe;
+pub mod raster;
+pub mod riff;
+pub mod sound;
+pub mod source;
+pub mod startcode;
+pub mod syncframe;
+pub mod text;
+pub mod tiff;
 
-pub use common::{bigendian_bytes, bytes_index, bytes_to_str, compare_bytes, littleendian_bytes};
+pub use common::{
+    be16, be32, bigendian_bytes, bytes_index, bytes_to_str, compare_bytes, le16, le32,
+    littleendian_bytes,
+};
@@ -0,0 +1,609 @@
+// Duration, picture size, frame rate and tracks of video containers, from their headers:
+// `moov` of ISO BMFF and QuickTime, Info and Tracks of Matroska, `hdrl` of AVI, the
+// `onMetaData` script tag of FLV and the Header Object of ASF.
+// ref: ISO/IEC 14496-12, 8.2.2 (mvhd), 8.3.2 (tkhd), 8.4.2 (mdhd), 8.6.1.2 (stts)
+// ref: https://learn.microsoft.com/en-us/windows/win32/directshow/avi-riff-file-reference
+// ref: Adobe Flash Video File Format Specification 10.1, E.4.4 (SCRIPTDATA) and AMF0
+// ref: Advanced Systems Format (ASF) Specification, 3.2 (File Properties) and 3.3
+use super::{asf, be16, be32, ebml, image::is_iso_bmf, isobmff, le16, le32, riff};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Subtitle,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VideoTrack {
+    pub kind: TrackKind,
+    // The sample entry of MP4 (`avc1`), the CodecID of Matroska (`V_VP9`), the FourCC or
+    // hexadecimal WAVE format tag of AVI and ASF (`H264`, `0x0055`), the codec of FLV.
+    pub codec: Option<String>,
+    // ISO 639-2 (`eng`), or BCP 47 where Matroska gives it.
+    pub language: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VideoInfo {
+    pub duration: Option<Duration>,
+    // The picture size and frame rate of the first video track.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub tracks: Vec<VideoTrack>,
+}
+
+// Script tags inspected before giving up on `onMetaData`, and the nesting of AMF values.
+const MAX_FLV_TAGS: usize = 8;
+const MAX_AMF_DEPTH: usize = 8;
+
+pub fn parse(buf: &[u8]) -> Option<VideoInfo> {
+    match buf.get(..4)? {
+        b"RIFF" => avi(buf),
+        [b'F', b'L', b'V', 1] => flv(buf),
+        [0x1A, 0x45, 0xDF, 0xA3] => matroska(buf),
+        [0x30, 0x26, 0xB2, 0x75] => asf(buf),
+        // QuickTime files may start with any atom.
+        _ if is_iso_bmf(buf) || isobmff::find(buf, &[b"moov"]).is_some() => mp4(buf),
+        _ => None,
+    }
+}
+
+fn fourcc(b: &[u8]) -> Option<String> {
+    let b = b.get(..4)?;
+    (b.iter().all(|c| (0x20..=0x7E).contains(c)) && b != b"    ")
+        .then(|| String::from_utf8_lossy(b).into_owned())
+}
+
+fn ticks_duration(ticks: u64, per_second: u32) -> Option<Duration> {
+    (per_second > 0)
+        .then(|| Duration::from_nanos((ticks as u128 * 1_000_000_000 / per_second as u128) as u64))
+}
+
+fn first_video<T>(tracks: &[(VideoTrack, T)]) -> Option<&T> {
+    tracks
+        .iter()
+        .find(|(t, _)| t.kind == TrackKind::Video)
+        .map(|(_, v)| v)
+}
+
+// The timescale and duration of `mvhd` or `mdhd`, whose version 1 widens the times.
+fn timing(full_box: &[u8]) -> Option<(u32, u64)> {
+    match full_box.first()? {
+        1 => Some((
+            be32(full_box, 20)?,
+            u64::from_be_bytes(full_box.get(24..32)?.try_into().ok()?),
+        )),
+        _ => Some((be32(full_box, 12)?, be32(full_box, 16)? as u64)),
+    }
+}
+
+// A packed ISO 639-2/T code; values below 0x400 are Macintosh language codes.
+fn mp4_language(code: u16) -> Option<String> {
+    if code < 0x400 {
+        return None;
+    }
+    let language: String = [10, 5, 0]
+        .iter()
+        .map(|shift| ((code >> shift) & 0x1F) as u8 + 0x60)
+        .map(char::from)
+        .collect();
+    (language.chars().all(|c| c.is_ascii_lowercase()) && language != "und").then_some(language)
+}
+
+fn mp4(buf: &[u8]) -> Option<VideoInfo> {
+    let moov = isobmff::find(buf, &[b"moov"])?;
+    let (timescale, length) = timing(isobmff::find(moov.data, &[b"mvhd"])?.data)?;
+
+    let mut tracks = vec![];
+    for trak in moov.children().iter().filter(|b| &b.kind == b"trak") {
+        let Some(mdia) = isobmff::find(trak.data, &[b"mdia"]) else {
+            continue;
+        };
+        let kind = match isobmff::find(mdia.data, &[b"hdlr"]).and_then(|h| h.data.get(8..12)) {
+            Some(b"vide") => TrackKind::Video,
+            Some(b"soun") => TrackKind::Audio,
+            Some(b"sbtl" | b"subt" | b"text" | b"clcp") => TrackKind::Subtitle,
+            _ => TrackKind::Other,
+        };
+        let mdhd = isobmff::find(mdia.data, &[b"mdhd"]).map(|b| b.data);
+        let language = mdhd
+            .and_then(|m| match m.first()? {
+                1 => be16(m, 32),
+                _ => be16(m, 20),
+            })
+            .and_then(mp4_language);
+
+        let stbl = isobmff::find(mdia.data, &[b"minf", b"stbl"]);
+        let entry = stbl
+            .as_ref()
+            .and_then(|s| isobmff::find(s.data, &[b"stsd"]))
+            .and_then(|stsd| isobmff::boxes(stsd.data.get(8..)?).into_iter().next());
+
+        // The presentation size of `tkhd` (16.16 fixed point), or the coded size of a
+        // visual sample entry.
+        let tkhd = isobmff::find(trak.data, &[b"tkhd"]).map(|b| b.data);
+        let size = tkhd
+            .and_then(|t| match t.first()? {
+                1 => Some((be32(t, 88)?, be32(t, 92)?)),
+                _ => Some((be32(t, 76)?, be32(t, 80)?)),
+            })
+            .map(|(w, h)| (w >> 16, h >> 16))
+            .filter(|(w, h)| *w > 0 && *h > 0)
+            .or_else(|| {
+                let e = entry.as_ref()?.data;
+                Some((be16(e, 24)?.into(), be16(e, 26)?.into()))
+            });
+
+        // Samples over the summed durations of the time-to-sample table.
+        let frame_rate = stbl
+            .as_ref()
+            .and_then(|s| isobmff::find(s.data, &[b"stts"]))
+            .and_then(|stts| {
+                let (track_timescale, _) = timing(mdhd?)?;
+                let count = be32(stts.data, 4)? as usize;
+                // Tables whose durations overflow are corrupt.
+                let (samples, ticks) = stts.data.get(8..)?.chunks_exact(8).take(count).try_fold(
+                    (0_u64, 0_u64),
+                    |(s, t), e| {
+                        let n = be32(e, 0)? as u64;
+                        let delta = be32(e, 4)? as u64;
+                        Some((s.checked_add(n)?, t.checked_add(n.checked_mul(delta)?)?))
+                    },
+                )?;
+                (ticks > 0).then(|| samples as f64 * track_timescale as f64 / ticks as f64)
+            });
+
+        tracks.push((
+            VideoTrack {
+                kind,
+                codec: entry.and_then(|e| fourcc(&e.kind)),
+                language,
+            },
+            (size, frame_rate),
+        ));
+    }
+
+    let (size, frame_rate) = first_video(&tracks).copied().unwrap_or_default();
+    Some(VideoInfo {
+        duration: ticks_duration(length, timescale),
+        width: size.map(|s| s.0),
+        height: size.map(|s| s.1),
+        frame_rate,
+        tracks: tracks.into_iter().map(|(t, _)| t).collect(),
+    })
+}
+
+fn matroska(buf: &[u8]) -> Option<VideoInfo> {
+    let doc = ebml::parse(buf)?;
+    let tracks: Vec<_> = doc
+        .tracks
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| {
+            let kind = match t.track_type {
+                ebml::TRACK_TYPE_VIDEO => TrackKind::Video,
+                ebml::TRACK_TYPE_AUDIO => TrackKind::Audio,
+                ebml::TRACK_TYPE_SUBTITLE => TrackKind::Subtitle,
+                _ => TrackKind::Other,
+            };
+            let track = VideoTrack {
+                kind,
+                codec: t.codec_id.map(str::to_string),
+                language: Some(t.language).filter(|l| *l != "und").map(str::to_string),
+            };
+            let size = t
+                .pixel_width
+                .zip(t.pixel_height)
+                .map(|(w, h)| (w as u32, h as u32));
+            let frame_rate = t
+                .default_duration
+                .filter(|d| *d > 0)
+                .map(|d| 1e9 / d as f64);
+            (track, (size, frame_rate))
+        })
+        .collect();
+
+    let (size, frame_rate) = first_video(&tracks).copied().unwrap_or_default();
+    Some(VideoInfo {
+        duration: doc.duration,
+        width: size.map(|s| s.0),
+        height: size.map(|s| s.1),
+        frame_rate,
+        tracks: tracks.into_iter().map(|(t, _)| t).collect(),
+    })
+}
+
+fn avi(buf: &[u8]) -> Option<VideoInfo> {
+    let riff = riff::parse(buf)?;
+    if &riff.form != b"AVI " {
+        return None;
+    }
+    let header = riff.find_list(b"hdrl")?.children();
+    let avih = header.iter().find(|c| &c.id == b"avih")?.data.get(..40)?;
+
+    let mut tracks = vec![];
+    for strl in header.iter().filter(|c| c.list_type() == Some(b"strl")) {
+        let chunks = strl.children();
+        let Some(strh) = chunks
+            .iter()
+            .find(|c| &c.id == b"strh")
+            .and_then(|c| c.data.get(..36))
+        else {
+            continue;
+        };
+        let strf = chunks.iter().find(|c| &c.id == b"strf").map(|c| c.data);
+
+        let (kind, codec) = match &strh[..4] {
+            // The BITMAPINFOHEADER compression, or the handler for uncompressed video.
+            b"vids" => (
+                TrackKind::Video,
+                strf.and_then(|f| f.get(16..20))
+                    .filter(|c| *c != [0; 4])
+                    .and_then(fourcc)
+                    .or_else(|| fourcc(&strh[4..8])),
+            ),
+            b"auds" => (
+                TrackKind::Audio,
+                strf.and_then(|f| le16(f, 0))
+                    .map(|tag| format!("0x{:04X}", tag)),
+            ),
+            b"txts" => (TrackKind::Subtitle, fourcc(&strh[4..8])),
+            _ => (TrackKind::Other, fourcc(&strh[4..8])),
+        };
+        let (scale, rate, length) = (le32(strh, 20)?, le32(strh, 24)?, le32(strh, 32)?);
+        let timing = (scale > 0 && rate > 0).then_some((scale, rate, length));
+        tracks.push((
+            VideoTrack {
+                kind,
+                codec,
+                language: None,
+            },
+            timing,
+        ));
+    }
+
+    // The video stream's rate is exact; the main header rounds to microseconds.
+    let (frame_rate, duration) = match first_video(&tracks).copied().flatten() {
+        Some((scale, rate, length)) => (
+            Some(rate as f64 / scale as f64),
+            ticks_duration(length as u64 * scale as u64, rate),
+        ),
+        None => (
+            le32(avih, 0).filter(|us| *us > 0).map(|us| 1e6 / us as f64),
+            Some(Duration::from_micros(
+                le32(avih, 16)? as u64 * le32(avih, 0)? as u64,
+            )),
+        ),
+    };
+
+    Some(VideoInfo {
+        duration,
+        width: le32(avih, 32),
+        height: le32(avih, 36),
+        frame_rate,
+        tracks: tracks.into_iter().map(|(t, _)| t).collect(),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Amf {
+    Number(f64),
+    String(String),
+    // Booleans, nested objects and arrays, dates and nulls.
+    Other,
+}
+
+// An AMF0 value at `offset` and the offset past it.
+fn amf_value(buf: &[u8], offset: usize, depth: usize) -> Option<(Amf, usize)> {
+    let string = |at: usize, len: usize| {
+        let s = buf.get(at..at + len)?;
+        Some((
+            Amf::String(String::from_utf8_lossy(s).into_owned()),
+            at + len,
+        ))
+    };
+    if depth > MAX_AMF_DEPTH {
+        return None;
+    }
+    let at = offset + 1;
+    match *buf.get(offset)? {
+        0 => Some((
+            Amf::Number(f64::from_be_bytes(buf.get(at..at + 8)?.try_into().ok()?)),
+            at + 8,
+        )),
+        1 => Some((Amf::Other, at + 1)),
+        2 => string(at + 2, be16(buf, at)? as usize),
+        3 => Some((Amf::Other, amf_properties(buf, at, depth, |_, _| {})?)),
+        5 | 6 => Some((Amf::Other, at)),
+        7 => Some((Amf::Other, at + 2)),
+        8 => Some((Amf::Other, amf_properties(buf, at + 4, depth, |_, _| {})?)),
+        10 => {
+            let count = be32(buf, at)?;
+            let mut next = at + 4;
+            for _ in 0..count {
+                next = amf_value(buf, next, depth + 1)?.1;
+            }
+            Some((Amf::Other, next))
+        }
+        11 => Some((Amf::Other, at + 10)),
+        12 => string(at + 4, be32(buf, at)? as usize),
+        _ => None,
+    }
+}
+
+// Name/value pairs up to the object end marker, passed to `f`; the offset past the marker.
+fn amf_properties(
+    buf: &[u8],
+    mut offset: usize,
+    depth: usize,
+    mut f: impl FnMut(&[u8], Amf),
+) -> Option<usize> {
+    loop {
+        let len = be16(buf, offset)? as usize;
+        if len == 0 && buf.get(offset + 2) == Some(&9) {
+            return Some(offset + 3);
+        }
+        let name = buf.get(offset + 2..offset + 2 + len)?;
+        let (value, next) = amf_value(buf, offset + 2 + len, depth + 1)?;
+        f(name, value);
+        offset = next;
+    }
+}
+
+fn flv_codec(value: &Amf, video: bool) -> Option<String> {
+    let id = match value {
+        Amf::Number(n) => *n as u32,
+        // Enhanced RTMP writes the FourCC.
+        Amf::String(s) => return Some(s.clone()),
+        Amf::Other => return None,
+    };
+    let name = match (video, id) {
+        (true, 2) => "h263",
+        (true, 3) => "screen",
+        (true, 4) => "vp6",
+        (true, 5) => "vp6a",
+        (true, 6) => "screen2",
+        (true, 7) => "avc",
+        (true, 12) => "hevc",
+        (false, 0 | 3) => "pcm",
+        (false, 1) => "adpcm",
+        (false, 2 | 14) => "mp3",
+        (false, 4..=6) => "nellymoser",
+        (false, 7) => "g711a",
+        (false, 8) => "g711u",
+        (false, 10) => "aac",
+        (false, 11) => "speex",
+        _ => return Some(id.to_string()),
+    };
+    Some(name.to_string())
+}
+
+fn flv(buf: &[u8]) -> Option<VideoInfo> {
+    let flags = *buf.get(4)?;
+    let mut offset = be32(buf, 5)? as usize + 4;
+    let mut metadata: Vec<(Vec<u8>, Amf)> = vec![];
+    for _ in 0..MAX_FLV_TAGS {
+        let Some(tag) = buf.get(offset..offset + 11) else {
+            break;
+        };
+        let size = (tag[1] as usize) << 16 | (tag[2] as usize) << 8 | tag[3] as usize;
+        let data = &buf[offset + 11..(offset + 11 + size).min(buf.len())];
+        if tag[0] & 0x1F == 18 {
+            if let Some((Amf::String(name), next)) = amf_value(data, 0, 0) {
+                if name == "onMetaData" {
+                    let start = match data.get(next) {
+                        Some(8) => next + 5,
+                        Some(3) => next + 1,
+                        _ => break,
+                    };
+                    amf_properties(data, start, 1, |k, v| metadata.push((k.to_vec(), v)));
+                    break;
+                }
+            }
+        }
+        offset += 11 + size + 4;
+    }
+
+    let get = |key: &str| {
+        metadata
+            .iter()
+            .find(|(k, _)| k == key.as_bytes())
+            .map(|(_, v)| v)
+    };
+    let number = |key| match get(key) {
+        Some(Amf::Number(n)) if n.is_finite() && *n > 0.0 => Some(*n),
+        _ => None,
+    };
+
+    let mut tracks = vec![];
+    if flags & 0x01 != 0 {
+        tracks.push(VideoTrack {
+            kind: TrackKind::Video,
+            codec: get("videocodecid").and_then(|v| flv_codec(v, true)),
+            language: None,
+        });
+    }
+    if flags & 0x04 != 0 {
+        tracks.push(VideoTrack {
+            kind: TrackKind::Audio,
+            codec: get("audiocodecid").and_then(|v| flv_codec(v, false)),
+            language: None,
+        });
+    }
+    Some(VideoInfo {
+        duration: number("duration").and_then(|d| Duration::try_from_secs_f64(d).ok()),
+        width: number("width").map(|w| w as u32),
+        height: number("height").map(|h| h as u32),
+        frame_rate: number("framerate"),
+        tracks,
+    })
+}
+
+fn asf(buf: &[u8]) -> Option<VideoInfo> {
+    let objects = asf::header_objects(buf)?;
+
+    // The play duration in 100 ns units includes the preroll, in milliseconds. Both are
+    // left at zero while broadcasting, which the first flag bit marks.
+    let duration = objects
+        .iter()
+        .find(|o| o.guid == asf::FILE_PROPERTIES)
+        .and_then(|o| o.body.get(40..68))
+        .filter(|p| p[24] & 0x01 == 0)
+        .map(|p| {
+            let play = u64::from_le_bytes(p[0..8].try_into().unwrap());
+            let preroll = u64::from_le_bytes(p[16..24].try_into().unwrap());
+            Duration::from_nanos(play.saturating_mul(100))
+                .saturating_sub(Duration::from_millis(preroll))
+        })
+        .filter(|d| !d.is_zero());
+
+    let mut tracks = vec![];
+    for object in objects.iter().filter(|o| o.guid == asf::STREAM_PROPERTIES) {
+        let Some(stream_type) = object.body.get(..16) else {
+            continue;
+        };
+        // Type-specific data: image size, flags and a BITMAPINFOHEADER for video; a
+        // WAVEFORMATEX for audio.
+        let specific = object.body.get(54..).unwrap_or_default();
+        let (kind, codec, size) = if stream_type == asf::VIDEO_MEDIA {
+            let size = le32(specific, 0).zip(le32(specific, 4));
+            (
+                TrackKind::Video,
+                specific.get(27..31).and_then(fourcc),
+                size,
+            )
+        } else if stream_type == asf::AUDIO_MEDIA {
+            let tag = le16(specific, 0).map(|t| format!("0x{:04X}", t));
+            (TrackKind::Audio, tag, None)
+        } else {
+            (TrackKind::Other, None, None)
+        };
+        tracks.push((
+            VideoTrack {
+                kind,
+                codec,
+                language: None,
+            },
+            size,
+        ));
+    }
+
+    let size = first_video(&tracks).copied().flatten();
+    Some(VideoInfo {
+        duration,
+        width: size.map(|s| s.0),
+        height: size.map(|s| s.1),
+        frame_rate: None,
+        tracks: tracks.into_iter().map(|(t, _)| t).collect(),
+    })
+}
+
+#[test]
+fn test_movie_avi() {
+    let chunk = |id: &[u8], data: &[u8]| {
+        let mut c = id.to_vec();
+        c.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        c.extend_from_slice(data);
+        c
+    };
+    let list = |kind: &[u8], body: &[u8]| chunk(b"LIST", &[kind, body].concat());
+
+    let mut avih = vec![0; 40];
+    avih[32..36].copy_from_slice(&640_u32.to_le_bytes());
+    avih[36..40].copy_from_slice(&480_u32.to_le_bytes());
+    // 30000/1001 fps, 300 frames.
+    let mut strh = b"vidsH264".to_vec();
+    strh.resize(56, 0);
+    strh[20..24].copy_from_slice(&1001_u32.to_le_bytes());
+    strh[24..28].copy_from_slice(&30000_u32.to_le_bytes());
+    strh[32..36].copy_from_slice(&300_u32.to_le_bytes());
+    let mut bih = vec![0; 40];
+    bih[16..20].copy_from_slice(b"avc1");
+    let mut auds = b"auds".to_vec();
+    auds.resize(56, 0);
+
+    let video = list(
+        b"strl",
+        &[chunk(b"strh", &strh), chunk(b"strf", &bih)].concat(),
+    );
+    let audio = list(
+        b"strl",
+        &[chunk(b"strh", &auds), chunk(b"strf", &[0x55, 0])].concat(),
+    );
+    let hdrl = list(b"hdrl", &[chunk(b"avih", &avih), video, audio].concat());
+    let avi = [&b"RIFF\0\0\0\0AVI "[..], &hdrl].concat();
+
+    let info = parse(&avi).unwrap();
+    assert_eq!((Some(640), Some(480)), (info.width, info.height));
+    assert_eq!(Some(10.01), info.duration.map(|d| d.as_secs_f64()));
+    assert!((info.frame_rate.unwrap() - 29.97).abs() < 0.01);
+    assert_eq!(Some("avc1"), info.tracks[0].codec.as_deref());
+    assert_eq!(TrackKind::Audio, info.tracks[1].kind);
+    assert_eq!(Some("0x0055"), info.tracks[1].codec.as_deref());
+}
+
+#[test]
+fn test_movie_flv() {
+    let mut flv = b"FLV\x01\x05\0\0\0\x09\0\0\0\0".to_vec();
+    let mut script = b"\x02\0\x0AonMetaData\x08\0\0\0\x04".to_vec();
+    let mut number = |key: &str, value: f64| {
+        script.extend_from_slice(&(key.len() as u16).to_be_bytes());
+        script.extend_from_slice(key.as_bytes());
+        script.push(0);
+        script.extend_from_slice(&value.to_be_bytes());
+    };
+    number("duration", 12.5);
+    number("width", 1280.0);
+    number("height", 720.0);
+    number("videocodecid", 7.0);
+    script.extend_from_slice(&[0, 0, 9]);
+    flv.extend_from_slice(&[18, 0, 0, script.len() as u8, 0, 0, 0, 0, 0, 0, 0]);
+    flv.extend(script);
+
+    let info = parse(&flv).unwrap();
+    assert_eq!(Some(Duration::from_millis(12500)), info.duration);
+    assert_eq!((Some(1280), Some(720)), (info.width, info.height));
+    assert_eq!(2, info.tracks.len());
+    assert_eq!(Some("avc"), info.tracks[0].codec.as_deref());
+    assert_eq!(None, info.tracks[1].codec);
+}
+
+#[test]
+fn test_movie_mp4_stts() {
+    let atom = |kind: &[u8], data: &[u8]| {
+        let mut a = ((data.len() + 8) as u32).to_be_bytes().to_vec();
+        a.extend_from_slice(kind);
+        a.extend_from_slice(data);
+        a
+    };
+    let mp4 = |stts: &[(u32, u32)]| {
+        let mut table = vec![0; 4];
+        table.extend_from_slice(&(stts.len() as u32).to_be_bytes());
+        for (count, delta) in stts {
+            table.extend_from_slice(&count.to_be_bytes());
+            table.extend_from_slice(&delta.to_be_bytes());
+        }
+        // mvhd and mdhd version 0 with a 30000 timescale.
+        let mut header = vec![0; 24];
+        header[12..16].copy_from_slice(&30000_u32.to_be_bytes());
+        let mdia = [
+            atom(b"mdhd", &header),
+            atom(b"hdlr", b"\0\0\0\0\0\0\0\0vide"),
+            atom(b"minf", &atom(b"stbl", &atom(b"stts", &table))),
+        ]
+        .concat();
+        let moov = [atom(b"mvhd", &header), atom(b"trak", &atom(b"mdia", &mdia))].concat();
+        [atom(b"ftyp", b"isom\0\0\0\0isom"), atom(b"moov", &moov)].concat()
+    };
+
+    let info = parse(&mp4(&[(300, 1001)])).unwrap();
+    assert!((info.frame_rate.unwrap() - 29.97).abs() < 0.01);
+    // Sample counts and durations whose products overflow.
+    let info = parse(&mp4(&[(u32::MAX, u32::MAX), (u32::MAX, u32::MAX)])).unwrap();
+    assert_eq!(TrackKind::Video, info.tracks[0].kind);
+    assert_eq!(None, info.frame_rate);
+}
@@ -0,0 +1,357 @@
+// Raw MPEG audio streams: MPEG-1/2/2.5 Layer I-III frames and AAC in ADTS or LOAS
+// framing, optionally preceded by an ID3v2 tag. A lone sync word is too weak a
+// signature, so two consecutive frames must decode.
+// ref: http://www.mp3-tech.org/programmer/frame_header.html
+// ref: https://wiki.multimedia.cx/index.php/ADTS
+// ref: https://id3.org/id3v2.4.0-structure
+// ref: https://www.codeproject.com/Articles/8295/MPEG-Audio-Frame-Header (Xing, VBRI)
+// ref: http://gabriel.mp3-tech.org/mp3infotag.html (LAME)
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Mp1,
+    Mp2,
+    Mp3,
+    // AAC with Audio Data Transport Stream headers.
+    Adts,
+    // AAC in LATM, wrapped in the LOAS AudioSyncStream.
+    Loas,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Frame {
+    kind: Kind,
+    // Fields that must stay the same from one frame to the next.
+    format: u8,
+    len: usize,
+}
+
+// kbit/s by [version is MPEG-1][layer - 1][index - 1]
+const BITRATES: [[[u16; 14]; 3]; 2] = [
+    [
+        [
+            32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+        ],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ],
+    [
+        [
+            32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+        ],
+        [
+            32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+        ],
+        [
+            32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ],
+    ],
+];
+
+const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+const ADTS_SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+// What the first frame, and the VBR header that encoders put in place of its audio,
+// say about the whole stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stream {
+    pub kind: Kind,
+    pub sample_rate: u32,
+    pub channels: u16,
+    // bit/s: the average when a VBR header gives the stream size, otherwise that of the
+    // first frame, which is exact only for constant bitrate streams.
+    pub bitrate: Option<u32>,
+    // Decoded samples per channel, less the encoder delay and padding, when a VBR
+    // header counts the frames.
+    pub samples: Option<u64>,
+}
+
+// The number of frames expected before the signature is trusted.
+const MIN_FRAMES: usize = 2;
+
+pub fn identify(buf: &[u8]) -> Option<Kind> {
+    let start = match id3v2_len(buf) {
+        // The tag runs past the inspected bytes (large cover art is common), so the
+        // frames cannot be checked; ID3v2 is overwhelmingly used by MP3.
+        Some(len) if len >= buf.len() => return Some(Kind::Mp3),
+        Some(len) => len,
+        None => 0,
+    };
+
+    let first = frame(&buf[start..])?;
+    let mut offset = start + first.len;
+    for _ in 1..MIN_FRAMES {
+        // A stream that ends exactly on a frame boundary is complete. Past the end of the
+        // inspected bytes, an ID3v2 tag is taken as evidence enough.
+        if offset == buf.len() || (offset > buf.len() && start > 0) {
+            break;
+        }
+        match buf.get(offset..).and_then(frame) {
+            Some(next) if next.kind == first.kind && next.format == first.format => {
+                offset += next.len;
+            }
+            _ => return None,
+        }
+    }
+    Some(first.kind)
+}
+
+// Stream parameters of MPEG audio or ADTS; LOAS keeps them in its payload.
+pub fn stream(buf: &[u8]) -> Option<Stream> {
+    let start = id3v2_len(buf).unwrap_or(0);
+    match buf.get(start..)? {
+        h @ [0xFF, b1, ..] if b1 & 0xF6 == 0xF0 => adts_stream(h),
+        h @ [0xFF, b1, ..] if b1 & 0xE0 == 0xE0 => mpeg_stream(h),
+        _ => None,
+    }
+}
+
+fn mpeg_stream(frame: &[u8]) -> Option<Stream> {
+    let Frame { kind, .. } = mpeg_frame(frame)?;
+    let version = (frame[1] >> 3) & 0x03;
+    let mpeg1 = version == 3;
+    let sample_rate = match version {
+        3 => SAMPLE_RATES[((frame[2] >> 2) & 0x03) as usize],
+        2 => SAMPLE_RATES[((frame[2] >> 2) & 0x03) as usize] / 2,
+        _ => SAMPLE_RATES[((frame[2] >> 2) & 0x03) as usize] / 4,
+    };
+    let bitrate_index = (frame[2] >> 4) as usize;
+    let layer = 4 - ((frame[1] >> 1) & 0x03) as usize;
+    let bitrate = BITRATES[mpeg1 as usize][layer - 1][bitrate_index - 1] as u32 * 1000;
+    let channels = if frame[3] >> 6 == 3 { 1 } else { 2 };
+    let frame_samples: u64 = match (layer, mpeg1) {
+        (1, _) => 384,
+        (2, _) | (3, true) => 1152,
+        _ => 576,
+    };
+
+    // Xing (or Info, for CBR) follows the side information of a Layer III frame;
+    // VBRI sits at a fixed 32 bytes past the header.
+    let side_info = match (mpeg1, channels) {
+        (true, 1) | (false, 2) => 17,
+        (true, _) => 32,
+        (false, _) => 9,
+    };
+    let be32 = |at: usize| {
+        frame
+            .get(at..at + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as u64)
+    };
+    let xing = 4 + side_info;
+    let (frames, bytes, gap) = match frame.get(xing..xing + 4) {
+        Some(b"Xing" | b"Info") => {
+            let flags = be32(xing + 4)?;
+            let mut at = xing + 8;
+            let mut field = |flag: u64, len: usize| {
+                let value = (flags & flag != 0).then(|| be32(at)).flatten();
+                if flags & flag != 0 {
+                    at += len;
+                }
+                value
+            };
+            let frames = field(0x01, 4);
+            let bytes = field(0x02, 4);
+            field(0x04, 100);
+            field(0x08, 4);
+            // The LAME extension stores the encoder delay and padding as two 12-bit values.
+            let gap = frame
+                .get(at + 21..at + 24)
+                .filter(|_| frame[at..].starts_with(b"LAME") || frame[at..].starts_with(b"Lavc"))
+                .map(|g| {
+                    ((g[0] as u64) << 4 | (g[1] >> 4) as u64)
+                        + (((g[1] & 0x0F) as u64) << 8 | g[2] as u64)
+                });
+            (frames, bytes, gap)
+        }
+        _ if frame.get(36..40) == Some(b"VBRI") => (
+            be32(50),
+            be32(46),
+            frame
+                .get(42..44)
+                .map(|d| u16::from_be_bytes([d[0], d[1]]) as u64),
+        ),
+        _ => (None, None, None),
+    };
+
+    let samples = frames.map(|f| (f * frame_samples).saturating_sub(gap.unwrap_or(0)));
+    let average = match (frames, bytes) {
+        (Some(f), Some(b)) if f > 0 => {
+            Some((b * 8 * sample_rate as u64 / (f * frame_samples)) as u32)
+        }
+        _ => None,
+    };
+    Some(Stream {
+        kind,
+        sample_rate,
+        channels,
+        bitrate: average.or(Some(bitrate)),
+        samples,
+    })
+}
+
+fn adts_stream(frame: &[u8]) -> Option<Stream> {
+    adts_frame(frame)?;
+    // 0 leaves the channel layout to a program config element.
+    let channels = ((frame[2] & 0x01) << 2 | frame[3] >> 6) as u16;
+    Some(Stream {
+        kind: Kind::Adts,
+        sample_rate: ADTS_SAMPLE_RATES[((frame[2] >> 2) & 0x0F) as usize],
+        channels,
+        bitrate: None,
+        samples: None,
+    })
+}
+
+// Length of a leading ID3v2 tag, including its header and optional footer.
+fn id3v2_len(buf: &[u8]) -> Option<usize> {
+    if buf.len() < 10 || buf[..3] != *b"ID3" || buf[3] == 0xFF || buf[4] == 0xFF {
+        return None;
+    }
+    // The size is "syncsafe": four 7-bit bytes.
+    if buf[6..10].iter().any(|b| b & 0x80 != 0) {
+        return None;
+    }
+    let size = buf[6..10]
+        .iter()
+        .fold(0_usize, |acc, b| acc << 7 | *b as usize);
+    let footer = if buf[5] & 0x10 != 0 { 10 } else { 0 };
+    Some(10 + size + footer)
+}
+
+fn frame(buf: &[u8]) -> Option<Frame> {
+    match buf {
+        [0xFF, b1, ..] if b1 & 0xF6 == 0xF0 => adts_frame(buf),
+        [0xFF, b1, ..] if b1 & 0xE0 == 0xE0 => mpeg_frame(buf),
+        [0x56, b1, ..] if b1 & 0xE0 == 0xE0 => loas_frame(buf),
+        _ => None,
+    }
+}
+
+fn mpeg_frame(buf: &[u8]) -> Option<Frame> {
+    let header = buf.get(..4)?;
+    let version = (header[1] >> 3) & 0x03;
+    let layer = (header[1] >> 1) & 0x03;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 0x03) as usize;
+    let padding = ((header[2] >> 1) & 0x01) as usize;
+
+    // Reserved values; a bitrate index of 0 is "free format", whose frame length
+    // cannot be derived from the header.
+    if version == 1
+        || layer == 0
+        || bitrate_index == 0
+        || bitrate_index == 15
+        || sample_rate_index == 3
+        || header[3] & 0x03 == 2
+    {
+        return None;
+    }
+
+    let mpeg1 = version == 3;
+    // Layer bits are 3 for Layer I down to 1 for Layer III.
+    let layer = 4 - layer as usize;
+    let bitrate = BITRATES[mpeg1 as usize][layer - 1][bitrate_index - 1] as usize * 1000;
+    let sample_rate = match version {
+        3 => SAMPLE_RATES[sample_rate_index],
+        2 => SAMPLE_RATES[sample_rate_index] / 2,
+        _ => SAMPLE_RATES[sample_rate_index] / 4,
+    } as usize;
+
+    let (kind, len) = match layer {
+        1 => (Kind::Mp1, (12 * bitrate / sample_rate + padding) * 4),
+        2 => (Kind::Mp2, 144 * bitrate / sample_rate + padding),
+        _ if mpeg1 => (Kind::Mp3, 144 * bitrate / sample_rate + padding),
+        _ => (Kind::Mp3, 72 * bitrate / sample_rate + padding),
+    };
+
+    Some(Frame {
+        kind,
+        format: header[1] & 0x1E | (sample_rate_index as u8) << 5,
+        len,
+    })
+}
+
+fn adts_frame(buf: &[u8]) -> Option<Frame> {
+    let header = buf.get(..7)?;
+    let sample_rate_index = (header[2] >> 2) & 0x0F;
+    // Indices 13 and up are reserved or mean an explicit rate, which ADTS cannot carry.
+    if sample_rate_index > 12 {
+        return None;
+    }
+
+    let header_len = if header[1] & 0x01 == 0 { 9 } else { 7 };
+    let len =
+        ((header[3] & 0x03) as usize) << 11 | (header[4] as usize) << 3 | (header[5] >> 5) as usize;
+    if len < header_len {
+        return None;
+    }
+
+    Some(Frame {
+        kind: Kind::Adts,
+        format: header[1] & 0x08 | sample_rate_index << 4,
+        len,
+    })
+}
+
+fn loas_frame(buf: &[u8]) -> Option<Frame> {
+    let header = buf.get(..3)?;
+    let len = ((header[1] & 0x1F) as usize) << 8 | header[2] as usize;
+    if len == 0 {
+        return None;
+    }
+
+    Some(Frame {
+        kind: Kind::Loas,
+        format: 0,
+        len: 3 + len,
+    })
+}
+
+#[cfg(test)]
+fn frames(header: [u8; 4], len: usize, count: usize) -> Vec<u8> {
+    let mut frame = header.to_vec();
+    frame.resize(len, 0);
+    frame.repeat(count)
+}
+
+#[test]
+fn test_mpeg_audio_frames() {
+    // MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, no CRC: 417 bytes.
+    let mp3 = frames([0xFF, 0xFB, 0x90, 0x00], 417, 3);
+    assert_eq!(Some(Kind::Mp3), identify(&mp3));
+    // One byte short: the second sync word is misplaced.
+    assert_eq!(None, identify(&frames([0xFF, 0xFB, 0x90, 0x00], 416, 3)));
+    // MPEG-2 Layer III with CRC, 64 kbit/s, 22.05 kHz: 208 bytes.
+    let mp3 = frames([0xFF, 0xF2, 0x80, 0x00], 208, 2);
+    assert_eq!(Some(Kind::Mp3), identify(&mp3));
+    // MPEG-1 Layer II, 192 kbit/s, 48 kHz: 576 bytes.
+    let mp2 = frames([0xFF, 0xFD, 0xA4, 0x00], 576, 2);
+    assert_eq!(Some(Kind::Mp2), identify(&mp2));
+    // MPEG-1 Layer I, 384 kbit/s, 48 kHz: 384 bytes.
+    let mp1 = frames([0xFF, 0xFF, 0xC4, 0x00], 384, 2);
+    assert_eq!(Some(Kind::Mp1), identify(&mp1));
+    // A lone sync word followed by junk.
+    assert_eq!(None, identify(&[0xFF, 0xFB, 0x90, 0x00, 0x12, 0x34]));
+}
+
+#[test]
+fn test_mpeg_aac_and_id3() {
+    // ADTS, MPEG-4 AAC LC, 44.1 kHz, stereo, 16-byte frames.
+    let mut adts = [0xFF, 0xF1, 0x50, 0x80, 0x02, 0x1F, 0xFC].to_vec();
+    adts.resize(16, 0);
+    assert_eq!(Some(Kind::Adts), identify(&adts.repeat(2)));
+
+    let mut loas = [0x56, 0xE0, 0x05].to_vec();
+    loas.resize(8, 0);
+    assert_eq!(Some(Kind::Loas), identify(&loas.repeat(2)));
+
+    let mut tagged = b"ID3\x04\0\0\0\0\x01\x00".to_vec();
+    tagged.resize(10 + 128, 0);
+    tagged.extend(frames([0xFF, 0xFB, 0x90, 0x00], 417, 2));
+    assert_eq!(Some(Kind::Mp3), identify(&tagged));
+    // The tag is larger than the buffer.
+    assert_eq!(Some(Kind::Mp3), identify(&tagged[..64]));
+}
@@ -0,0 +1,240 @@
+// MPEG-2 Transport Streams: fixed-size packets, each starting with the sync byte 0x47.
+// Besides the plain 188-byte packets, Blu-ray/AVCHD (M2TS) prefix each packet with a
+// 4-byte timestamp and some DVB captures append 16 bytes of Reed-Solomon parity.
+// The Program Association Table (PID 0) lists the PIDs of the Program Map Tables,
+// which in turn list the elementary streams.
+// ref: ISO/IEC 13818-1, 2.4.3 and 2.4.4
+
+const SYNC_BYTE: u8 = 0x47;
+const PACKET_LEN: usize = 188;
+const PACKET_SIZES: [usize; 3] = [188, 192, 204];
+
+// Packets whose sync byte must be in place before the cadence is trusted.
+const MIN_PACKETS: usize = 3;
+const MAX_PACKETS: usize = 8;
+
+const PID_PAT: u16 = 0;
+const TABLE_ID_PAT: u8 = 0x00;
+const TABLE_ID_PMT: u8 = 0x02;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TsStream {
+    pub stream_type: u8,
+    pub pid: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TsProgram {
+    pub number: u16,
+    pub pmt_pid: u16,
+    // Empty when the PMT is not within the inspected bytes.
+    pub streams: Vec<TsStream>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TsInfo {
+    // 188, 192 (M2TS) or 204 (with Reed-Solomon parity).
+    pub packet_size: usize,
+    pub programs: Vec<TsProgram>,
+}
+
+impl TsStream {
+    pub fn stream_type_name(&self) -> &'static str {
+        match self.stream_type {
+            0x01 => "mpeg1-video",
+            0x02 => "mpeg2-video",
+            0x03 => "mpeg1-audio",
+            0x04 => "mpeg2-audio",
+            0x05 => "private-sections",
+            0x06 => "private-pes",
+            0x0F => "aac-adts",
+            0x10 => "mpeg4-video",
+            0x11 => "aac-latm",
+            0x15 => "metadata",
+            0x1B => "h264",
+            0x24 => "h265",
+            0x33 => "h266",
+            0x80 => "lpcm",
+            0x81 => "ac3",
+            0x82 => "dts",
+            0x83 => "truehd",
+            0x84 | 0x87 | 0xA1 => "eac3",
+            0x85 | 0x86 => "dts-hd",
+            0x90 => "pgs",
+            0x92 => "text-subtitles",
+            0xEA => "vc1",
+            _ => "unknown",
+        }
+    }
+}
+
+impl TsInfo {
+    pub fn streams(&self) -> impl Iterator<Item = &TsStream> {
+        self.programs.iter().flat_map(|p| p.streams.iter())
+    }
+}
+
+// The packet size whose cadence lines up with the sync bytes in `buf`.
+pub fn packet_size(buf: &[u8]) -> Option<usize> {
+    PACKET_SIZES.into_iter().find(|size| {
+        let start = sync_offset(*size);
+        let count = match buf.len().checked_sub(start + PACKET_LEN) {
+            Some(rest) => (rest / size + 1).min(MAX_PACKETS),
+            None => 0,
+        };
+        count >= MIN_PACKETS && (0..count).all(|i| buf[start + i * size] == SYNC_BYTE)
+    })
+}
+
+pub fn parse(buf: &[u8]) -> Option<TsInfo> {
+    let size = packet_size(buf)?;
+    let packets: Vec<&[u8]> = buf
+        .chunks_exact(size)
+        .map(|p| &p[sync_offset(size)..][..PACKET_LEN])
+        .collect();
+
+    let mut programs: Vec<TsProgram> = packets
+        .iter()
+        .find_map(|p| section(p, PID_PAT, TABLE_ID_PAT))
+        .map(pat_programs)
+        .unwrap_or_default();
+    for program in &mut programs {
+        if let Some(pmt) = packets
+            .iter()
+            .find_map(|p| section(p, program.pmt_pid, TABLE_ID_PMT))
+        {
+            program.streams = pmt_streams(pmt);
+        }
+    }
+
+    Some(TsInfo {
+        packet_size: size,
+        programs,
+    })
+}
+
+// Where the sync byte sits in a packet of `size` bytes: M2TS packets carry the 4-byte
+// timestamp before it, while the Reed-Solomon parity of 204-byte packets follows the
+// 188 bytes of the packet proper.
+fn sync_offset(size: usize) -> usize {
+    match size {
+        192 => 4,
+        _ => 0,
+    }
+}
+
+// The PSI section starting in `packet`, cut to its length and without the CRC.
+fn section(packet: &[u8], pid: u16, table_id: u8) -> Option<&[u8]> {
+    let packet_pid = u16::from_be_bytes([packet[1] & 0x1F, packet[2]]);
+    let payload_unit_start = packet[1] & 0x40 != 0;
+    let has_payload = packet[3] & 0x10 != 0;
+    if packet_pid != pid || !payload_unit_start || !has_payload {
+        return None;
+    }
+
+    let mut offset = 4;
+    if packet[3] & 0x20 != 0 {
+        offset += 1 + packet[4] as usize;
+    }
+    let pointer = *packet.get(offset)? as usize;
+    let section = packet.get(offset + 1 + pointer..)?;
+    if section.len() < 8 || section[0] != table_id {
+        return None;
+    }
+
+    let length = u16::from_be_bytes([section[1] & 0x0F, section[2]]) as usize;
+    if length < 9 {
+        return None;
+    }
+    // Sections continued in the next packet are cut to what this one holds.
+    Some(&section[..(3 + length - 4).min(section.len())])
+}
+
+fn pat_programs(section: &[u8]) -> Vec<TsProgram> {
+    section[8..]
+        .chunks_exact(4)
+        .filter_map(|entry| {
+            let number = u16::from_be_bytes([entry[0], entry[1]]);
+            let pid = u16::from_be_bytes([entry[2] & 0x1F, entry[3]]);
+            // Program 0 points at the Network Information Table.
+            (number != 0).then_some(TsProgram {
+                number,
+                pmt_pid: pid,
+                streams: vec![],
+            })
+        })
+        .collect()
+}
+
+fn pmt_streams(section: &[u8]) -> Vec<TsStream> {
+    let mut streams = vec![];
+    if section.len() < 12 {
+        return streams;
+    }
+
+    let program_info_length = u16::from_be_bytes([section[10] & 0x0F, section[11]]) as usize;
+    let mut offset = 12 + program_info_length;
+    while let Some(entry) = section.get(offset..offset + 5) {
+        streams.push(TsStream {
+            stream_type: entry[0],
+            pid: u16::from_be_bytes([entry[1] & 0x1F, entry[2]]),
+        });
+        offset += 5 + u16::from_be_bytes([entry[3] & 0x0F, entry[4]]) as usize;
+    }
+    streams
+}
+
+#[cfg(test)]
+fn psi_packet(pid: u16, table_id: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![SYNC_BYTE, 0x40 | (pid >> 8) as u8, pid as u8, 0x10, 0];
+    let length = 5 + body.len() + 4;
+    packet.extend_from_slice(&[table_id, 0xB0 | (length >> 8) as u8, length as u8]);
+    packet.extend_from_slice(&[0, 1, 0xC1, 0, 0]);
+    packet.extend_from_slice(body);
+    packet.extend_from_slice(&[0; 4]);
+    packet.resize(PACKET_LEN, 0xFF);
+    packet
+}
+
+#[test]
+fn test_ts_pat_pmt() {
+    let mut ts = psi_packet(PID_PAT, TABLE_ID_PAT, &[0, 0, 0xE0, 0x10, 0, 1, 0xE1, 0]);
+    ts.extend(psi_packet(
+        0x100,
+        TABLE_ID_PMT,
+        &[
+            0xE1, 0x01, 0xF0, 0, 0x1B, 0xE1, 0x01, 0xF0, 0, 0x0F, 0xE1, 0x02, 0xF0, 0,
+        ],
+    ));
+    let mut null = vec![SYNC_BYTE, 0x1F, 0xFF, 0x10];
+    null.resize(PACKET_LEN, 0xFF);
+    ts.extend(null);
+
+    let info = parse(&ts).unwrap();
+    assert_eq!(188, info.packet_size);
+    assert_eq!(1, info.programs.len());
+    assert_eq!(1, info.programs[0].number);
+    assert_eq!(0x100, info.programs[0].pmt_pid);
+    let names: Vec<_> = info.streams().map(|s| s.stream_type_name()).collect();
+    assert_eq!(vec!["h264", "aac-adts"], names);
+
+    // The same packets behind 4-byte M2TS timestamps.
+    let m2ts: Vec<u8> = ts
+        .chunks(PACKET_LEN)
+        .flat_map(|p| [&[0_u8; 4], p].concat())
+        .collect();
+    assert_eq!(Some(192), packet_size(&m2ts));
+    assert_eq!(2, parse(&m2ts).unwrap().streams().count());
+
+    // And followed by 16 bytes of Reed-Solomon parity.
+    let rs: Vec<u8> = ts
+        .chunks(PACKET_LEN)
+        .flat_map(|p| [p, &[0_u8; 16]].concat())
+        .collect();
+    assert_eq!(Some(204), packet_size(&rs));
+    assert_eq!(2, parse(&rs).unwrap().streams().count());
+
+    assert_eq!(None, packet_size(&ts[..PACKET_LEN * 2]));
+    ts[PACKET_LEN] = 0;
+    assert_eq!(None, packet_size(&ts));
+}
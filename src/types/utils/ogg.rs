@@ -0,0 +1,189 @@
+// Ogg is a container; the codec of each logical stream is named by the first packet
+// of its beginning-of-stream (BOS) page, and all BOS pages come first in the file.
+// ref: https://www.rfc-editor.org/rfc/rfc3533
+// ref: https://wiki.xiph.org/MIMETypesCodecs
+
+const HEADER_TYPE_BOS: u8 = 0x02;
+
+// Multiplexed files rarely carry more than a handful of streams.
+const MAX_STREAMS: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    Vorbis,
+    Opus,
+    Flac,
+    Speex,
+    Theora,
+    Daala,
+    Skeleton,
+    // Streams of the legacy OGM (Ogg Media) mapping, as written by DirectShow filters.
+    OgmVideo,
+    OgmAudio,
+    OgmText,
+    Unknown,
+}
+
+impl Codec {
+    fn is_video(&self) -> bool {
+        matches!(self, Codec::Theora | Codec::Daala)
+    }
+
+    pub fn is_ogm(&self) -> bool {
+        matches!(self, Codec::OgmVideo | Codec::OgmAudio | Codec::OgmText)
+    }
+}
+
+pub struct Page<'a> {
+    pub header_type: u8,
+    // Position of the last packet that ends on the page, in codec-specific units;
+    // all ones when none does.
+    pub granule: u64,
+    pub serial: u32,
+    // The page's packet data, truncated by the end of the buffer.
+    pub body: &'a [u8],
+    // The length of the whole page, header included.
+    pub len: usize,
+}
+
+// The page at the start of `buf`, if its header and segment table are complete.
+pub fn parse_page(buf: &[u8]) -> Option<Page<'_>> {
+    let header = buf.get(..27).filter(|p| p[..4] == *b"OggS" && p[4] == 0)?;
+    let segments = header[26] as usize;
+    let table = buf.get(27..27 + segments)?;
+    let body = 27 + segments;
+    let len = body + table.iter().map(|l| *l as usize).sum::<usize>();
+
+    Some(Page {
+        header_type: header[5],
+        granule: u64::from_le_bytes(header[6..14].try_into().unwrap()),
+        serial: u32::from_le_bytes(header[14..18].try_into().unwrap()),
+        body: &buf[body..len.min(buf.len())],
+        len,
+    })
+}
+
+// The granule position of the last page of stream `serial` within `buf`, usually a read
+// of the end of the file.
+pub fn last_granule(buf: &[u8], serial: u32) -> Option<u64> {
+    (0..=buf.len().saturating_sub(27))
+        .rev()
+        .filter(|i| buf[*i..].starts_with(b"OggS"))
+        .filter_map(|i| parse_page(&buf[i..]))
+        .find(|p| p.serial == serial && p.granule != u64::MAX)
+        .map(|p| p.granule)
+}
+
+// The codecs of the logical streams that begin within `buf`, in page order.
+pub fn streams(buf: &[u8]) -> Option<Vec<Codec>> {
+    let mut codecs = vec![];
+    let mut offset = 0;
+
+    while codecs.len() < MAX_STREAMS {
+        let page = match buf.get(offset..).and_then(parse_page) {
+            Some(p) if p.header_type & HEADER_TYPE_BOS != 0 => p,
+            _ => break,
+        };
+        codecs.push(codec(page.body));
+        offset += page.len;
+    }
+
+    if codecs.is_empty() {
+        None
+    } else {
+        Some(codecs)
+    }
+}
+
+// The codec that best describes the file: OGM if any stream uses that mapping, then
+// the first video stream, then the first other stream that is not Skeleton metadata.
+// Files without a BOS page at the start, such as streams cut mid-file or pages of a later
+// version, are Ogg of an unknown codec.
+pub fn identify(buf: &[u8]) -> Option<Codec> {
+    let codecs = match streams(buf) {
+        Some(codecs) => codecs,
+        None if buf.starts_with(b"OggS") => return Some(Codec::Unknown),
+        None => return None,
+    };
+
+    if let Some(ogm) = codecs.iter().find(|c| c.is_ogm()) {
+        return Some(*ogm);
+    }
+    if let Some(video) = codecs.iter().find(|c| c.is_video()) {
+        return Some(*video);
+    }
+    Some(
+        codecs
+            .into_iter()
+            .find(|c| *c != Codec::Skeleton)
+            .unwrap_or(Codec::Unknown),
+    )
+}
+
+fn codec(packet: &[u8]) -> Codec {
+    if packet.starts_with(b"\x01vorbis") {
+        Codec::Vorbis
+    } else if packet.starts_with(b"OpusHead") {
+        Codec::Opus
+    } else if packet.starts_with(b"\x7FFLAC") || packet.starts_with(b"fLaC") {
+        Codec::Flac
+    } else if packet.starts_with(b"Speex   ") {
+        Codec::Speex
+    } else if packet.starts_with(b"\x80theora") {
+        Codec::Theora
+    } else if packet.starts_with(b"\x80daala") {
+        Codec::Daala
+    } else if packet.starts_with(b"fishead\0") {
+        Codec::Skeleton
+    } else if packet.starts_with(b"\x01video\0\0\0") {
+        Codec::OgmVideo
+    } else if packet.starts_with(b"\x01audio\0\0\0") {
+        Codec::OgmAudio
+    } else if packet.starts_with(b"\x01text\0\0\0\0") {
+        Codec::OgmText
+    } else {
+        Codec::Unknown
+    }
+}
+
+#[cfg(test)]
+fn page(bos: bool, packet: &[u8]) -> Vec<u8> {
+    let mut buf = b"OggS\0".to_vec();
+    buf.push(if bos { HEADER_TYPE_BOS } else { 0 });
+    buf.extend_from_slice(&[0; 20]);
+    buf.push(1);
+    buf.push(packet.len() as u8);
+    buf.extend_from_slice(packet);
+    buf
+}
+
+#[test]
+fn test_ogg_streams() {
+    let mut buf = page(true, b"fishead\0\0\x03");
+    buf.extend(page(true, b"\x80theora\x03\x02\x01"));
+    buf.extend(page(true, b"\x01vorbis\0\0\0\0"));
+    buf.extend(page(false, b"\x03vorbis"));
+
+    assert_eq!(
+        Some(vec![Codec::Skeleton, Codec::Theora, Codec::Vorbis]),
+        streams(&buf)
+    );
+    assert_eq!(Some(Codec::Theora), identify(&buf));
+
+    assert_eq!(
+        Some(Codec::Opus),
+        identify(&page(true, b"OpusHead\x01\x02"))
+    );
+    assert_eq!(Some(Codec::Flac), identify(&page(true, b"\x7FFLAC\x01\0")));
+    assert_eq!(
+        Some(Codec::OgmAudio),
+        identify(&page(true, b"\x01audio\0\0\x0055\0\0"))
+    );
+    // A stream cut mid-file, and a page of an unknown version.
+    assert_eq!(None, streams(&page(false, b"OpusHead")));
+    assert_eq!(Some(Codec::Unknown), identify(&page(false, b"OpusHead")));
+    let mut v1 = page(true, b"OpusHead");
+    v1[4] = 1;
+    assert_eq!(Some(Codec::Unknown), identify(&v1));
+    assert_eq!(None, identify(b"OggX"));
+}
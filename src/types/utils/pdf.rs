@@ -0,0 +1,1159 @@
+// PDF file structure: the header, the cross-reference sections (tables or, since PDF 1.5,
+// compressed streams) chained through `/Prev`, the trailer, and the few objects read
+// from the catalog. Objects may sit in object streams. Files whose cross-reference data
+// is broken are indexed again by scanning for `N G obj`, as readers do.
+// ref: ISO 32000-2:2020, 7.3 (objects), 7.4.4 (FlateDecode predictors), 7.5 (file
+//      structure), 7.6.4.2 (standard security handler, Table 22), 14.8 (tagged PDF)
+// ref: ISO 32000-2:2020, Annex F (linearized PDF)
+// ref: ISO 19005-1, 6.7.11 (pdfaid); ISO 14289-1, 6.7.1 (pdfuaid); ISO 15930-7, 6.7 (pdfxid)
+// ref: ISO 24517-1, 6.4 (pdfe)
+use super::{bytes_index, gzip::inflate_zlib_prefix, metadata::xmp_property};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdfInfo {
+    // The header version, or the catalog's `/Version` when that is later.
+    pub version: String,
+    // Bytes before `%PDF-`, which readers skip.
+    pub header_offset: usize,
+    pub linearized: bool,
+    pub encrypted: bool,
+    pub permissions: Option<Permissions>,
+    pub pages: Option<u32>,
+    // Not read from encrypted files, whose strings are encrypted too.
+    pub producer: Option<String>,
+    // The catalog's `/MarkInfo` declares a structure tree.
+    pub tagged: bool,
+    // Conformance claimed in the XMP metadata, e.g. "PDF/A-2B", "PDF/UA-1", "PDF/X-4"
+    // and "PDF/E-1".
+    pub pdf_a: Option<String>,
+    pub pdf_ua: Option<String>,
+    pub pdf_x: Option<String>,
+    pub pdf_e: Option<String>,
+    // Saved by Adobe Illustrator with its private data, i.e. an .ai file.
+    pub illustrator: bool,
+}
+
+// The user access permissions of the standard security handler (`/P`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Permissions {
+    pub print: bool,
+    pub modify: bool,
+    pub copy: bool,
+    pub annotate: bool,
+    pub fill_forms: bool,
+    pub extract_for_accessibility: bool,
+    pub assemble: bool,
+    pub print_high_quality: bool,
+}
+
+impl Permissions {
+    fn from_bits(p: i64) -> Permissions {
+        let bit = |n: u32| p & (1 << (n - 1)) != 0;
+        Permissions {
+            print: bit(3),
+            modify: bit(4),
+            copy: bit(5),
+            annotate: bit(6),
+            fill_forms: bit(9),
+            extract_for_accessibility: bit(10),
+            assemble: bit(11),
+            print_high_quality: bit(12),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Object {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Name(String),
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dict(Dict),
+    Ref(u32),
+    // The dictionary and the still encoded data.
+    Stream(Dict, Vec<u8>),
+}
+
+type Dict = Vec<(String, Object)>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum XrefEntry {
+    // The position of `N G obj` in the buffer.
+    Offset(usize),
+    // The object stream and the index of the object in it.
+    Compressed(u32, usize),
+}
+
+// Leading junk readers accept before `%PDF-`.
+const MAX_HEADER_OFFSET: usize = 1024;
+// `startxref` is in the last kilobyte, after the final `%%EOF` give or take some junk.
+const STARTXREF_WINDOW: usize = 1024;
+const MAX_XREF_SECTIONS: usize = 64;
+// Nesting of arrays and dictionaries, and of references followed through objects.
+const MAX_DEPTH: usize = 32;
+const MAX_STREAM_BYTES: usize = 4 << 20;
+// The bytes read for the header, and for an object or a cross-reference table before
+// trying again with room for the largest stream.
+const WINDOW_BYTES: usize = 64 << 10;
+// Files whose cross-reference data is broken are scanned a chunk at a time.
+const SCAN_BYTES: usize = 1 << 20;
+const SCAN_LOOKBACK: usize = 64;
+
+const ILLUSTRATOR_MARKERS: [&[u8]; 3] = [
+    b"AIPrivateData",
+    b"/Illustrator",
+    b"http://ns.adobe.com/illustrator/1.0/",
+];
+
+// The offset of `%PDF-x.y`: 0, or the length of up to 1 KiB of leading junk, such as
+// mail or HTTP headers or PJL commands. Junk must be text that does not start a
+// PostScript file, and an object must follow the header, so that other files holding
+// a PDF are not taken for one.
+pub fn header_offset(buf: &[u8]) -> Option<usize> {
+    let window = &buf[..buf.len().min(MAX_HEADER_OFFSET + 8)];
+    let at = match bytes_index(window, b"%PDF-") {
+        u32::MAX => return None,
+        at => at as usize,
+    };
+    let version = buf.get(at + 5..at + 8)?;
+    if !(version[0].is_ascii_digit() && version[1] == b'.' && version[2].is_ascii_digit()) {
+        return None;
+    }
+    if at == 0 {
+        return Some(0);
+    }
+
+    let junk = &buf[..at];
+    let text = junk
+        .iter()
+        .all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace() || *b == 0x1B);
+    let after = &buf[at..buf.len().min(at + MAX_HEADER_OFFSET)];
+    (text && !junk.starts_with(b"%!") && bytes_index(after, b" obj") != u32::MAX).then_some(at)
+}
+
+// Traces of Adobe Illustrator: the private data of the PieceInfo dictionary, or its
+// XMP namespace.
+pub fn is_illustrator(buf: &[u8]) -> bool {
+    ILLUSTRATOR_MARKERS
+        .iter()
+        .any(|m| bytes_index(buf, m) != u32::MAX)
+}
+
+// Fills a buffer with the bytes at an offset and returns how many were read, like
+// `get_bytes_at`.
+type ReadAt<'a> = &'a dyn Fn(u64, &mut [u8]) -> usize;
+
+pub fn parse(buf: &[u8]) -> Option<PdfInfo> {
+    parse_from(buf.len() as u64, &|offset, out| {
+        let rest = usize::try_from(offset)
+            .ok()
+            .and_then(|at| buf.get(at..))
+            .unwrap_or_default();
+        let n = rest.len().min(out.len());
+        out[..n].copy_from_slice(&rest[..n]);
+        n
+    })
+}
+
+// Like `parse`, for a file of `len` bytes that is read a window at a time: the header,
+// the cross-reference data at the end, and the objects it points to.
+pub fn parse_from(len: u64, read: ReadAt) -> Option<PdfInfo> {
+    let mut head = vec![0_u8; WINDOW_BYTES];
+    let n = read(0, &mut head);
+    head.truncate(n);
+    let base = header_offset(&head)?;
+    let doc = Document::open(read, usize::try_from(len).unwrap_or(usize::MAX), base);
+    let mut info = PdfInfo {
+        version: String::from_utf8_lossy(&head[base + 5..base + 8]).into_owned(),
+        header_offset: base,
+        linearized: doc.linearized(),
+        illustrator: is_illustrator(&head),
+        ..Default::default()
+    };
+
+    let encrypt = get(&doc.trailer, "Encrypt").map(|e| doc.resolve(e));
+    info.encrypted = encrypt.is_some();
+    info.permissions = match encrypt {
+        Some(Object::Dict(dict)) => int(&dict, "P").map(Permissions::from_bits),
+        _ => None,
+    };
+
+    let catalog = doc.dict(&doc.trailer, "Root").unwrap_or_default();
+    if let Some(Object::Name(version)) = get(&catalog, "Version") {
+        if version_key(version) > version_key(&info.version) {
+            info.version = version.clone();
+        }
+    }
+    info.pages = doc
+        .dict(&catalog, "Pages")
+        .and_then(|pages| int(&pages, "Count"))
+        .and_then(|n| u32::try_from(n).ok());
+    info.tagged = doc
+        .dict(&catalog, "MarkInfo")
+        .is_some_and(|m| get(&m, "Marked") == Some(&Object::Bool(true)));
+
+    let document_info = doc.dict(&doc.trailer, "Info").unwrap_or_default();
+    if !info.encrypted {
+        info.producer = text(&document_info, "Producer");
+    }
+
+    let xmp = match get(&catalog, "Metadata").map(|m| doc.resolve(m)) {
+        Some(Object::Stream(dict, data)) => decode(&dict, &data),
+        _ => None,
+    };
+    let xmp = String::from_utf8_lossy(xmp.as_deref().unwrap_or_default()).into_owned();
+    // Past the header, Illustrator leaves its namespace in the XMP metadata and its
+    // private data in the catalog's `/PieceInfo`.
+    info.illustrator |= is_illustrator(xmp.as_bytes())
+        || doc
+            .dict(&catalog, "PieceInfo")
+            .is_some_and(|p| get(&p, "Illustrator").is_some());
+    let property = |name| xmp_property(&xmp, name);
+
+    if info.producer.is_none() && !info.encrypted {
+        info.producer = property("pdf:Producer").map(str::to_string);
+    }
+    info.pdf_a = property("pdfaid:part").map(|part| {
+        let conformance = property("pdfaid:conformance").unwrap_or_default();
+        format!("PDF/A-{part}{}", conformance.to_ascii_uppercase())
+    });
+    info.pdf_ua = property("pdfuaid:part").map(|part| format!("PDF/UA-{part}"));
+    info.pdf_x = property("pdfxid:GTS_PDFXVersion")
+        .or_else(|| property("pdfx:GTS_PDFXVersion"))
+        .map(str::to_string)
+        .or_else(|| text(&document_info, "GTS_PDFXVersion"));
+    info.pdf_e = property("pdfe:ISO_PDFEVersion").map(str::to_string);
+    Some(info)
+}
+
+fn version_key(version: &str) -> (u32, u32) {
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    (major.parse().unwrap_or(0), minor.parse().unwrap_or(0))
+}
+
+fn get<'d>(dict: &'d Dict, key: &str) -> Option<&'d Object> {
+    dict.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn int(dict: &Dict, key: &str) -> Option<i64> {
+    match get(dict, key)? {
+        Object::Int(n) => Some(*n),
+        _ => None,
+    }
+}
+
+// A text string: UTF-16BE after a byte order mark, UTF-8 after one (PDF 2.0), and
+// otherwise PDFDocEncoding, read as Latin-1.
+fn text(dict: &Dict, key: &str) -> Option<String> {
+    let bytes = match get(dict, key)? {
+        Object::String(s) => s,
+        _ => return None,
+    };
+    let s = if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8_lossy(utf8).into_owned()
+    } else {
+        bytes.iter().map(|b| *b as char).collect()
+    };
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+// The decoded data of a stream without a filter or with FlateDecode, the only filter
+// of cross-reference streams, object streams and metadata in practice.
+fn decode(dict: &Dict, data: &[u8]) -> Option<Vec<u8>> {
+    let filter = match get(dict, "Filter") {
+        None => None,
+        Some(Object::Name(name)) => Some(name.as_str()),
+        Some(Object::Array(a)) => match a.as_slice() {
+            [] => None,
+            [Object::Name(name)] => Some(name.as_str()),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let parms = match get(dict, "DecodeParms") {
+        Some(Object::Dict(d)) => Some(d),
+        Some(Object::Array(a)) => match a.first() {
+            Some(Object::Dict(d)) => Some(d),
+            _ => None,
+        },
+        _ => None,
+    };
+    match filter {
+        None => Some(data.to_vec()),
+        Some("FlateDecode" | "Fl") => {
+            let inflated = inflate_zlib_prefix(data, MAX_STREAM_BYTES)?;
+            match parms {
+                Some(parms) => unpredict(inflated, parms),
+                None => Some(inflated),
+            }
+        }
+        _ => None,
+    }
+}
+
+// Undoes the PNG predictors, which prefix each row with its filter type.
+fn unpredict(data: Vec<u8>, parms: &Dict) -> Option<Vec<u8>> {
+    let predictor = int(parms, "Predictor").unwrap_or(1);
+    if predictor < 10 {
+        // 2 is the TIFF predictor, never used for the streams read here.
+        return (predictor == 1).then_some(data);
+    }
+    let value = |key, default| int(parms, key).unwrap_or(default).clamp(1, 1 << 16) as usize;
+    let bits = value("Colors", 1) * value("BitsPerComponent", 8);
+    let bpp = bits.div_ceil(8);
+    let row_len = (bits * value("Columns", 1)).div_ceil(8);
+
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev = vec![0_u8; row_len];
+    for row in data.chunks_exact(row_len + 1) {
+        let mut cur = row[1..].to_vec();
+        for i in 0..row_len {
+            let a = if i >= bpp { cur[i - bpp] } else { 0 };
+            let b = prev[i];
+            let c = if i >= bpp { prev[i - bpp] } else { 0 };
+            cur[i] = cur[i].wrapping_add(match row[0] {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return None,
+            });
+        }
+        out.extend_from_slice(&cur);
+        prev = cur;
+    }
+    Some(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+struct Lexer<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(
+        b,
+        b'\0'
+            | b'\t'
+            | b'\n'
+            | 0x0C
+            | b'\r'
+            | b' '
+            | b'('
+            | b')'
+            | b'<'
+            | b'>'
+            | b'['
+            | b']'
+            | b'{'
+            | b'}'
+            | b'/'
+            | b'%'
+    )
+}
+
+impl<'a> Lexer<'a> {
+    fn new(buf: &'a [u8], pos: usize) -> Lexer<'a> {
+        Lexer { buf, pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buf.get(self.pos).copied()
+    }
+
+    // Skips white space and comments.
+    fn skip_space(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b'\0' | b'\t' | b'\n' | 0x0C | b'\r' | b' ' => self.pos += 1,
+                b'%' => {
+                    while !matches!(self.peek(), None | Some(b'\r' | b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    // A run of regular characters, e.g. a number or a keyword.
+    fn token(&mut self) -> &'a [u8] {
+        self.skip_space();
+        let start = self.pos;
+        while self.peek().is_some_and(|b| !is_delimiter(b)) {
+            self.pos += 1;
+        }
+        self.buf.get(start..self.pos).unwrap_or_default()
+    }
+
+    fn keyword(&mut self, keyword: &[u8]) -> bool {
+        let pos = self.pos;
+        if self.token() == keyword {
+            return true;
+        }
+        self.pos = pos;
+        false
+    }
+
+    fn int(&mut self) -> Option<i64> {
+        let pos = self.pos;
+        let n = std::str::from_utf8(self.token()).ok()?.parse().ok();
+        if n.is_none() {
+            self.pos = pos;
+        }
+        n
+    }
+
+    fn object(&mut self, depth: usize) -> Option<Object> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        self.skip_space();
+        match self.peek()? {
+            b'/' => {
+                self.pos += 1;
+                Some(Object::Name(self.name()))
+            }
+            b'(' => {
+                self.pos += 1;
+                self.literal_string().map(Object::String)
+            }
+            b'<' if self.buf.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                let dict = self.dict(depth)?;
+                Some(
+                    self.stream(&dict)
+                        .map_or(Object::Dict(dict.clone()), |data| {
+                            Object::Stream(dict, data)
+                        }),
+                )
+            }
+            b'<' => {
+                self.pos += 1;
+                self.hex_string().map(Object::String)
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = vec![];
+                loop {
+                    self.skip_space();
+                    if self.peek()? == b']' {
+                        self.pos += 1;
+                        return Some(Object::Array(items));
+                    }
+                    items.push(self.object(depth + 1)?);
+                }
+            }
+            _ => self.number_or_keyword(),
+        }
+    }
+
+    fn number_or_keyword(&mut self) -> Option<Object> {
+        let token = self.token();
+        match token {
+            b"true" => return Some(Object::Bool(true)),
+            b"false" => return Some(Object::Bool(false)),
+            b"null" => return Some(Object::Null),
+            _ => {}
+        }
+        let s = std::str::from_utf8(token).ok()?;
+        if let Ok(n) = s.parse::<i64>() {
+            // `N G R` is a reference.
+            let pos = self.pos;
+            if let (Ok(num), Some(_), true) = (u32::try_from(n), self.int(), self.keyword(b"R")) {
+                return Some(Object::Ref(num));
+            }
+            self.pos = pos;
+            return Some(Object::Int(n));
+        }
+        s.parse().ok().map(Object::Real)
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = vec![];
+        while let Some(b) = self.peek().filter(|b| !is_delimiter(*b)) {
+            let escaped = self
+                .buf
+                .get(self.pos + 1..self.pos + 3)
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+            match (b, escaped) {
+                (b'#', Some(c)) => {
+                    name.push(c);
+                    self.pos += 3;
+                }
+                _ => {
+                    name.push(b);
+                    self.pos += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&name).into_owned()
+    }
+
+    fn literal_string(&mut self) -> Option<Vec<u8>> {
+        let mut s = vec![];
+        let mut nesting = 0;
+        loop {
+            let b = self.peek()?;
+            self.pos += 1;
+            match b {
+                b'(' => nesting += 1,
+                b')' if nesting == 0 => return Some(s),
+                b')' => nesting -= 1,
+                b'\\' => {
+                    let e = self.peek()?;
+                    self.pos += 1;
+                    match e {
+                        b'n' => s.push(b'\n'),
+                        b'r' => s.push(b'\r'),
+                        b't' => s.push(b'\t'),
+                        b'b' => s.push(0x08),
+                        b'f' => s.push(0x0C),
+                        b'0'..=b'7' => {
+                            let mut v = (e - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        v = v * 8 + (d - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            s.push(v as u8);
+                        }
+                        // A line continuation.
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        _ => s.push(e),
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            s.push(b);
+        }
+    }
+
+    fn hex_string(&mut self) -> Option<Vec<u8>> {
+        let mut digits = vec![];
+        loop {
+            let b = self.peek()?;
+            self.pos += 1;
+            match b {
+                b'>' => break,
+                _ if b.is_ascii_hexdigit() => digits.push((b as char).to_digit(16)? as u8),
+                _ if b.is_ascii_whitespace() || b == 0 => {}
+                _ => return None,
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+        Some(digits.chunks(2).map(|d| d[0] << 4 | d[1]).collect())
+    }
+
+    fn dict(&mut self, depth: usize) -> Option<Dict> {
+        let mut dict = vec![];
+        loop {
+            self.skip_space();
+            if self.buf.get(self.pos..self.pos + 2)? == b">>" {
+                self.pos += 2;
+                return Some(dict);
+            }
+            let key = match self.object(depth + 1)? {
+                Object::Name(key) => key,
+                _ => return None,
+            };
+            let value = self.object(depth + 1)?;
+            dict.push((key, value));
+        }
+    }
+
+    // The data of the stream following a dictionary: `/Length` bytes when they end at
+    // `endstream`, otherwise everything up to `endstream`.
+    fn stream(&mut self, dict: &Dict) -> Option<Vec<u8>> {
+        let pos = self.pos;
+        if !self.keyword(b"stream") {
+            return None;
+        }
+        match self.buf.get(self.pos..self.pos + 2) {
+            Some(b"\r\n") => self.pos += 2,
+            Some([b'\n' | b'\r', _]) => self.pos += 1,
+            _ => {}
+        }
+        let start = self.pos;
+        let declared = int(dict, "Length")
+            .and_then(|len| usize::try_from(len).ok())
+            .and_then(|len| start.checked_add(len))
+            .filter(|end| {
+                let mut lexer = Lexer::new(self.buf, *end);
+                *end <= self.buf.len() && lexer.keyword(b"endstream")
+            });
+        let end = match declared {
+            Some(end) => end,
+            None => {
+                let found = bytes_index(&self.buf[start..], b"endstream");
+                if found == u32::MAX {
+                    self.pos = pos;
+                    return None;
+                }
+                start + found as usize
+            }
+        };
+        self.pos = end;
+        self.keyword(b"endstream");
+        Some(self.buf[start..end].to_vec())
+    }
+
+    // `N G obj`, then the object.
+    fn indirect(&mut self) -> Option<(u32, Object)> {
+        let num = u32::try_from(self.int()?).ok()?;
+        self.int()?;
+        if !self.keyword(b"obj") {
+            return None;
+        }
+        Some((num, self.object(0)?))
+    }
+}
+
+struct Document<'a> {
+    read: ReadAt<'a>,
+    len: usize,
+    base: usize,
+    xref: HashMap<u32, XrefEntry>,
+    trailer: Dict,
+}
+
+impl<'a> Document<'a> {
+    fn open(read: ReadAt<'a>, len: usize, base: usize) -> Document<'a> {
+        let mut doc = Document {
+            read,
+            len,
+            base,
+            xref: HashMap::new(),
+            trailer: vec![],
+        };
+
+        let mut next = doc.startxref();
+        let mut seen = vec![];
+        while let Some(offset) = next.filter(|o| !seen.contains(o)) {
+            if seen.len() >= MAX_XREF_SECTIONS {
+                break;
+            }
+            seen.push(offset);
+            let trailer = match doc.read_section(offset) {
+                Some(t) => t,
+                None => break,
+            };
+            // Hybrid files list the objects of their object streams in a separate
+            // cross-reference stream, which takes precedence over the table.
+            if let Some(stm) = int(&trailer, "XRefStm") {
+                doc.read_section(stm);
+            }
+            next = int(&trailer, "Prev");
+            if doc.trailer.is_empty() {
+                doc.trailer = trailer;
+            }
+        }
+
+        if get(&doc.trailer, "Root").is_none() {
+            doc.rebuild();
+        }
+        doc
+    }
+
+    // Up to `len` bytes from `at`.
+    fn read(&self, at: usize, len: usize) -> Vec<u8> {
+        let mut buf = vec![0_u8; len.min(self.len.saturating_sub(at))];
+        let n = (self.read)(at as u64, &mut buf);
+        buf.truncate(n);
+        buf
+    }
+
+    // Runs `f` on the bytes from `at`, and again on a window that holds the largest
+    // stream read when the first one falls short of both the end of the file and what
+    // `f` needs.
+    fn lex<T>(&self, at: usize, f: impl Fn(&mut Lexer) -> Option<T>) -> Option<T> {
+        for len in [WINDOW_BYTES, MAX_STREAM_BYTES + WINDOW_BYTES] {
+            let buf = self.read(at, len);
+            let ret = f(&mut Lexer::new(&buf, 0));
+            if ret.is_some() || buf.len() < len {
+                return ret;
+            }
+        }
+        None
+    }
+
+    fn startxref(&self) -> Option<i64> {
+        let tail = self.read(self.len.saturating_sub(STARTXREF_WINDOW), STARTXREF_WINDOW);
+        let at = tail.windows(9).rposition(|w| w == b"startxref")?;
+        Lexer::new(&tail, at + 9).int()
+    }
+
+    // Reads a cross-reference table or stream and returns its trailer. Offsets are
+    // relative to the header; files with junk ahead of it sometimes count the junk.
+    // Offsets past the end, as in truncated files, are rejected.
+    fn read_section(&mut self, offset: i64) -> Option<Dict> {
+        let offset = usize::try_from(offset).ok()?;
+        let len = self.len;
+        [self.base.checked_add(offset), Some(offset)]
+            .into_iter()
+            .flatten()
+            .filter(|at| *at < len)
+            .find_map(|at| self.read_table(at).or_else(|| self.read_stream(at)))
+    }
+
+    fn read_table(&mut self, at: usize) -> Option<Dict> {
+        if at >= self.len {
+            return None;
+        }
+        let (entries, trailer) = self.lex(at, |lexer| {
+            if !lexer.keyword(b"xref") {
+                return None;
+            }
+            let mut entries = vec![];
+            while !lexer.keyword(b"trailer") {
+                let (start, count) = (lexer.int()?, lexer.int()?);
+                for i in 0..count.clamp(0, self.len as i64 / 20) {
+                    let (offset, _) = (lexer.int()?, lexer.int()?);
+                    let in_use = match lexer.token() {
+                        b"n" => true,
+                        b"f" => false,
+                        _ => return None,
+                    };
+                    let num = start.checked_add(i).and_then(|n| u32::try_from(n).ok());
+                    if let (true, Some(num), Ok(offset)) = (in_use, num, usize::try_from(offset)) {
+                        entries.push((num, offset));
+                    }
+                }
+            }
+            match lexer.object(0)? {
+                Object::Dict(d) => Some((entries, d)),
+                _ => None,
+            }
+        })?;
+
+        let shift = self.shift(&entries);
+        for (num, offset) in entries {
+            self.xref
+                .entry(num)
+                .or_insert(XrefEntry::Offset(offset.saturating_add(shift)));
+        }
+        Some(trailer)
+    }
+
+    fn read_stream(&mut self, at: usize) -> Option<Dict> {
+        if at >= self.len {
+            return None;
+        }
+        let (dict, data) = match self.lex(at, |lexer| lexer.indirect())?.1 {
+            Object::Stream(dict, data) if get(&dict, "Type") == Some(&name("XRef")) => (dict, data),
+            _ => return None,
+        };
+        let data = decode(&dict, &data)?;
+        let widths: Vec<usize> = match get(&dict, "W")? {
+            Object::Array(w) => w
+                .iter()
+                .map(|o| match o {
+                    Object::Int(n) => usize::try_from(*n).ok().filter(|n| *n <= 8),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+        if widths.len() != 3 {
+            return None;
+        }
+        let index: Vec<i64> = match get(&dict, "Index") {
+            Some(Object::Array(a)) => a
+                .iter()
+                .filter_map(|o| match o {
+                    Object::Int(n) => Some(*n),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![0, int(&dict, "Size")?],
+        };
+
+        let row_len: usize = widths.iter().sum();
+        let mut rows = data.chunks_exact(row_len.max(1));
+        let mut entries = vec![];
+        for range in index.chunks_exact(2) {
+            for num in range[0]..range[0].saturating_add(range[1]) {
+                let row = match rows.next() {
+                    Some(r) => r,
+                    None => break,
+                };
+                let mut fields = [0_u64; 3];
+                let mut at = 0;
+                for (field, width) in fields.iter_mut().zip(&widths) {
+                    *field = row[at..at + width]
+                        .iter()
+                        .fold(0, |v, b| v << 8 | *b as u64);
+                    at += width;
+                }
+                // The type defaults to 1 when its field is omitted.
+                let kind = if widths[0] == 0 { 1 } else { fields[0] };
+                if let Ok(num) = u32::try_from(num) {
+                    entries.push((num, kind, fields[1] as usize, fields[2] as usize));
+                }
+            }
+        }
+
+        let offsets: Vec<_> = entries
+            .iter()
+            .filter(|e| e.1 == 1)
+            .map(|e| (e.0, e.2))
+            .collect();
+        let shift = self.shift(&offsets);
+        for (num, kind, a, b) in entries {
+            let entry = match kind {
+                1 => XrefEntry::Offset(a + shift),
+                2 => XrefEntry::Compressed(a as u32, b),
+                _ => continue,
+            };
+            self.xref.entry(num).or_insert(entry);
+        }
+        Some(dict)
+    }
+
+    // Whether the offsets of a section count from the header or from the start of the
+    // file, judged by the first object that can be found.
+    fn shift(&self, entries: &[(u32, usize)]) -> usize {
+        let is_object = |num, at| {
+            self.lex(at, |lexer| lexer.indirect())
+                .is_some_and(|(n, _)| n == num)
+        };
+        match entries.iter().find(|(num, o)| *num > 0 && *o > 0) {
+            Some((num, offset))
+                if !is_object(*num, self.base.saturating_add(*offset))
+                    && is_object(*num, *offset) =>
+            {
+                0
+            }
+            _ => self.base,
+        }
+    }
+
+    // Indexes the objects found by scanning the file, later definitions winning, and
+    // takes the last trailer, or failing that the first catalog. Each chunk is read with
+    // a little of the previous one, for the `N G ` before `obj`, and a window of the
+    // next one, for the objects that start in the chunk.
+    fn rebuild(&mut self) {
+        let mut trailer_at = None;
+        let mut chunk = 0;
+        while chunk < self.len {
+            let from = chunk.saturating_sub(SCAN_LOOKBACK);
+            let buf = self.read(from, chunk - from + SCAN_BYTES + WINDOW_BYTES);
+            let end = buf.len().min(chunk - from + SCAN_BYTES);
+            let last = from + buf.len() >= self.len;
+            let mut at = chunk - from;
+            loop {
+                let obj = match bytes_index(&buf[at..], b"obj") {
+                    u32::MAX => break,
+                    found => at + found as usize,
+                };
+                if obj >= end {
+                    break;
+                }
+                at = obj + 3;
+                if buf.get(at).is_some_and(|b| !is_delimiter(*b)) {
+                    continue;
+                }
+                // Back over `N G `.
+                let mut start = obj;
+                for _ in 0..2 {
+                    while start > 0 && buf[start - 1].is_ascii_whitespace() {
+                        start -= 1;
+                    }
+                    while start > 0 && buf[start - 1].is_ascii_digit() {
+                        start -= 1;
+                    }
+                }
+                // Part of a longer header, found whole with the previous chunk or not
+                // at all.
+                if start == 0 && from > 0 {
+                    continue;
+                }
+                let object = Lexer::new(&buf, start).indirect().or_else(|| {
+                    // Objects that run past the window are read on their own.
+                    let mut header = Lexer::new(&buf, start);
+                    let cut = !last
+                        && header.int().is_some()
+                        && header.int().is_some()
+                        && header.keyword(b"obj");
+                    cut.then(|| self.lex(from + start, |lexer| lexer.indirect()))
+                        .flatten()
+                });
+                if let Some((num, _)) = object {
+                    self.xref.insert(num, XrefEntry::Offset(from + start));
+                }
+            }
+            let tail = &buf[..buf.len().min(end + 6)];
+            if let Some(t) = tail.windows(7).rposition(|w| w == b"trailer") {
+                trailer_at = Some(from + t);
+            }
+            chunk += SCAN_BYTES;
+        }
+
+        if let Some(Object::Dict(trailer)) =
+            trailer_at.and_then(|t| self.lex(t + 7, |lexer| lexer.object(0)))
+        {
+            if get(&trailer, "Root").is_some() {
+                self.trailer = trailer;
+                return;
+            }
+        }
+        let mut nums: Vec<_> = self.xref.keys().copied().collect();
+        nums.sort_unstable();
+        let catalog = nums.into_iter().find(|num| {
+            matches!(self.load(*num, 0), Some(Object::Dict(d)) if get(&d, "Type") == Some(&name("Catalog")))
+        });
+        if let Some(num) = catalog {
+            self.trailer = vec![("Root".to_string(), Object::Ref(num))];
+        }
+    }
+
+    fn load(&self, num: u32, depth: usize) -> Option<Object> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match *self.xref.get(&num)? {
+            XrefEntry::Offset(at) => match self.lex(at, |lexer| lexer.indirect())? {
+                (n, object) if n == num => Some(object),
+                _ => None,
+            },
+            XrefEntry::Compressed(stream, index) => {
+                let (dict, data) = match self.load(stream, depth + 1)? {
+                    Object::Stream(dict, data) => (dict, data),
+                    _ => return None,
+                };
+                let data = decode(&dict, &data)?;
+                let first = usize::try_from(int(&dict, "First")?).ok()?;
+                // Pairs of object number and offset from `/First`.
+                let mut header = Lexer::new(&data, 0);
+                for _ in 0..index {
+                    header.int()?;
+                    header.int()?;
+                }
+                if u32::try_from(header.int()?).ok()? != num {
+                    return None;
+                }
+                let offset = usize::try_from(header.int()?).ok()?;
+                Lexer::new(&data, first.checked_add(offset)?).object(0)
+            }
+        }
+    }
+
+    fn resolve(&self, object: &Object) -> Object {
+        let mut object = object.clone();
+        for depth in 0..MAX_DEPTH {
+            match object {
+                Object::Ref(num) => object = self.load(num, depth).unwrap_or(Object::Null),
+                _ => break,
+            }
+        }
+        object
+    }
+
+    // A dictionary value, direct or referenced.
+    fn dict(&self, dict: &Dict, key: &str) -> Option<Dict> {
+        match self.resolve(get(dict, key)?) {
+            Object::Dict(d) | Object::Stream(d, _) => Some(d),
+            _ => None,
+        }
+    }
+
+    // The first object after the header: a linearization parameter dictionary in
+    // linearized files.
+    fn linearized(&self) -> bool {
+        let buf = self.read(self.base + 8, WINDOW_BYTES);
+        let mut lexer = Lexer::new(&buf, 0);
+        lexer.skip_space();
+        match lexer.indirect() {
+            Some((_, Object::Dict(dict))) => get(&dict, "Linearized").is_some(),
+            _ => false,
+        }
+    }
+}
+
+fn name(s: &str) -> Object {
+    Object::Name(s.to_string())
+}
+
+// A PDF with a classic cross-reference table, its offsets computed while writing.
+#[cfg(test)]
+fn build(junk: &[u8], objects: &[&[u8]], trailer: &str) -> Vec<u8> {
+    let mut buf = junk.to_vec();
+    let base = buf.len();
+    buf.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+    let mut offsets = vec![];
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(buf.len() - base);
+        buf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        buf.extend_from_slice(object);
+        buf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = buf.len() - base;
+    buf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        buf.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    buf.extend_from_slice(format!("trailer\n{trailer}\nstartxref\n{xref}\n%%EOF\n").as_bytes());
+    buf
+}
+
+#[test]
+fn test_pdf_lexer() {
+    let mut lexer = Lexer::new(
+        b"<< /Type /Cat#61log /Kids [3 0 R 4 0 R] /T (a\\(b\\)\\101) /H <48 69 7> /N -1.5 /B true >>",
+        0,
+    );
+    let dict = match lexer.object(0) {
+        Some(Object::Dict(d)) => d,
+        other => panic!("{other:?}"),
+    };
+    assert_eq!(Some(&name("Catalog")), get(&dict, "Type"));
+    assert_eq!(
+        Some(&Object::Array(vec![Object::Ref(3), Object::Ref(4)])),
+        get(&dict, "Kids")
+    );
+    assert_eq!(Some(&Object::String(b"a(b)A".to_vec())), get(&dict, "T"));
+    assert_eq!(Some(&Object::String(b"Hip".to_vec())), get(&dict, "H"));
+    assert_eq!(Some(&Object::Real(-1.5)), get(&dict, "N"));
+    assert_eq!(Some(&Object::Bool(true)), get(&dict, "B"));
+
+    // The PNG Up predictor over rows of 3 bytes.
+    let parms = vec![
+        ("Predictor".to_string(), Object::Int(12)),
+        ("Columns".to_string(), Object::Int(3)),
+    ];
+    assert_eq!(
+        Some(vec![1, 0, 16, 1, 0, 32]),
+        unpredict(vec![2, 1, 0, 16, 2, 0, 0, 16], &parms)
+    );
+}
+
+#[test]
+fn test_pdf_table() {
+    let buf = build(
+        b"",
+        &[
+            b"<< /Type /Catalog /Pages 2 0 R /MarkInfo << /Marked true >> /Version /1.7 >>",
+            b"<< /Type /Pages /Kids [] /Count 3 >>",
+            b"<< /Producer <FEFF00500044004600E9> >>",
+        ],
+        "<< /Size 4 /Root 1 0 R /Info 3 0 R >>",
+    );
+    let info = parse(&buf).unwrap();
+    assert_eq!(
+        ("1.7", Some(3), true),
+        (info.version.as_str(), info.pages, info.tagged)
+    );
+    assert_eq!(Some("PDF\u{E9}"), info.producer.as_deref());
+    assert!(!info.encrypted && !info.linearized);
+
+    // -3132 allows printing, filling in forms and accessibility, but nothing else.
+    let buf = build(
+        b"HTTP/1.1 200 OK\r\n\r\n",
+        &[
+            b"<< /Linearized 1 /L 1000 >>",
+            b"<< /Type /Catalog >>",
+            b"<< /P -3132 >>",
+        ],
+        "<< /Size 4 /Root 2 0 R /Encrypt 3 0 R >>",
+    );
+    let info = parse(&buf).unwrap();
+    assert_eq!(19, info.header_offset);
+    assert!(info.encrypted && info.linearized);
+    let p = info.permissions.unwrap();
+    assert!(p.print && p.fill_forms && p.extract_for_accessibility);
+    assert!(!p.modify && !p.copy && !p.annotate && !p.assemble && !p.print_high_quality);
+
+    // A broken `startxref` is recovered by scanning for objects.
+    let mut broken = build(
+        b"",
+        &[b"<< /Type /Catalog /Pages 2 0 R >>", b"<< /Count 7 >>"],
+        "<< >>",
+    );
+    let at = broken.windows(9).rposition(|w| w == b"startxref").unwrap();
+    broken.truncate(at);
+    assert_eq!(Some(7), parse(&broken).unwrap().pages);
+
+    // Truncated files whose cross-reference offsets point past the end, and a subsection
+    // numbered at the end of the integer range.
+    let truncated = b"%PDF-1.4\n1 0 obj<</Type/Catalog>>endobj\nstartxref\n99999\n%%EOF\n";
+    assert_eq!("1.4", parse(truncated).unwrap().version);
+    let truncated = b"%PDF-1.4\n1 0 obj<</Type/Catalog>>endobj\nstartxref\n61\n%%EOF\n";
+    assert!(parse(truncated).is_some());
+    let far = b"%PDF-1.4\nxref\n9223372036854775807 2\n0000000000 65535 f \n0000000009 00000 n \ntrailer\n<< >>\nstartxref\n9\n%%EOF\n";
+    assert!(parse(far).is_some());
+
+    // A stream that runs past the first window and across a scanning chunk, read from
+    // both the table and a scan, and without reading the stream through the table.
+    let mut stream = format!("<< /Length {} >>\nstream\n", SCAN_BYTES).into_bytes();
+    stream.resize(stream.len() + SCAN_BYTES, b'x');
+    stream.extend_from_slice(b"\nendstream");
+    let mut big = build(
+        b"",
+        &[
+            b"<< /Type /Catalog /Pages 3 0 R >>",
+            &stream,
+            b"<< /Count 9 >>",
+        ],
+        "<< /Size 4 /Root 1 0 R >>",
+    );
+    let read = std::cell::Cell::new(0);
+    let info = parse_from(big.len() as u64, &|offset, out| {
+        let at = offset as usize;
+        let n = out.len().min(big.len() - at);
+        out[..n].copy_from_slice(&big[at..at + n]);
+        read.set(read.get() + n);
+        n
+    });
+    assert_eq!(Some(9), info.unwrap().pages);
+    assert!(read.get() < SCAN_BYTES);
+    let at = big.windows(9).rposition(|w| w == b"startxref").unwrap();
+    big.truncate(at);
+    let info = parse(&big).unwrap();
+    assert_eq!(Some(9), info.pages);
+
+    assert_eq!(None, header_offset(b"PK\x03\x04\x14\0%PDF-1.4\n1 0 obj"));
+    assert_eq!(None, header_offset(b"%!PS-Adobe-3.0\n%PDF-1.4\n1 0 obj"));
+    assert_eq!(None, header_offset(b"no header here"));
+}
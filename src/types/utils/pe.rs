@@ -0,0 +1,335 @@
+// Executables starting with `MZ`: plain DOS programs, or a DOS stub whose `e_lfanew`
+// field points at a newer header (PE, NE, LE or LX).
+// ref: https://learn.microsoft.com/en-us/windows/win32/debug/pe-format
+use super::littleendian_bytes;
+
+const IMAGE_FILE_DLL: u16 = 0x2000;
+const IMAGE_NT_OPTIONAL_HDR32_MAGIC: u16 = 0x10B;
+const IMAGE_NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20B;
+const IMAGE_DIRECTORY_ENTRY_IMPORT: usize = 1;
+const IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR: usize = 14;
+const IMAGE_DLLCHARACTERISTICS_WDM_DRIVER: u16 = 0x2000;
+
+const IMAGE_SUBSYSTEM_NATIVE: u16 = 1;
+const IMAGE_SUBSYSTEM_EFI_APPLICATION: u16 = 10;
+const IMAGE_SUBSYSTEM_EFI_ROM: u16 = 13;
+
+// Kernel-mode images link against the kernel or the HAL; native user-mode programs such
+// as smss.exe link against ntdll.dll only.
+const KERNEL_IMPORTS: [&[u8]; 2] = [b"ntoskrnl.exe", b"hal.dll"];
+const MAX_IMPORTS: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeKind {
+    // A real-mode DOS program without a new-style header.
+    Dos,
+    // 16-bit Windows / OS/2 1.x.
+    Ne,
+    // Linear Executable, used by VxDs and DOS extenders.
+    Le,
+    // OS/2 2.x linear executable.
+    Lx,
+    Exe,
+    Dll,
+    // Kernel-mode image: native subsystem, not a DLL, and either marked as a WDM driver or
+    // importing from the kernel.
+    Driver,
+    Efi,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeInfo {
+    pub kind: PeKind,
+    // PE32+ (64-bit) rather than PE32; always false for non-PE kinds.
+    pub pe32_plus: bool,
+    // COFF `Machine`; 0 (IMAGE_FILE_MACHINE_UNKNOWN) for non-PE kinds.
+    pub machine: u16,
+    // Optional header `Subsystem`; 0 (IMAGE_SUBSYSTEM_UNKNOWN) for non-PE kinds.
+    pub subsystem: u16,
+    // Carries a CLR runtime header, i.e. a .NET assembly.
+    pub dotnet: bool,
+}
+
+impl PeInfo {
+    pub fn machine_name(&self) -> &'static str {
+        match self.machine {
+            0x014C => "i386",
+            0x0166 => "mips",
+            0x01C0 => "arm",
+            0x01C2 => "thumb",
+            0x01C4 => "armnt",
+            0x01F0 => "powerpc",
+            0x0200 => "ia64",
+            0x5032 => "riscv32",
+            0x5064 => "riscv64",
+            0x6232 => "loongarch32",
+            0x6264 => "loongarch64",
+            0x8664 => "amd64",
+            0xA641 => "arm64ec",
+            0xAA64 => "arm64",
+            0x0EBC => "efi-bytecode",
+            _ => "unknown",
+        }
+    }
+
+    pub fn subsystem_name(&self) -> &'static str {
+        match self.subsystem {
+            1 => "native",
+            2 => "windows-gui",
+            3 => "windows-cui",
+            5 => "os2-cui",
+            7 => "posix-cui",
+            8 => "native-windows",
+            9 => "windows-ce-gui",
+            10 => "efi-application",
+            11 => "efi-boot-service-driver",
+            12 => "efi-runtime-driver",
+            13 => "efi-rom",
+            14 => "xbox",
+            16 => "windows-boot-application",
+            _ => "unknown",
+        }
+    }
+}
+
+pub fn parse(buf: &[u8]) -> Option<PeInfo> {
+    if buf.len() < 0x40 || buf[..2] != *b"MZ" {
+        return None;
+    }
+
+    let dos = PeInfo {
+        kind: PeKind::Dos,
+        pe32_plus: false,
+        machine: 0,
+        subsystem: 0,
+        dotnet: false,
+    };
+
+    // DOS programs may keep arbitrary data at 0x3C, so a bad `e_lfanew` just means DOS.
+    let e_lfanew = littleendian_bytes(&buf[0x3C..0x40]) as usize;
+    if e_lfanew < 0x40 || e_lfanew.saturating_add(4) > buf.len() {
+        return Some(dos);
+    }
+
+    let kind = match &buf[e_lfanew..e_lfanew + 2] {
+        b"NE" => PeKind::Ne,
+        b"LE" => PeKind::Le,
+        b"LX" => PeKind::Lx,
+        b"PE" if buf[e_lfanew + 2..e_lfanew + 4] == [0, 0] => {
+            return Some(parse_pe(buf, e_lfanew + 4).unwrap_or(dos))
+        }
+        _ => return Some(dos),
+    };
+
+    Some(PeInfo { kind, ..dos })
+}
+
+// `offset` points at the COFF file header following the `PE\0\0` signature.
+fn parse_pe(buf: &[u8], offset: usize) -> Option<PeInfo> {
+    let coff = buf.get(offset..offset + 20)?;
+    let machine = read_u16(coff, 0);
+    let characteristics = read_u16(coff, 18);
+
+    let optional = buf.get(offset + 20..)?;
+    if optional.len() < 72 {
+        return None;
+    }
+    let (pe32_plus, directories) = match read_u16(optional, 0) {
+        IMAGE_NT_OPTIONAL_HDR32_MAGIC => (false, 92),
+        IMAGE_NT_OPTIONAL_HDR64_MAGIC => (true, 108),
+        _ => return None,
+    };
+    let subsystem = read_u16(optional, 68);
+
+    let dotnet = optional.len() >= directories + 4 && {
+        let count = littleendian_bytes(&optional[directories..directories + 4]) as usize;
+        let clr = directories + 4 + IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR * 8;
+        count > IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR
+            && optional.len() >= clr + 8
+            && littleendian_bytes(&optional[clr..clr + 4]) != 0
+            && littleendian_bytes(&optional[clr + 4..clr + 8]) != 0
+    };
+
+    let dll = characteristics & IMAGE_FILE_DLL != 0;
+    let kind = match subsystem {
+        IMAGE_SUBSYSTEM_EFI_APPLICATION..=IMAGE_SUBSYSTEM_EFI_ROM => PeKind::Efi,
+        IMAGE_SUBSYSTEM_NATIVE
+            if !dll
+                && (read_u16(optional, 70) & IMAGE_DLLCHARACTERISTICS_WDM_DRIVER != 0
+                    || imports_kernel(buf, offset, directories)) =>
+        {
+            PeKind::Driver
+        }
+        _ if dll => PeKind::Dll,
+        _ => PeKind::Exe,
+    };
+
+    Some(PeInfo {
+        kind,
+        pe32_plus,
+        machine,
+        subsystem,
+        dotnet,
+    })
+}
+
+// Whether the import directory names the kernel or the HAL. Imports outside `buf` are
+// not seen.
+fn imports_kernel(buf: &[u8], offset: usize, directories: usize) -> bool {
+    let optional = offset + 20;
+    let entry = optional + directories + 4 + IMAGE_DIRECTORY_ENTRY_IMPORT * 8;
+    let count = buf.get(optional + directories..optional + directories + 4);
+    if count.is_none_or(|count| littleendian_bytes(count) as usize <= IMAGE_DIRECTORY_ENTRY_IMPORT)
+    {
+        return false;
+    }
+    let rva = match buf.get(entry..entry + 4) {
+        Some(rva) if rva != [0; 4] => littleendian_bytes(rva),
+        _ => return false,
+    };
+    let sections = optional + read_u16(buf, offset + 16) as usize;
+    let count = read_u16(buf, offset + 2) as usize;
+    let to_offset = |rva: u32| -> Option<usize> {
+        (0..count).find_map(|i| {
+            let header = buf.get(sections + 40 * i..sections + 40 * (i + 1))?;
+            let virtual_size = littleendian_bytes(&header[8..12]);
+            let address = littleendian_bytes(&header[12..16]);
+            let raw_size = littleendian_bytes(&header[16..20]);
+            let delta = rva.checked_sub(address)?;
+            (delta < virtual_size.max(raw_size))
+                .then(|| littleendian_bytes(&header[20..24]) as usize + delta as usize)
+        })
+    };
+
+    let descriptors = match to_offset(rva) {
+        Some(descriptors) => descriptors,
+        None => return false,
+    };
+    (0..MAX_IMPORTS)
+        .map_while(|i| buf.get(descriptors + 20 * i..descriptors + 20 * (i + 1)))
+        .take_while(|descriptor| *descriptor != [0; 20])
+        .filter_map(|descriptor| to_offset(littleendian_bytes(&descriptor[12..16])))
+        .filter_map(|name| {
+            let name = buf.get(name..)?;
+            Some(&name[..name.iter().position(|b| *b == 0)?])
+        })
+        .any(|name| KERNEL_IMPORTS.iter().any(|k| name.eq_ignore_ascii_case(k)))
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+#[cfg(test)]
+fn build_pe(machine: u16, characteristics: u16, magic: u16, subsystem: u16, clr: bool) -> Vec<u8> {
+    let mut buf = vec![0_u8; 0x80];
+    buf[..2].copy_from_slice(b"MZ");
+    buf[0x3C] = 0x80;
+    buf.extend_from_slice(b"PE\0\0");
+
+    let mut coff = [0_u8; 20];
+    coff[..2].copy_from_slice(&machine.to_le_bytes());
+    coff[18..].copy_from_slice(&characteristics.to_le_bytes());
+    buf.extend_from_slice(&coff);
+
+    let directories = if magic == IMAGE_NT_OPTIONAL_HDR64_MAGIC {
+        108
+    } else {
+        92
+    };
+    let mut optional = vec![0_u8; directories + 4 + 16 * 8];
+    optional[..2].copy_from_slice(&magic.to_le_bytes());
+    optional[68..70].copy_from_slice(&subsystem.to_le_bytes());
+    optional[directories] = 16;
+    if clr {
+        let at = directories + 4 + 14 * 8;
+        optional[at..at + 8].copy_from_slice(&[0, 0x20, 0, 0, 0x48, 0, 0, 0]);
+    }
+    buf.extend(optional);
+    buf
+}
+
+// Adds an import section listing `dlls` to an image from `build_pe`.
+#[cfg(test)]
+fn with_imports(mut buf: Vec<u8>, dlls: &[&str]) -> Vec<u8> {
+    let optional = 0x84 + 20;
+    let directories = if read_u16(&buf, optional) == IMAGE_NT_OPTIONAL_HDR64_MAGIC {
+        108
+    } else {
+        92
+    };
+    let size_of_optional = (buf.len() - optional) as u16;
+    buf[0x86..0x88].copy_from_slice(&1_u16.to_le_bytes());
+    buf[0x94..0x96].copy_from_slice(&size_of_optional.to_le_bytes());
+    let entry = optional + directories + 4 + IMAGE_DIRECTORY_ENTRY_IMPORT * 8;
+    buf[entry..entry + 4].copy_from_slice(&0x1000_u32.to_le_bytes());
+
+    let raw = buf.len() as u32 + 40;
+    let mut header = [0_u8; 40];
+    header[..6].copy_from_slice(b".idata");
+    header[8..12].copy_from_slice(&0x1000_u32.to_le_bytes());
+    header[12..16].copy_from_slice(&0x1000_u32.to_le_bytes());
+    header[16..20].copy_from_slice(&0x200_u32.to_le_bytes());
+    header[20..24].copy_from_slice(&raw.to_le_bytes());
+    buf.extend_from_slice(&header);
+
+    let mut names = vec![];
+    let mut descriptors = vec![];
+    for dll in dlls {
+        let rva = 0x1000 + 20 * (dlls.len() as u32 + 1) + names.len() as u32;
+        let mut descriptor = [0_u8; 20];
+        descriptor[12..16].copy_from_slice(&rva.to_le_bytes());
+        descriptors.extend_from_slice(&descriptor);
+        names.extend_from_slice(dll.as_bytes());
+        names.push(0);
+    }
+    descriptors.extend_from_slice(&[0; 20]);
+    buf.extend(descriptors);
+    buf.extend(names);
+    buf
+}
+
+#[test]
+fn test_pe_kinds() {
+    let exe = parse(&build_pe(0x8664, 0x0022, 0x20B, 3, false)).unwrap();
+    assert_eq!(PeKind::Exe, exe.kind);
+    assert!(exe.pe32_plus);
+    assert_eq!("amd64", exe.machine_name());
+    assert_eq!("windows-cui", exe.subsystem_name());
+
+    let dll = parse(&build_pe(0x014C, 0x2102, 0x10B, 2, true)).unwrap();
+    assert_eq!(PeKind::Dll, dll.kind);
+    assert!(!dll.pe32_plus);
+    assert!(dll.dotnet);
+
+    let sys = build_pe(0x8664, 0x0022, 0x20B, 1, false);
+    let sys = parse(&with_imports(sys, &["HAL.dll", "ntoskrnl.exe"])).unwrap();
+    assert_eq!(PeKind::Driver, sys.kind);
+    let mut wdm = build_pe(0x014C, 0x0102, 0x10B, 1, false);
+    wdm[0x98 + 70..0x98 + 72].copy_from_slice(&0x2000_u16.to_le_bytes());
+    assert_eq!(PeKind::Driver, parse(&wdm).unwrap().kind);
+    // Native user-mode programs, like smss.exe, import from ntdll.dll only.
+    let smss = build_pe(0x8664, 0x0022, 0x20B, 1, false);
+    let smss = parse(&with_imports(smss, &["ntdll.dll"])).unwrap();
+    assert_eq!((PeKind::Exe, "native"), (smss.kind, smss.subsystem_name()));
+    let efi = parse(&build_pe(0xAA64, 0x0022, 0x20B, 10, false)).unwrap();
+    assert_eq!(PeKind::Efi, efi.kind);
+}
+
+#[test]
+fn test_pe_legacy() {
+    let mut dos = vec![0_u8; 0x40];
+    dos[..2].copy_from_slice(b"MZ");
+    assert_eq!(PeKind::Dos, parse(&dos).unwrap().kind);
+    // e_lfanew pointing past the buffer
+    dos[0x3D] = 0x10;
+    assert_eq!(PeKind::Dos, parse(&dos).unwrap().kind);
+
+    let mut ne = vec![0_u8; 0x40];
+    ne[..2].copy_from_slice(b"MZ");
+    ne[0x3C] = 0x40;
+    ne.extend_from_slice(b"NE\x05\x0A");
+    assert_eq!(PeKind::Ne, parse(&ne).unwrap().kind);
+    assert_eq!(0, parse(&ne).unwrap().machine);
+    assert!(parse(b"ZM").is_none());
+}
@@ -0,0 +1,976 @@
+// Dimensions and pixel format of raster images, and the size of SVG drawings, read from
+// their headers without touching the pixel data. Each format keeps this in a fixed
+// header or in the first chunks, except JPEG, whose frame header may follow large EXIF
+// or ICC segments. Camera RAW files built on TIFF are read as TIFF, RW2 from its sensor
+// borders and CR3 from the TIFF in its `CMT1` box. Fujifilm RAF, Minolta MRW and Sigma
+// X3F files and animated cursors (ANI) are not read.
+// ref: https://www.w3.org/TR/png-3/#11IHDR and #acTL-chunk
+// ref: ITU-T T.81, B.1.1.3 and B.2.2
+// ref: https://www.w3.org/Graphics/GIF/spec-gif89a.txt
+// ref: https://learn.microsoft.com/en-us/windows/win32/gdi/bitmap-header-types
+// ref: https://developers.google.com/speed/webp/docs/riff_container
+// ref: Adobe Photoshop File Formats Specification, File Header Section
+// ref: ISO/IEC 23008-12, 6.5.3 (ispe), 6.5.6 (pixi), 6.5.8 (auxC)
+// ref: ISO/IEC 15444-1, I.5.3 (JP2 Header box)
+// ref: https://openexr.com/en/latest/OpenEXRFileLayout.html
+// ref: ITU-T T.832, A.5 (JPEG XR image file directory)
+// ref: https://www.w3.org/TR/SVG2/struct.html#SVGElement
+// ref: https://exiftool.org/TagNames/PanasonicRaw.html
+// ref: https://github.com/lclevy/canon_cr3
+use super::{
+    be16, be32, gzip,
+    image::{get_ftyp, is_iso_bmf},
+    isobmff, le16, le32,
+    markup::{self, Markup},
+    riff, text,
+    tiff::{self, Tiff},
+};
+use crate::constants::NUM_SIGNATURE_BYTES;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorType {
+    // One bit per pixel, black and white.
+    Bilevel,
+    Grayscale,
+    Indexed,
+    Rgb,
+    YCbCr,
+    Cmyk,
+    Lab,
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    // Bits per channel, or per palette index for indexed color.
+    pub bit_depth: Option<u8>,
+    pub color_type: ColorType,
+    pub alpha: bool,
+    // None when the inspected bytes do not settle the count, e.g. a long animated GIF.
+    pub frames: Option<u32>,
+}
+
+// The outcome of walking JPEG segments up to the frame header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JpegScan {
+    Frame(ImageInfo),
+    // The segment at this offset is not wholly in the buffer.
+    Next(usize),
+    Invalid,
+}
+
+const TAG_IMAGE_WIDTH: u16 = 0x0100;
+const TAG_IMAGE_LENGTH: u16 = 0x0101;
+const TAG_BITS_PER_SAMPLE: u16 = 0x0102;
+const TAG_EXTRA_SAMPLES: u16 = 0x0152;
+
+// The raw image of an RW2 lies within these borders of the sensor.
+const TAG_RW2_SENSOR_WIDTH: u16 = 0x0002;
+const TAG_RW2_SENSOR_HEIGHT: u16 = 0x0003;
+const TAG_RW2_TOP_BORDER: u16 = 0x0004;
+const TAG_RW2_LEFT_BORDER: u16 = 0x0005;
+const TAG_RW2_BOTTOM_BORDER: u16 = 0x0006;
+const TAG_RW2_RIGHT_BORDER: u16 = 0x0007;
+const TAG_RW2_BITS_PER_SAMPLE: u16 = 0x000A;
+
+// IFDs followed when counting the pages of a TIFF.
+const MAX_PAGES: u32 = 1024;
+
+const TAG_JXR_PIXEL_FORMAT: u16 = 0xBC01;
+const TAG_JXR_IMAGE_WIDTH: u16 = 0xBC80;
+const TAG_JXR_IMAGE_HEIGHT: u16 = 0xBC81;
+// The WIC pixel format GUIDs of JPEG XR share all but their last byte.
+const JXR_PIXEL_FORMAT_PREFIX: [u8; 15] = [
+    0x24, 0xC3, 0xDD, 0x6F, 0x03, 0x4E, 0xFE, 0x4B, 0xB1, 0x85, 0x3D, 0x77, 0x76, 0x8D, 0xC9,
+];
+
+// CSS pixels per unit of the absolute SVG lengths.
+const SVG_UNITS: [(&str, f64); 6] = [
+    ("px", 1.0),
+    ("in", 96.0),
+    ("cm", 96.0 / 2.54),
+    ("mm", 96.0 / 25.4),
+    ("pt", 96.0 / 72.0),
+    ("pc", 16.0),
+];
+
+const HEIF_BRANDS: [&str; 6] = ["heic", "heix", "mif1", "msf1", "avif", "avis"];
+// The `uuid` box in `moov` that holds the metadata of a CR3.
+const CR3_UUID: [u8; 16] = [
+    0x85, 0xC0, 0xB6, 0x87, 0x82, 0x0F, 0x11, 0xE0, 0x81, 0x11, 0xF4, 0xCE, 0x46, 0x2B, 0x6A, 0x48,
+];
+const ALPHA_AUX_TYPES: [&[u8]; 2] = [
+    b"urn:mpeg:mpegB:cicp:systems:auxiliary:alpha",
+    b"urn:mpeg:hevc:2015:auxid:1",
+];
+
+pub fn parse(buf: &[u8]) -> Option<ImageInfo> {
+    match buf.get(..4)? {
+        [0x89, b'P', b'N', b'G'] => png(buf),
+        [0xFF, 0xD8, 0xFF, _] => match jpeg_scan(buf, 2) {
+            JpegScan::Frame(info) => Some(info),
+            _ => None,
+        },
+        b"GIF8" => gif(buf),
+        [b'B', b'M', _, _] => bmp(buf),
+        b"RIFF" => webp(buf),
+        // TIFF, and the ORF and RW2 variants with their own magic numbers.
+        b"II*\0" | b"MM\0*" | b"IIRO" | b"IIRS" | b"MMOR" | b"IIU\0" => tiff_image(buf),
+        b"8BPS" => psd(buf),
+        // Icons and cursors.
+        [0, 0, 1 | 2, 0] => ico(buf),
+        [b'I', b'I', 0xBC, _] => jxr(buf),
+        [0x76, 0x2F, 0x31, 0x01] => exr(buf),
+        [0, 0, 0, 0x0C] if buf.get(4..8) == Some(b"jP  ") => jp2(buf),
+        [0x1F, 0x8B, _, _] => svg(&gzip::inflate_prefix(buf, NUM_SIGNATURE_BYTES)?),
+        _ if is_iso_bmf(buf) && get_ftyp(buf).0 == "crx " => cr3(buf),
+        _ if is_iso_bmf(buf) => heif(buf),
+        _ => svg(buf),
+    }
+}
+
+fn png(buf: &[u8]) -> Option<ImageInfo> {
+    let ihdr = buf.get(8..29)?;
+    if &ihdr[4..8] != b"IHDR" {
+        return None;
+    }
+    let (color_type, mut alpha) = match ihdr[17] {
+        0 => (ColorType::Grayscale, false),
+        2 => (ColorType::Rgb, false),
+        3 => (ColorType::Indexed, false),
+        4 => (ColorType::Grayscale, true),
+        6 => (ColorType::Rgb, true),
+        _ => return None,
+    };
+
+    // acTL and tRNS both come before the image data.
+    let mut frames = None;
+    let mut offset = 8;
+    while let Some(chunk) = buf.get(offset..offset + 8) {
+        let data = &buf[offset + 8..];
+        match &chunk[4..8] {
+            b"acTL" => frames = be32(data, 0).or(frames),
+            b"tRNS" => alpha = true,
+            b"IDAT" => {
+                frames = frames.or(Some(1));
+                break;
+            }
+            _ => {}
+        }
+        offset += 12 + be32(chunk, 0)? as usize;
+    }
+
+    Some(ImageInfo {
+        width: be32(ihdr, 8)?,
+        height: be32(ihdr, 12)?,
+        bit_depth: Some(ihdr[16]),
+        color_type,
+        alpha,
+        frames,
+    })
+}
+
+// Walks the marker segments from `offset` to the first frame header (SOFn).
+pub fn jpeg_scan(buf: &[u8], mut offset: usize) -> JpegScan {
+    loop {
+        let (Some(marker), Some(len)) = (buf.get(offset..offset + 2), be16(buf, offset + 2)) else {
+            return JpegScan::Next(offset);
+        };
+        if marker[0] != 0xFF {
+            return JpegScan::Invalid;
+        }
+        match marker[1] {
+            // Fill bytes before a marker.
+            0xFF => offset += 1,
+            // Markers without a length.
+            0x01 | 0xD0..=0xD8 => offset += 2,
+            // DHT, JPG and DAC share the SOFn range.
+            0xC0..=0xCF if !matches!(marker[1], 0xC4 | 0xC8 | 0xCC) => {
+                let sof = buf.get(offset + 4..).unwrap_or_default();
+                let (Some(&bits), Some(height), Some(width), Some(&components)) =
+                    (sof.first(), be16(sof, 1), be16(sof, 3), sof.get(5))
+                else {
+                    return JpegScan::Next(offset);
+                };
+                let color_type = match components {
+                    1 => ColorType::Grayscale,
+                    3 => ColorType::YCbCr,
+                    4 => ColorType::Cmyk,
+                    _ => ColorType::Other,
+                };
+                return JpegScan::Frame(ImageInfo {
+                    width: width.into(),
+                    height: height.into(),
+                    bit_depth: Some(bits),
+                    color_type,
+                    alpha: false,
+                    frames: Some(1),
+                });
+            }
+            // Image data or the end of the image before any frame header.
+            0xD9 | 0xDA => return JpegScan::Invalid,
+            _ => offset += 2 + len as usize,
+        }
+    }
+}
+
+// The end of a run of GIF data sub-blocks starting at `offset`.
+fn gif_sub_blocks_end(buf: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        match *buf.get(offset)? {
+            0 => return Some(offset + 1),
+            len => offset += 1 + len as usize,
+        }
+    }
+}
+
+fn gif(buf: &[u8]) -> Option<ImageInfo> {
+    let header = buf.get(..13)?;
+    let packed = header[10];
+    let table_len = |packed: u8| match packed & 0x80 {
+        0 => 0,
+        _ => 3 << ((packed & 0x07) + 1),
+    };
+
+    let mut alpha = false;
+    let mut images = 0;
+    let mut offset = 13 + table_len(packed);
+    let frames = loop {
+        let next = match buf.get(offset) {
+            Some(0x3B) => break Some(images),
+            Some(0x21) => {
+                // A Graphic Control Extension with the transparency flag set.
+                if buf.get(offset + 1) == Some(&0xF9) {
+                    alpha |= buf.get(offset + 3).is_some_and(|p| p & 1 != 0);
+                }
+                gif_sub_blocks_end(buf, offset + 2)
+            }
+            Some(0x2C) => {
+                images += 1;
+                buf.get(offset + 9)
+                    .and_then(|p| gif_sub_blocks_end(buf, offset + 11 + table_len(*p)))
+            }
+            _ => None,
+        };
+        match next {
+            Some(n) => offset = n,
+            None => break None,
+        }
+    };
+
+    Some(ImageInfo {
+        width: le16(header, 6)?.into(),
+        height: le16(header, 8)?.into(),
+        bit_depth: (packed & 0x80 != 0).then_some((packed & 0x07) + 1),
+        color_type: ColorType::Indexed,
+        alpha,
+        frames,
+    })
+}
+
+fn bmp(buf: &[u8]) -> Option<ImageInfo> {
+    let header_size = le32(buf, 14)?;
+    let (width, height, bpp, compression) = match header_size {
+        // OS/2 BITMAPCOREHEADER
+        12 => {
+            let h = buf.get(18..26)?;
+            let (width, height) = (le16(h, 0)?.into(), le16(h, 2)?.into());
+            (width, height, le16(h, 6)?, 0)
+        }
+        40.. => {
+            let h = buf.get(18..34)?;
+            let width = i32::from_le_bytes([h[0], h[1], h[2], h[3]]).unsigned_abs();
+            // Negative for rows stored top-down.
+            let height = i32::from_le_bytes([h[4], h[5], h[6], h[7]]).unsigned_abs();
+            (width, height, le16(h, 10)?, le32(h, 12)?)
+        }
+        _ => return None,
+    };
+    if width == 0 || height == 0 || bpp == 0 {
+        return None;
+    }
+
+    // BI_BITFIELDS and BI_ALPHABITFIELDS with a v3+ header carry an alpha mask.
+    let alpha = header_size >= 56
+        && matches!(compression, 3 | 6)
+        && le32(buf, 66).is_some_and(|mask| mask != 0);
+    let (color_type, bit_depth) = match bpp {
+        1..=8 => (ColorType::Indexed, bpp),
+        16 => (ColorType::Rgb, 5),
+        _ => (ColorType::Rgb, 8),
+    };
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth: Some(bit_depth as u8),
+        color_type,
+        alpha,
+        frames: Some(1),
+    })
+}
+
+fn webp(buf: &[u8]) -> Option<ImageInfo> {
+    let riff = riff::parse(buf)?;
+    if &riff.form != b"WEBP" {
+        return None;
+    }
+    let first = riff.chunks.first()?;
+    let d = first.data;
+    let info = |width, height, color_type, alpha, frames| ImageInfo {
+        width,
+        height,
+        bit_depth: Some(8),
+        color_type,
+        alpha,
+        frames,
+    };
+
+    match &first.id {
+        b"VP8 " if d.get(3..6) == Some(&[0x9D, 0x01, 0x2A]) => Some(info(
+            u32::from(le16(d, 6)? & 0x3FFF),
+            u32::from(le16(d, 8)? & 0x3FFF),
+            ColorType::YCbCr,
+            false,
+            Some(1),
+        )),
+        b"VP8L" if d.first() == Some(&0x2F) => {
+            let bits = le32(d, 1)?;
+            Some(info(
+                (bits & 0x3FFF) + 1,
+                ((bits >> 14) & 0x3FFF) + 1,
+                ColorType::Rgb,
+                bits >> 28 & 1 != 0,
+                Some(1),
+            ))
+        }
+        b"VP8X" => {
+            let h = d.get(..10)?;
+            let le24 = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], 0]);
+            let animated = h[0] & 0x02 != 0;
+            // Frames can only be counted when the whole file is in the buffer.
+            let complete = (le32(buf, 4)? as usize).saturating_add(8) <= buf.len();
+            let frames = match (animated, complete) {
+                (false, _) => Some(1),
+                (true, true) => {
+                    Some(riff.chunks.iter().filter(|c| &c.id == b"ANMF").count() as u32)
+                }
+                (true, false) => None,
+            };
+            Some(info(
+                le24(&h[4..7]) + 1,
+                le24(&h[7..10]) + 1,
+                ColorType::Rgb,
+                h[0] & 0x10 != 0,
+                frames,
+            ))
+        }
+        _ => None,
+    }
+}
+
+// The first image of a TIFF, also used for TIFF-based RAW files, where it is often the
+// preview.
+fn tiff_image(buf: &[u8]) -> Option<ImageInfo> {
+    let tiff = Tiff::parse(buf)?;
+    let (entries, mut next) = tiff.ifd(tiff.ifd0)?;
+    let value = |tag| tiff::find(&entries, tag).and_then(|e| tiff.value(e));
+
+    let bits = value(TAG_BITS_PER_SAMPLE);
+    let color_type = match value(tiff::TAG_PHOTOMETRIC) {
+        Some(0 | 1) if bits == Some(1) => ColorType::Bilevel,
+        Some(0 | 1) => ColorType::Grayscale,
+        Some(2) => ColorType::Rgb,
+        Some(3) => ColorType::Indexed,
+        Some(5) => ColorType::Cmyk,
+        Some(6) => ColorType::YCbCr,
+        Some(8..=10) => ColorType::Lab,
+        _ => ColorType::Other,
+    };
+
+    let mut pages = 1;
+    let frames = loop {
+        if next == 0 {
+            break Some(pages);
+        }
+        match tiff.ifd(next) {
+            Some((_, n)) if pages < MAX_PAGES => {
+                pages += 1;
+                next = n;
+            }
+            _ => break None,
+        }
+    };
+
+    if tiff::identify(buf) == Some(tiff::Kind::Rw2) {
+        let span = |from, to, whole| match (value(from), value(to)) {
+            (Some(from), Some(to)) if to > from => Some(to - from),
+            _ => value(whole),
+        };
+        return Some(ImageInfo {
+            width: span(
+                TAG_RW2_LEFT_BORDER,
+                TAG_RW2_RIGHT_BORDER,
+                TAG_RW2_SENSOR_WIDTH,
+            )?,
+            height: span(
+                TAG_RW2_TOP_BORDER,
+                TAG_RW2_BOTTOM_BORDER,
+                TAG_RW2_SENSOR_HEIGHT,
+            )?,
+            bit_depth: value(TAG_RW2_BITS_PER_SAMPLE).and_then(|b| u8::try_from(b).ok()),
+            color_type: ColorType::Other,
+            alpha: false,
+            frames: Some(1),
+        });
+    }
+
+    Some(ImageInfo {
+        width: value(TAG_IMAGE_WIDTH)?,
+        height: value(TAG_IMAGE_LENGTH)?,
+        bit_depth: bits.and_then(|b| u8::try_from(b).ok()),
+        color_type,
+        alpha: tiff::find(&entries, TAG_EXTRA_SAMPLES).is_some(),
+        frames,
+    })
+}
+
+// The first IFD of the TIFF in `CMT1` describes the full-size image.
+fn cr3(buf: &[u8]) -> Option<ImageInfo> {
+    let uuid = isobmff::boxes(isobmff::find(buf, &[b"moov"])?.data)
+        .into_iter()
+        .find(|b| &b.kind == b"uuid" && b.data.starts_with(&CR3_UUID))?;
+    tiff_image(isobmff::find(&uuid.data[16..], &[b"CMT1"])?.data)
+}
+
+fn psd(buf: &[u8]) -> Option<ImageInfo> {
+    let h = buf.get(..26)?;
+    let channels = be16(h, 12)?;
+    let (color_type, color_channels) = match be16(h, 24)? {
+        0 => (ColorType::Bilevel, 1),
+        // Duotone images are stored as grayscale.
+        1 | 8 => (ColorType::Grayscale, 1),
+        2 => (ColorType::Indexed, 1),
+        3 => (ColorType::Rgb, 3),
+        4 => (ColorType::Cmyk, 4),
+        7 => (ColorType::Other, channels),
+        9 => (ColorType::Lab, 3),
+        _ => return None,
+    };
+    Some(ImageInfo {
+        width: be32(h, 18)?,
+        height: be32(h, 14)?,
+        bit_depth: u8::try_from(be16(h, 22)?).ok(),
+        color_type,
+        alpha: channels > color_channels,
+        frames: Some(1),
+    })
+}
+
+// The largest image of an icon or cursor. Cursor entries hold the hotspot where icons
+// give the bits per pixel, so those are read from the image itself: a PNG or a
+// BITMAPINFOHEADER.
+fn ico(buf: &[u8]) -> Option<ImageInfo> {
+    let cursor = buf[2] == 2;
+    let count = le16(buf, 4)?;
+    let largest = (0..count as usize)
+        .filter_map(|i| {
+            let e = buf.get(6 + i * 16..22 + i * 16)?;
+            // 0 stands for 256 pixels.
+            let size = |b: u8| if b == 0 { 256 } else { b as u32 };
+            let bpp = match le16(e, 6)?.into() {
+                bpp if bpp != 0 && !cursor => bpp,
+                _ => icon_bpp(buf, le32(e, 12)? as usize).unwrap_or(0),
+            };
+            Some((size(e[0]), size(e[1]), bpp))
+        })
+        .max_by_key(|(w, h, bpp)| (*w as u64 * *h as u64, *bpp))?;
+
+    let (width, height, bpp) = largest;
+    let (color_type, bit_depth) = match bpp {
+        0 => (ColorType::Rgb, None),
+        1..=8 => (ColorType::Indexed, Some(bpp as u8)),
+        _ => (ColorType::Rgb, Some(8)),
+    };
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth,
+        color_type,
+        alpha: bpp == 32,
+        frames: Some(count.into()),
+    })
+}
+
+// The bits per pixel of the icon image at `offset`.
+fn icon_bpp(buf: &[u8], offset: usize) -> Option<u32> {
+    let image = buf.get(offset..)?;
+    if image.starts_with(b"\x89PNG") {
+        let channels = match *image.get(25)? {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        };
+        Some(*image.get(24)? as u32 * channels)
+    } else if image.get(..4) == Some(&[40, 0, 0, 0]) {
+        le16(image, 14).map(u32::from)
+    } else {
+        None
+    }
+}
+
+// JPEG XR: a TIFF-like directory with the dimensions and a pixel format GUID.
+fn jxr(buf: &[u8]) -> Option<ImageInfo> {
+    let ifd = le32(buf, 4)? as usize;
+    let count = le16(buf, ifd)? as usize;
+    let (mut width, mut height, mut format) = (None, None, None);
+    for i in 0..count {
+        let Some(e) = buf.get(ifd + 2 + i * 12..ifd + 14 + i * 12) else {
+            break;
+        };
+        // SHORT or LONG values are stored inline; the 16-byte GUID is not.
+        let value = match le16(e, 2)? {
+            3 => le16(e, 8)?.into(),
+            _ => le32(e, 8)?,
+        };
+        match le16(e, 0)? {
+            TAG_JXR_IMAGE_WIDTH => width = Some(value),
+            TAG_JXR_IMAGE_HEIGHT => height = Some(value),
+            TAG_JXR_PIXEL_FORMAT => {
+                format = buf
+                    .get(value as usize..value as usize + 16)
+                    .filter(|guid| guid[..15] == JXR_PIXEL_FORMAT_PREFIX)
+                    .map(|guid| guid[15]);
+            }
+            _ => {}
+        }
+    }
+
+    // GUID_WICPixelFormat* by their last byte.
+    let (color_type, bit_depth, alpha) = match format {
+        Some(0x05) => (ColorType::Bilevel, Some(1), false),
+        Some(0x08) => (ColorType::Grayscale, Some(8), false),
+        Some(0x0B | 0x13) => (ColorType::Grayscale, Some(16), false),
+        Some(0x11) => (ColorType::Grayscale, Some(32), false),
+        Some(0x09) => (ColorType::Rgb, Some(5), false),
+        Some(0x0C..=0x0E) => (ColorType::Rgb, Some(8), false),
+        Some(0x0F | 0x10) => (ColorType::Rgb, Some(8), true),
+        Some(0x14) => (ColorType::Rgb, Some(10), false),
+        Some(0x12 | 0x15) => (ColorType::Rgb, Some(16), false),
+        Some(0x16 | 0x17 | 0x1D) => (ColorType::Rgb, Some(16), true),
+        Some(0x18 | 0x1B) => (ColorType::Rgb, Some(32), false),
+        Some(0x19 | 0x1A | 0x1E) => (ColorType::Rgb, Some(32), true),
+        Some(0x1C) => (ColorType::Cmyk, Some(8), false),
+        Some(0x1F) => (ColorType::Cmyk, Some(16), false),
+        _ => (ColorType::Other, None, false),
+    };
+    Some(ImageInfo {
+        width: width?,
+        height: height?,
+        bit_depth,
+        color_type,
+        alpha,
+        frames: Some(1),
+    })
+}
+
+// The size of an SVG document: the `width` and `height` of the root element in absolute
+// units, else the `viewBox`. Relative lengths such as percentages leave only the
+// `viewBox`. SVG is drawn on a transparent canvas.
+fn svg(buf: &[u8]) -> Option<ImageInfo> {
+    let text = text::detect(buf)?.decode(buf);
+    if markup::identify(&text) != Some(Markup::Svg) {
+        return None;
+    }
+    let root = markup::prolog(&text)?.root?;
+    let view_box: Vec<f64> = root
+        .attribute("viewBox")
+        .map(|v| {
+            v.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|n| !n.is_empty())
+                .filter_map(|n| n.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let size = |attribute, index| {
+        root.attribute(attribute)
+            .and_then(svg_length)
+            .or_else(|| view_box.get(index).copied().filter(|_| view_box.len() == 4))
+            .map(|n| n.round())
+            .filter(|n| *n >= 1.0 && *n <= u32::MAX as f64)
+            .map(|n| n as u32)
+    };
+
+    Some(ImageInfo {
+        width: size("width", 2)?,
+        height: size("height", 3)?,
+        bit_depth: None,
+        color_type: ColorType::Rgb,
+        alpha: true,
+        frames: Some(1),
+    })
+}
+
+// An absolute SVG length in pixels.
+fn svg_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (number, scale) = SVG_UNITS
+        .iter()
+        .find_map(|(unit, scale)| Some((value.strip_suffix(unit)?, *scale)))
+        .unwrap_or((value, 1.0));
+    number
+        .trim_end()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .map(|n| n * scale)
+}
+
+// HEIF and AVIF: the item properties of the `meta` box.
+fn heif(buf: &[u8]) -> Option<ImageInfo> {
+    let (major, _, compatible) = get_ftyp(buf);
+    if !HEIF_BRANDS.contains(&major) && !compatible.iter().any(|b| HEIF_BRANDS.contains(b)) {
+        return None;
+    }
+    let properties = isobmff::find(buf, &[b"meta", b"iprp", b"ipco"])?.children();
+    let find = |kind: &'static [u8; 4]| properties.iter().filter(move |p| &p.kind == kind);
+
+    // A grid image has an `ispe` per tile as well as its own, which is the largest.
+    let (width, height) = find(b"ispe")
+        .filter_map(|p| Some((be32(p.data, 4)?, be32(p.data, 8)?)))
+        .max_by_key(|(w, h)| *w as u64 * *h as u64)?;
+
+    let mut color_type = ColorType::YCbCr;
+    let mut bit_depth = None;
+    if let Some(av1c) = find(b"av1C").next().and_then(|p| p.data.get(2)) {
+        bit_depth = Some(match av1c & 0x60 {
+            0x60 => 12,
+            0x40 => 10,
+            _ => 8,
+        });
+        if av1c & 0x10 != 0 {
+            color_type = ColorType::Grayscale;
+        }
+    } else if let Some(hvcc) = find(b"hvcC").next().and_then(|p| p.data.get(16..19)) {
+        if hvcc[0] & 0x03 == 0 {
+            color_type = ColorType::Grayscale;
+        }
+        bit_depth = Some((hvcc[1] & 0x07) + 8);
+    }
+    // pixi lists the bits of each channel explicitly.
+    if let Some(bits) = find(b"pixi").next().and_then(|p| p.data.get(5)) {
+        bit_depth = Some(*bits);
+    }
+
+    let alpha = find(b"auxC").any(|p| {
+        p.data
+            .get(4..)
+            .is_some_and(|t| ALPHA_AUX_TYPES.iter().any(|a| t.starts_with(a)))
+    });
+    // Image sequences keep their frames in tracks.
+    let frames = isobmff::find(buf, &[b"moov"]).is_none().then_some(1);
+
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth,
+        color_type,
+        alpha,
+        frames,
+    })
+}
+
+fn jp2(buf: &[u8]) -> Option<ImageInfo> {
+    let header = isobmff::find(buf, &[b"jp2h"])?.children();
+    let ihdr = header.iter().find(|b| &b.kind == b"ihdr")?.data.get(..11)?;
+    let components = be16(ihdr, 8)?;
+
+    // Enumerated colourspaces of the `colr` box.
+    let enumerated = header
+        .iter()
+        .find(|b| &b.kind == b"colr")
+        .and_then(|b| b.data.get(..7))
+        .filter(|d| d[0] == 1)
+        .and_then(|d| be32(d, 3));
+    let color_type = match (enumerated, components) {
+        (Some(16), _) => ColorType::Rgb,
+        (Some(17), _) => ColorType::Grayscale,
+        (Some(18), _) => ColorType::YCbCr,
+        (Some(12), _) => ColorType::Cmyk,
+        (Some(14), _) => ColorType::Lab,
+        (None, 1 | 2) => ColorType::Grayscale,
+        (None, 3 | 4) => ColorType::Rgb,
+        _ => ColorType::Other,
+    };
+    let alpha = header.iter().any(|b| &b.kind == b"cdef")
+        || (color_type != ColorType::Cmyk && matches!(components, 2 | 4));
+
+    Some(ImageInfo {
+        width: be32(ihdr, 4)?,
+        height: be32(ihdr, 0)?,
+        // 255 means the components differ; the low 7 bits hold the depth minus one.
+        bit_depth: (ihdr[10] != 255).then_some((ihdr[10] & 0x7F) + 1),
+        color_type,
+        alpha,
+        frames: Some(1),
+    })
+}
+
+// The `dataWindow` and `channels` attributes of an OpenEXR header.
+fn exr(buf: &[u8]) -> Option<ImageInfo> {
+    let multipart = buf.get(5)? & 0x10 != 0;
+    let mut window = None;
+    let mut channels: Vec<(&[u8], u32)> = vec![];
+    let mut offset = 8;
+    loop {
+        let name_len = buf.get(offset..)?.iter().position(|b| *b == 0)?;
+        if name_len == 0 {
+            break;
+        }
+        let name = &buf[offset..offset + name_len];
+        offset += name_len + 1;
+        offset += buf.get(offset..)?.iter().position(|b| *b == 0)? + 1;
+        let size = le32(buf, offset)? as usize;
+        let value = buf.get(offset + 4..offset + 4 + size)?;
+        offset += 4 + size;
+
+        match name {
+            b"dataWindow" if size == 16 => {
+                let v: Vec<i32> = value
+                    .chunks_exact(4)
+                    .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                window = Some(((v[2] - v[0] + 1) as u32, (v[3] - v[1] + 1) as u32));
+            }
+            b"channels" => {
+                let mut at = 0;
+                while let Some(len) = value.get(at..).and_then(|v| v.iter().position(|b| *b == 0)) {
+                    if len == 0 {
+                        break;
+                    }
+                    let pixel_type = le32(value, at + len + 1)?;
+                    channels.push((&value[at..at + len], pixel_type));
+                    at += len + 17;
+                }
+            }
+            _ => {}
+        }
+        if window.is_some() && !channels.is_empty() {
+            break;
+        }
+    }
+
+    let (width, height) = window?;
+    // The name after the last dot is the channel within its layer.
+    let has = |c: &[u8]| {
+        channels
+            .iter()
+            .any(|(name, _)| name.rsplit(|b| *b == b'.').next() == Some(c))
+    };
+    let color_type = if has(b"R") && has(b"G") && has(b"B") {
+        ColorType::Rgb
+    } else if has(b"Y") && has(b"RY") {
+        ColorType::YCbCr
+    } else if has(b"Y") {
+        ColorType::Grayscale
+    } else {
+        ColorType::Other
+    };
+    // UINT, HALF and FLOAT.
+    let bit_depth = channels.first().and_then(|(_, t)| match t {
+        0 | 2 => Some(32),
+        1 => Some(16),
+        _ => None,
+    });
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth,
+        color_type,
+        alpha: has(b"A"),
+        frames: (!multipart).then_some(1),
+    })
+}
+
+#[test]
+fn test_raster_png_gif() {
+    let mut png = b"\x89PNG\r\n\x1A\n\0\0\0\x0DIHDR\0\0\x01\0\0\0\0\x80\x08\x03\0\0\0".to_vec();
+    png.extend_from_slice(&[0; 4]);
+    png.extend_from_slice(b"\0\0\0\x01tRNS\0");
+    png.extend_from_slice(&[0; 4]);
+    png.extend_from_slice(b"\0\0\0\0IDAT");
+    let info = parse(&png).unwrap();
+    assert_eq!((256, 128), (info.width, info.height));
+    assert_eq!(ColorType::Indexed, info.color_type);
+    assert_eq!(
+        (Some(8), true, Some(1)),
+        (info.bit_depth, info.alpha, info.frames)
+    );
+
+    // 2x1, 4-colour global table, a transparent frame and a plain one.
+    let mut gif = b"GIF89a\x02\0\x01\0\x81\0\0".to_vec();
+    gif.extend_from_slice(&[0; 12]);
+    let frame = [
+        &[0x21, 0xF9, 4, 1, 0, 0, 0, 0][..],
+        &[0x2C, 0, 0, 0, 0, 2, 0, 1, 0, 0, 2, 2, 0x44, 0x01, 0][..],
+    ]
+    .concat();
+    gif.extend_from_slice(&frame);
+    gif.extend_from_slice(&frame[8..]);
+    gif.push(0x3B);
+    let info = parse(&gif).unwrap();
+    assert_eq!((2, 1, Some(2)), (info.width, info.height, info.bit_depth));
+    assert_eq!((true, Some(2)), (info.alpha, info.frames));
+    assert_eq!(None, parse(&gif[..gif.len() - 1]).unwrap().frames);
+}
+
+#[test]
+fn test_raster_jpeg() {
+    // APP0, then a baseline frame header: 8 bits, 480x640, 3 components.
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0, 4, 0, 0];
+    jpeg.extend_from_slice(&[0xFF, 0xC4, 0, 2]);
+    jpeg.extend_from_slice(&[0xFF, 0xC0, 0, 17, 8, 0x01, 0xE0, 0x02, 0x80, 3]);
+    let info = parse(&jpeg).unwrap();
+    assert_eq!((640, 480), (info.width, info.height));
+    assert_eq!(ColorType::YCbCr, info.color_type);
+    assert_eq!(JpegScan::Next(12), jpeg_scan(&jpeg[..14], 2));
+    assert_eq!(JpegScan::Invalid, jpeg_scan(&[0xFF, 0xDA, 0, 2], 0));
+}
+
+#[test]
+fn test_raster_headers() {
+    // BITMAPINFOHEADER, 3x2 bottom-up, 24 bits.
+    let mut bmp = b"BM".to_vec();
+    bmp.extend_from_slice(&[0; 12]);
+    bmp.extend_from_slice(&40_u32.to_le_bytes());
+    bmp.extend_from_slice(&3_i32.to_le_bytes());
+    bmp.extend_from_slice(&(-2_i32).to_le_bytes());
+    bmp.extend_from_slice(&[1, 0, 24, 0, 0, 0, 0, 0]);
+    let info = parse(&bmp).unwrap();
+    assert_eq!(
+        (3, 2, ColorType::Rgb),
+        (info.width, info.height, info.color_type)
+    );
+
+    // RGB with an extra alpha channel, 16 bits.
+    let mut psd = b"8BPS\0\x01".to_vec();
+    psd.extend_from_slice(&[0; 6]);
+    psd.extend_from_slice(&[0, 4, 0, 0, 0, 10, 0, 0, 0, 20, 0, 16, 0, 3]);
+    let info = parse(&psd).unwrap();
+    assert_eq!(
+        (20, 10, Some(16)),
+        (info.width, info.height, info.bit_depth)
+    );
+    assert!(info.alpha);
+
+    // Two icons: 16x16 at 8 bits and 256x256 at 32 bits.
+    let mut ico = vec![0, 0, 1, 0, 2, 0];
+    ico.extend_from_slice(&[16, 16, 0, 0, 1, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    ico.extend_from_slice(&[0, 0, 0, 0, 1, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let info = parse(&ico).unwrap();
+    assert_eq!(
+        (256, 256, true, Some(2)),
+        (info.width, info.height, info.alpha, info.frames)
+    );
+
+    // A cursor whose larger image is a 32-bit BITMAPINFOHEADER; the entries hold hotspots.
+    let mut cur = vec![0, 0, 2, 0, 2, 0];
+    cur.extend_from_slice(&[16, 16, 0, 0, 1, 0, 1, 0, 40, 0, 0, 0, 38, 0, 0, 0]);
+    cur.extend_from_slice(&[32, 32, 0, 0, 4, 0, 4, 0, 40, 0, 0, 0, 78, 0, 0, 0]);
+    cur.extend_from_slice(&[40, 0, 0, 0, 16, 0, 0, 0, 32, 0, 0, 0, 1, 0, 8, 0]);
+    cur.extend_from_slice(&[0; 24]);
+    cur.extend_from_slice(&[40, 0, 0, 0, 32, 0, 0, 0, 64, 0, 0, 0, 1, 0, 32, 0]);
+    let info = parse(&cur).unwrap();
+    assert_eq!(
+        (32, 32, Some(8), true),
+        (info.width, info.height, info.bit_depth, info.alpha)
+    );
+
+    // JPEG XR, 24bppRGB, with the dimensions as SHORT and LONG values.
+    let mut jxr = b"II\xBC\x01\x08\0\0\0\x03\0".to_vec();
+    jxr.extend_from_slice(&[0x01, 0xBC, 1, 0, 16, 0, 0, 0, 50, 0, 0, 0]);
+    jxr.extend_from_slice(&[0x80, 0xBC, 3, 0, 1, 0, 0, 0, 0x20, 0x03, 0, 0]);
+    jxr.extend_from_slice(&[0x81, 0xBC, 4, 0, 1, 0, 0, 0, 0x58, 0x02, 0, 0]);
+    jxr.extend_from_slice(&[0; 4]);
+    jxr.extend_from_slice(&JXR_PIXEL_FORMAT_PREFIX);
+    jxr.push(0x0D);
+    let info = parse(&jxr).unwrap();
+    assert_eq!(
+        (800, 600, ColorType::Rgb, Some(8)),
+        (info.width, info.height, info.color_type, info.bit_depth)
+    );
+
+    // Little-endian TIFF headers with the given magic and SHORT tags in IFD0.
+    let tiff = |magic: &[u8], tags: &[(u16, u16)]| {
+        let mut tiff = magic.to_vec();
+        tiff.extend_from_slice(&8_u32.to_le_bytes());
+        tiff.extend_from_slice(&(tags.len() as u16).to_le_bytes());
+        for (tag, value) in tags {
+            tiff.extend_from_slice(&tag.to_le_bytes());
+            tiff.extend_from_slice(&[3, 0, 1, 0, 0, 0]);
+            tiff.extend_from_slice(&value.to_le_bytes());
+            tiff.extend_from_slice(&[0, 0]);
+        }
+        tiff.extend_from_slice(&[0; 4]);
+        tiff
+    };
+    let orf = tiff(
+        b"IIRO",
+        &[(TAG_IMAGE_WIDTH, 4032), (TAG_IMAGE_LENGTH, 3024)],
+    );
+    let info = parse(&orf).unwrap();
+    assert_eq!((4032, 3024), (info.width, info.height));
+    let rw2 = [
+        (2, 5280),
+        (3, 3688),
+        (4, 8),
+        (5, 8),
+        (6, 3688),
+        (7, 5208),
+        (10, 12),
+    ];
+    let info = parse(&tiff(b"IIU\0", &rw2)).unwrap();
+    assert_eq!(
+        (5200, 3680, Some(12)),
+        (info.width, info.height, info.bit_depth)
+    );
+
+    // CR3: `ftyp`, then `moov` holding the Canon `uuid` box and its `CMT1`.
+    let mp4_box = |kind: &[u8], data: &[u8]| {
+        let mut b = ((data.len() + 8) as u32).to_be_bytes().to_vec();
+        b.extend_from_slice(kind);
+        b.extend_from_slice(data);
+        b
+    };
+    let cmt1 = tiff(
+        b"II*\0",
+        &[(TAG_IMAGE_WIDTH, 6000), (TAG_IMAGE_LENGTH, 4000)],
+    );
+    let cmt1 = mp4_box(b"CMT1", &cmt1);
+    let uuid = mp4_box(b"uuid", &[&CR3_UUID[..], &cmt1].concat());
+    let mut cr3 = mp4_box(b"ftyp", b"crx \0\0\0\x01crx isom");
+    cr3.extend_from_slice(&mp4_box(b"moov", &uuid));
+    let info = parse(&cr3).unwrap();
+    assert_eq!((6000, 4000), (info.width, info.height));
+    assert_eq!(None, parse(b"FUJIFILMCCD-RAW 0201FF383501"));
+
+    // SVG sizes in absolute units, and from the viewBox.
+    let svg = |root: &str| {
+        parse(format!("<svg xmlns=\"http://www.w3.org/2000/svg\" {}/>", root).as_bytes())
+    };
+    let info = svg("width=\"2in\" height=\"48pt\"").unwrap();
+    assert_eq!((192, 64), (info.width, info.height));
+    let info = svg("width=\"100%\" viewBox=\"0 0 320.4, 200\"").unwrap();
+    assert_eq!((320, 200), (info.width, info.height));
+    assert_eq!(None, svg("width=\"10em\""));
+
+    // Lossless WebP, 100x50 with alpha.
+    let bits: u32 = 99 | 49 << 14 | 1 << 28;
+    let mut webp = b"RIFF\x1A\0\0\0WEBPVP8L\x0D\0\0\0\x2F".to_vec();
+    webp.extend_from_slice(&bits.to_le_bytes());
+    webp.extend_from_slice(&[0; 9]);
+    let info = parse(&webp).unwrap();
+    assert_eq!((100, 50, true), (info.width, info.height, info.alpha));
+}
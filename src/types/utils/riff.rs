@@ -0,0 +1,295 @@
+// RIFF and its relatives share one layout: a 12-byte header (`RIFF`/`RIFX`/`RF64`/`BW64`,
+// a 32-bit size and a four-character form type) followed by a sequence of chunks.
+// ref: https://www.loc.gov/preservation/digital/formats/fdd/fdd000025.shtml
+// ref: https://tech.ebu.ch/docs/tech/tech3306v1_1.pdf (RF64)
+// ref: https://www.itu.int/rec/R-REC-BS.2088 (BW64)
+use super::{bigendian_bytes, littleendian_bytes};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Container {
+    Riff,
+    Rifx,
+    Rf64,
+    Bw64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Wave,
+    // Broadcast Wave: a WAVE form carrying a `bext` chunk.
+    Bwf,
+    Avi,
+    // AVI 2.0 (OpenDML): the `hdrl` list carries an `odml` list.
+    Avi2,
+    WebpLossy,
+    WebpLossless,
+    WebpExtended,
+    Ani,
+    Rmid,
+    Sf2,
+    Dls,
+    Cdr,
+    Qcp,
+    Unknown,
+}
+
+pub struct Chunk<'a> {
+    pub id: [u8; 4],
+    // Truncated when the buffer is only a prefix of the file.
+    pub data: &'a [u8],
+    // The size declared in the chunk header, or in `ds64` for the `data` chunk of RF64.
+    pub size: u64,
+    big_endian: bool,
+}
+
+pub struct Riff<'a> {
+    pub container: Container,
+    pub form: [u8; 4],
+    pub chunks: Vec<Chunk<'a>>,
+}
+
+impl Chunk<'_> {
+    // The list type of a `LIST` chunk, e.g. `hdrl` or `INFO`.
+    pub fn list_type(&self) -> Option<&[u8]> {
+        if &self.id == b"LIST" && self.data.len() >= 4 {
+            Some(&self.data[..4])
+        } else {
+            None
+        }
+    }
+
+    // The chunks nested inside a `LIST` chunk.
+    pub fn children(&self) -> Vec<Chunk<'_>> {
+        match self.list_type() {
+            Some(_) => walk(&self.data[4..], self.big_endian, None),
+            None => vec![],
+        }
+    }
+}
+
+impl<'a> Riff<'a> {
+    pub fn find(&self, id: &[u8; 4]) -> Option<&Chunk<'a>> {
+        self.chunks.iter().find(|c| &c.id == id)
+    }
+
+    pub fn find_list(&self, list_type: &[u8; 4]) -> Option<&Chunk<'a>> {
+        self.chunks
+            .iter()
+            .find(|c| c.list_type() == Some(&list_type[..]))
+    }
+
+    pub fn kind(&self) -> Kind {
+        match &self.form {
+            b"WAVE" => {
+                if self.find(b"bext").is_some() {
+                    Kind::Bwf
+                } else {
+                    Kind::Wave
+                }
+            }
+            b"AVI " => match self.find_list(b"hdrl") {
+                Some(hdrl) => {
+                    let children = hdrl.children();
+                    if !children.iter().any(|c| &c.id == b"avih") {
+                        Kind::Unknown
+                    } else if children.iter().any(|c| c.list_type() == Some(&b"odml"[..])) {
+                        Kind::Avi2
+                    } else {
+                        Kind::Avi
+                    }
+                }
+                None => Kind::Unknown,
+            },
+            // The first chunk of a WebP file decides its flavour.
+            b"WEBP" => match self.chunks.first().map(|c| &c.id) {
+                Some(b"VP8 ") => Kind::WebpLossy,
+                Some(b"VP8L") => Kind::WebpLossless,
+                Some(b"VP8X") => Kind::WebpExtended,
+                _ => Kind::Unknown,
+            },
+            b"ACON" => Kind::Ani,
+            b"RMID" => match self.find(b"data") {
+                Some(data) if data.data.starts_with(b"MThd") => Kind::Rmid,
+                _ => Kind::Unknown,
+            },
+            b"sfbk" => Kind::Sf2,
+            b"DLS " => Kind::Dls,
+            b"QLCM" => Kind::Qcp,
+            // CorelDRAW encodes its version in the last byte: `CDR9`, `CDRA`, `cdr7`...
+            [b'C', b'D', b'R', v] | [b'c', b'd', b'r', v] if v.is_ascii_alphanumeric() => Kind::Cdr,
+            _ => Kind::Unknown,
+        }
+    }
+}
+
+pub fn parse(buf: &[u8]) -> Option<Riff<'_>> {
+    if buf.len() < 12 {
+        return None;
+    }
+
+    let container = match &buf[..4] {
+        b"RIFF" => Container::Riff,
+        b"RIFX" => Container::Rifx,
+        b"RF64" => Container::Rf64,
+        b"BW64" => Container::Bw64,
+        _ => return None,
+    };
+    let big_endian = container == Container::Rifx;
+
+    let mut form = [0_u8; 4];
+    form.copy_from_slice(&buf[8..12]);
+    if !is_fourcc(&form) {
+        return None;
+    }
+
+    // RF64/BW64 put the real 64-bit data size into a leading `ds64` chunk.
+    let ds64_data_size = match container {
+        Container::Rf64 | Container::Bw64 if buf.len() >= 36 && buf[12..16] == *b"ds64" => {
+            Some(read_u64_le(&buf[28..36]))
+        }
+        _ => None,
+    };
+
+    Some(Riff {
+        container,
+        form,
+        chunks: walk(&buf[12..], big_endian, ds64_data_size),
+    })
+}
+
+pub fn identify(buf: &[u8]) -> Option<(Container, Kind)> {
+    parse(buf).map(|riff| (riff.container, riff.kind()))
+}
+
+fn walk(buf: &[u8], big_endian: bool, ds64_data_size: Option<u64>) -> Vec<Chunk<'_>> {
+    let mut chunks = vec![];
+    let mut offset = 0_usize;
+
+    while offset + 8 <= buf.len() {
+        let mut id = [0_u8; 4];
+        id.copy_from_slice(&buf[offset..offset + 4]);
+        if !is_fourcc(&id) {
+            break;
+        }
+
+        let raw = if big_endian {
+            bigendian_bytes(&buf[offset + 4..offset + 8])
+        } else {
+            littleendian_bytes(&buf[offset + 4..offset + 8])
+        };
+        let size = match ds64_data_size {
+            Some(s) if raw == u32::MAX && &id == b"data" => s,
+            _ => raw as u64,
+        };
+
+        let start = offset + 8;
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|size| start.checked_add(size));
+        chunks.push(Chunk {
+            id,
+            data: &buf[start..end.unwrap_or(usize::MAX).min(buf.len())],
+            size,
+            big_endian,
+        });
+
+        // Chunks are padded to an even length. A chunk running past the address space
+        // ends the walk.
+        offset = match end.and_then(|end| end.checked_add(end & 1)) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    chunks
+}
+
+fn is_fourcc(id: &[u8; 4]) -> bool {
+    id.iter().all(|b| (0x20..=0x7E).contains(b))
+}
+
+fn read_u64_le(buf: &[u8]) -> u64 {
+    littleendian_bytes(&buf[..4]) as u64 | (littleendian_bytes(&buf[4..8]) as u64) << 32
+}
+
+#[cfg(test)]
+fn build(header: &[u8; 4], form: &[u8; 4], chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut body = form.to_vec();
+    for (id, data) in chunks {
+        body.extend_from_slice(*id);
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+    let mut buf = header.to_vec();
+    buf.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    buf.extend(body);
+    buf
+}
+
+#[test]
+fn test_riff_wave() {
+    let wav = build(b"RIFF", b"WAVE", &[(b"fmt ", &[0; 16]), (b"data", &[0; 3])]);
+    let riff = parse(&wav).unwrap();
+    assert_eq!(Container::Riff, riff.container);
+    assert_eq!(Kind::Wave, riff.kind());
+    assert_eq!(2, riff.chunks.len());
+
+    let bwf = build(
+        b"RIFF",
+        b"WAVE",
+        &[(b"bext", &[0; 602]), (b"fmt ", &[0; 16])],
+    );
+    assert_eq!(Kind::Bwf, parse(&bwf).unwrap().kind());
+
+    let mut ds64 = vec![0_u8; 28];
+    ds64[8..16].copy_from_slice(&4_u64.to_le_bytes());
+    let mut rf64 = build(
+        b"RF64",
+        b"WAVE",
+        &[(b"ds64", &ds64), (b"data", &[0; 4]), (b"LIST", b"INFO")],
+    );
+    rf64[52..56].copy_from_slice(&u32::MAX.to_le_bytes());
+    let riff = parse(&rf64).unwrap();
+    assert_eq!(Container::Rf64, riff.container);
+    assert_eq!(Kind::Wave, riff.kind());
+    assert!(riff.find_list(b"INFO").is_some());
+
+    // A 64-bit data size that runs to the end of the address space.
+    rf64[28..36].copy_from_slice(&u64::MAX.to_le_bytes());
+    let riff = parse(&rf64).unwrap();
+    assert_eq!(2, riff.chunks.len());
+    assert_eq!(u64::MAX, riff.chunks[1].size);
+}
+
+#[test]
+fn test_riff_avi() {
+    let mut hdrl = b"hdrl".to_vec();
+    hdrl.extend_from_slice(b"avih\x04\0\0\0\0\0\0\0");
+    let avi = build(b"RIFF", b"AVI ", &[(b"LIST", &hdrl)]);
+    assert_eq!(Kind::Avi, parse(&avi).unwrap().kind());
+
+    let mut odml = b"odml".to_vec();
+    odml.extend_from_slice(b"dmlh\x04\0\0\0\0\0\0\0");
+    hdrl.extend_from_slice(b"LIST");
+    hdrl.extend_from_slice(&(odml.len() as u32).to_le_bytes());
+    hdrl.extend(odml);
+    let avi2 = build(b"RIFF", b"AVI ", &[(b"LIST", &hdrl)]);
+    assert_eq!(Kind::Avi2, parse(&avi2).unwrap().kind());
+}
+
+#[test]
+fn test_riff_misc() {
+    let webp = build(b"RIFF", b"WEBP", &[(b"VP8L", &[0x2F, 0, 0, 0, 0])]);
+    assert_eq!(Kind::WebpLossless, parse(&webp).unwrap().kind());
+    let webp = build(b"RIFF", b"WEBP", &[(b"JUNK", &[])]);
+    assert_eq!(Kind::Unknown, parse(&webp).unwrap().kind());
+
+    let rmid = build(b"RIFF", b"RMID", &[(b"data", b"MThd\0\0\0\x06")]);
+    assert_eq!(Kind::Rmid, parse(&rmid).unwrap().kind());
+    let cdr = build(b"RIFF", b"CDRA", &[(b"vrsn", &[0; 2])]);
+    assert_eq!(Kind::Cdr, parse(&cdr).unwrap().kind());
+    assert!(parse(b"RIFF\0\0\0\0\x01\x02\x03\x04").is_none());
+}
@@ -0,0 +1,447 @@
+// Stream parameters of audio files, read from the format headers: the `fmt ` chunk of
+// WAVE, `COMM` of AIFF, FLAC STREAMINFO, the Ogg identification headers, the MPEG frame
+// and VBR headers, the `mdhd` and `stsd` boxes of MP4 and the MIDI header chunk.
+// ref: https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/WAVE/WAVE.html
+// ref: https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/AIFF/Docs/AIFF-1.3.pdf
+// ref: https://www.rfc-editor.org/rfc/rfc9639 (FLAC, 8.2)
+// ref: https://xiph.org/vorbis/doc/Vorbis_I_spec.html (4.2.2)
+// ref: https://www.rfc-editor.org/rfc/rfc7845 (Opus in Ogg, 5.1)
+// ref: https://www.rfc-editor.org/rfc/rfc4867 (AMR storage format, 5)
+// ref: ISO/IEC 14496-12, 8.4.2 (mdhd), 12.2.3 (audio sample entry)
+// ref: https://www.midi.org/specifications (Standard MIDI Files 1.0, Header Chunks)
+use super::{be16, be32, isobmff, le16, le32, mpeg, ogg, riff};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioInfo {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    // For PCM and lossless codecs; lossy codecs have no fixed sample size.
+    pub bits_per_sample: Option<u16>,
+    pub duration: Option<Duration>,
+    // Average bit/s.
+    pub bitrate: Option<u32>,
+    pub midi: Option<MidiHeader>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MidiHeader {
+    // 0: a single track, 1: simultaneous tracks, 2: independent sequences.
+    pub format: u16,
+    pub tracks: u16,
+    // Ticks per quarter note, or SMPTE frames and ticks per frame when the top bit is set.
+    pub division: u16,
+}
+
+// WAVE format tags whose sample count is the data size over the block size.
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+// AIFF-C compression types of uncompressed samples.
+const AIFC_PCM: [&[u8; 4]; 6] = [b"NONE", b"sowt", b"twos", b"raw ", b"fl32", b"fl64"];
+
+// MP4 sample entries that store plain or losslessly packed samples.
+const MP4_PCM: [&[u8; 4]; 7] = [
+    b"lpcm", b"sowt", b"twos", b"ipcm", b"fpcm", b"alac", b"fLaC",
+];
+
+// Payload bytes of each AMR frame type; 8 and 9 (or 9 for AMR-WB) are comfort noise.
+const AMR_FRAME_BYTES: [usize; 9] = [12, 13, 15, 17, 19, 20, 26, 31, 5];
+const AMR_WB_FRAME_BYTES: [usize; 10] = [17, 23, 32, 36, 40, 46, 50, 58, 60, 5];
+// Each AMR frame holds 20 ms of speech.
+const AMR_FRAME_MILLIS: u64 = 20;
+
+// Opus always decodes at 48 kHz, and its granule positions count at that rate.
+const OPUS_RATE: u32 = 48000;
+
+pub fn parse(buf: &[u8]) -> Option<AudioInfo> {
+    match buf.get(..4)? {
+        b"RIFF" | b"RIFX" | b"RF64" | b"BW64" => wave(buf),
+        b"FORM" => aiff(buf),
+        b"fLaC" => flac(buf.get(8..)?),
+        b"OggS" => ogg(ogg::parse_page(buf)?.body),
+        b"MThd" => midi(buf),
+        b"#!AM" => amr(buf),
+        _ if super::image::is_iso_bmf(buf) => mp4(buf),
+        _ => mpeg(buf),
+    }
+}
+
+// The duration of an Ogg stream from its first page in `head` and its last in `tail`.
+pub fn ogg_duration(head: &[u8], tail: &[u8]) -> Option<Duration> {
+    let first = ogg::parse_page(head)?;
+    let granule = ogg::last_granule(tail, first.serial)?;
+    let (rate, pre_skip) = match first.body {
+        b if b.starts_with(b"OpusHead") => (OPUS_RATE, le16(b, 10)? as u64),
+        b => (ogg(b)?.sample_rate?, 0),
+    };
+    Some(samples_duration(granule.saturating_sub(pre_skip), rate))
+}
+
+fn samples_duration(samples: u64, rate: u32) -> Duration {
+    Duration::from_nanos((samples as u128 * 1_000_000_000 / rate.max(1) as u128) as u64)
+}
+
+fn bytes_duration(bytes: u64, bytes_per_second: u32) -> Option<Duration> {
+    (bytes_per_second > 0).then(|| samples_duration(bytes, bytes_per_second))
+}
+
+fn wave(buf: &[u8]) -> Option<AudioInfo> {
+    let riff = riff::parse(buf)?;
+    if &riff.form != b"WAVE" {
+        return None;
+    }
+    let big_endian = riff.container == riff::Container::Rifx;
+    let u16_at = |b: &[u8], at| if big_endian { be16(b, at) } else { le16(b, at) };
+    let u32_at = |b: &[u8], at| if big_endian { be32(b, at) } else { le32(b, at) };
+
+    let fmt = riff.find(b"fmt ")?.data.get(..16)?;
+    let tag = u16_at(fmt, 0)?;
+    let sample_rate = u32_at(fmt, 4)?;
+    let byte_rate = u32_at(fmt, 8)?;
+
+    // Compressed formats give the length in samples in a `fact` chunk.
+    let pcm = matches!(
+        tag,
+        WAVE_FORMAT_PCM | WAVE_FORMAT_IEEE_FLOAT | WAVE_FORMAT_EXTENSIBLE
+    );
+    let fact = riff.find(b"fact").and_then(|c| u32_at(c.data, 0));
+    let duration = match (pcm, fact) {
+        (false, Some(samples)) if sample_rate > 0 => {
+            Some(samples_duration(samples as u64, sample_rate))
+        }
+        _ => riff
+            .find(b"data")
+            .and_then(|data| bytes_duration(data.size, byte_rate)),
+    };
+
+    Some(AudioInfo {
+        sample_rate: Some(sample_rate),
+        channels: u16_at(fmt, 2),
+        bits_per_sample: u16_at(fmt, 14).filter(|b| *b > 0),
+        duration,
+        bitrate: byte_rate.checked_mul(8),
+        midi: None,
+    })
+}
+
+// An IEEE 754 80-bit extended float, as AIFF stores its sample rate.
+fn extended(b: &[u8]) -> Option<u32> {
+    let exponent = (be16(b, 0)? & 0x7FFF) as i32 - 16383;
+    let mantissa = u64::from_be_bytes(b[2..10].try_into().ok()?);
+    (0..=63)
+        .contains(&exponent)
+        .then(|| (mantissa >> (63 - exponent)) as u32)
+}
+
+fn aiff(buf: &[u8]) -> Option<AudioInfo> {
+    let form = buf.get(8..12)?;
+    if form != b"AIFF" && form != b"AIFC" {
+        return None;
+    }
+
+    // IFF chunks, big-endian and padded to an even length like RIFF's.
+    let mut offset = 12;
+    let comm = loop {
+        let size = be32(buf, offset + 4)? as usize;
+        if buf.get(offset..offset + 4)? == b"COMM" {
+            break buf.get(offset + 8..offset + 8 + size.min(22))?;
+        }
+        offset += 8 + size + (size & 1);
+    };
+
+    let channels = be16(comm, 0)?;
+    let frames = be32(comm, 2)?;
+    let bits = be16(comm, 6)?;
+    let sample_rate = extended(comm.get(8..18)?)?;
+    let pcm = form == b"AIFF"
+        || comm
+            .get(18..22)
+            .is_some_and(|c| AIFC_PCM.iter().any(|p| *p == c));
+
+    Some(AudioInfo {
+        sample_rate: Some(sample_rate),
+        channels: Some(channels),
+        bits_per_sample: Some(bits),
+        duration: (sample_rate > 0).then(|| samples_duration(frames as u64, sample_rate)),
+        bitrate: pcm.then(|| sample_rate * channels as u32 * bits as u32),
+        midi: None,
+    })
+}
+
+// The STREAMINFO block, the first of a native FLAC stream and of the Ogg FLAC header.
+fn flac(streaminfo: &[u8]) -> Option<AudioInfo> {
+    let b = streaminfo.get(10..18)?;
+    let sample_rate = (b[0] as u32) << 12 | (b[1] as u32) << 4 | (b[2] >> 4) as u32;
+    let channels = ((b[2] >> 1) & 0x07) as u16 + 1;
+    let bits = (((b[2] & 0x01) << 4) | (b[3] >> 4)) as u16 + 1;
+    // 0 when the encoder did not know the length.
+    let samples = ((b[3] & 0x0F) as u64) << 32 | be32(b, 4)? as u64;
+
+    Some(AudioInfo {
+        sample_rate: Some(sample_rate),
+        channels: Some(channels),
+        bits_per_sample: Some(bits),
+        duration: (samples > 0 && sample_rate > 0).then(|| samples_duration(samples, sample_rate)),
+        bitrate: None,
+        midi: None,
+    })
+}
+
+// The identification header: the first packet of the first Ogg page.
+fn ogg(packet: &[u8]) -> Option<AudioInfo> {
+    let (sample_rate, channels, bitrate) = if packet.starts_with(b"\x01vorbis") {
+        let h = packet.get(7..28)?;
+        let nominal = le32(h, 13)? as i32;
+        (
+            le32(h, 5)?,
+            h[4] as u16,
+            (nominal > 0).then_some(nominal as u32),
+        )
+    } else if packet.starts_with(b"OpusHead") {
+        (OPUS_RATE, *packet.get(9)? as u16, None)
+    } else if packet.starts_with(b"\x7FFLAC") {
+        // Mapping header, `fLaC` and the STREAMINFO block header precede STREAMINFO.
+        return flac(packet.get(17..)?);
+    } else if packet.starts_with(b"Speex   ") {
+        let h = packet.get(36..56)?;
+        let bitrate = le32(h, 16)? as i32;
+        (
+            le32(h, 0)?,
+            le32(h, 12)? as u16,
+            (bitrate > 0).then_some(bitrate as u32),
+        )
+    } else {
+        return None;
+    };
+
+    Some(AudioInfo {
+        sample_rate: Some(sample_rate),
+        channels: Some(channels),
+        bits_per_sample: None,
+        duration: None,
+        bitrate,
+        midi: None,
+    })
+}
+
+fn midi(buf: &[u8]) -> Option<AudioInfo> {
+    let h = buf.get(8..14)?;
+    Some(AudioInfo {
+        sample_rate: None,
+        channels: None,
+        bits_per_sample: None,
+        duration: None,
+        bitrate: None,
+        midi: Some(MidiHeader {
+            format: be16(h, 0)?,
+            tracks: be16(h, 2)?,
+            division: be16(h, 4)?,
+        }),
+    })
+}
+
+// Frames are counted when the whole file is in `buf`; otherwise the first frame's mode
+// gives the bitrate.
+fn amr(buf: &[u8]) -> Option<AudioInfo> {
+    let (sample_rate, sizes, start): (u32, &[usize], usize) = if buf.starts_with(b"#!AMR\n") {
+        (8000, &AMR_FRAME_BYTES, 6)
+    } else if buf.starts_with(b"#!AMR-WB\n") {
+        (16000, &AMR_WB_FRAME_BYTES, 9)
+    } else {
+        return None;
+    };
+    let frame_bytes = |toc: u8| sizes.get(((toc >> 3) & 0x0F) as usize).copied();
+
+    let mut offset = start;
+    let mut frames = 0;
+    // NO_DATA frames (type 15) have no payload.
+    while let Some(toc) = buf.get(offset) {
+        offset += 1 + frame_bytes(*toc).unwrap_or(0);
+        frames += 1;
+    }
+    let duration = (offset == buf.len() && frames > 0)
+        .then(|| Duration::from_millis(frames * AMR_FRAME_MILLIS));
+    let bitrate = match duration {
+        Some(d) => Some(((buf.len() - start) as u128 * 8_000 / d.as_millis()) as u32),
+        None => {
+            frame_bytes(*buf.get(start)?).map(|n| (n * 8 * 1000 / AMR_FRAME_MILLIS as usize) as u32)
+        }
+    };
+
+    Some(AudioInfo {
+        sample_rate: Some(sample_rate),
+        channels: Some(1),
+        bits_per_sample: None,
+        duration,
+        bitrate,
+        midi: None,
+    })
+}
+
+// The first sound track of an MP4 file; `moov` must be within `buf`.
+fn mp4(buf: &[u8]) -> Option<AudioInfo> {
+    let moov = isobmff::find(buf, &[b"moov"])?;
+    let mdia = moov
+        .children()
+        .into_iter()
+        .filter(|t| &t.kind == b"trak")
+        .filter_map(|t| isobmff::find(t.data, &[b"mdia"]))
+        .find(|m| {
+            isobmff::find(m.data, &[b"hdlr"]).and_then(|h| h.data.get(8..12)) == Some(b"soun")
+        })?;
+
+    let mdhd = isobmff::find(mdia.data, &[b"mdhd"])?.data;
+    let (timescale, length) = match mdhd.first()? {
+        1 => (
+            be32(mdhd, 20)?,
+            u64::from_be_bytes(mdhd.get(24..32)?.try_into().ok()?),
+        ),
+        _ => (be32(mdhd, 12)?, be32(mdhd, 16)? as u64),
+    };
+    let duration = (timescale > 0).then(|| samples_duration(length, timescale));
+
+    let stsd = isobmff::find(mdia.data, &[b"minf", b"stbl", b"stsd"])?;
+    let entry = isobmff::boxes(stsd.data.get(8..)?).into_iter().next()?;
+    let e = entry.data.get(..28)?;
+    let channels = be16(e, 16)?;
+    let bits = be16(e, 18)?;
+    let sample_rate = match be32(e, 24)? >> 16 {
+        0 => timescale,
+        rate => rate,
+    };
+
+    // QuickTime sound descriptions version 1 and 2 extend the entry before its boxes.
+    let extension = match be16(e, 8)? {
+        1 => 16,
+        2 => 36,
+        _ => 0,
+    };
+    let bitrate = entry
+        .data
+        .get(28 + extension..)
+        .and_then(|b| isobmff::find(b, &[b"esds"]))
+        .and_then(|esds| isobmff::decoder_config(esds.data))
+        .map(|config| config.avg_bitrate)
+        .filter(|avg| *avg > 0);
+
+    Some(AudioInfo {
+        sample_rate: Some(sample_rate),
+        channels: Some(channels),
+        bits_per_sample: MP4_PCM.contains(&&entry.kind).then_some(bits),
+        duration,
+        bitrate,
+        midi: None,
+    })
+}
+
+fn mpeg(buf: &[u8]) -> Option<AudioInfo> {
+    let stream = mpeg::stream(buf)?;
+    Some(AudioInfo {
+        sample_rate: Some(stream.sample_rate),
+        channels: Some(stream.channels).filter(|c| *c > 0),
+        bits_per_sample: None,
+        duration: stream
+            .samples
+            .map(|s| samples_duration(s, stream.sample_rate)),
+        bitrate: stream.bitrate,
+        midi: None,
+    })
+}
+
+#[test]
+fn test_sound_wave_aiff() {
+    // 16-bit stereo at 44.1 kHz, one second of data.
+    let mut fmt = vec![1, 0, 2, 0];
+    fmt.extend_from_slice(&44100_u32.to_le_bytes());
+    fmt.extend_from_slice(&176400_u32.to_le_bytes());
+    fmt.extend_from_slice(&[4, 0, 16, 0]);
+    let mut wav = b"RIFF\0\0\0\0WAVEfmt \x10\0\0\0".to_vec();
+    wav.extend(fmt);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&176400_u32.to_le_bytes());
+    let info = parse(&wav).unwrap();
+    assert_eq!(
+        (Some(44100), Some(2), Some(16)),
+        (info.sample_rate, info.channels, info.bits_per_sample)
+    );
+    assert_eq!(Some(Duration::from_secs(1)), info.duration);
+    assert_eq!(Some(1_411_200), info.bitrate);
+
+    // Mono, 8 bits, 22050 frames at 22.05 kHz (0x400D AC44 ...).
+    let mut aiff = b"FORM\0\0\0\0AIFFCOMM\0\0\0\x12\0\x01".to_vec();
+    aiff.extend_from_slice(&22050_u32.to_be_bytes());
+    aiff.extend_from_slice(&[0, 8, 0x40, 0x0D, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]);
+    let info = parse(&aiff).unwrap();
+    assert_eq!((Some(22050), Some(1)), (info.sample_rate, info.channels));
+    assert_eq!(Some(Duration::from_secs(1)), info.duration);
+}
+
+#[test]
+fn test_sound_flac_ogg_midi() {
+    // STREAMINFO: 48 kHz, stereo, 24 bits, 96000 samples.
+    let mut flac_stream = b"fLaC\x80\0\0\x22".to_vec();
+    flac_stream.extend_from_slice(&[0; 10]);
+    flac_stream.extend_from_slice(&[0x0B, 0xB8, 0x03, 0x70, 0, 0x01, 0x77, 0x00]);
+    flac_stream.extend_from_slice(&[0; 16]);
+    let info = parse(&flac_stream).unwrap();
+    assert_eq!(
+        (Some(48000), Some(2), Some(24)),
+        (info.sample_rate, info.channels, info.bits_per_sample)
+    );
+    assert_eq!(Some(Duration::from_secs(2)), info.duration);
+
+    let page = |granule: u64, packet: &[u8]| {
+        let mut p = b"OggS\0\x02".to_vec();
+        p.extend_from_slice(&granule.to_le_bytes());
+        p.extend_from_slice(&[7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, packet.len() as u8]);
+        p.extend_from_slice(packet);
+        p
+    };
+    let head = page(0, b"OpusHead\x01\x02\x38\x01\x80\xBB\0\0\0\0\0");
+    assert_eq!(Some(2), parse(&head).unwrap().channels);
+    let tail = [&[0; 5][..], &page(48000 + 312, b"audio")].concat();
+    assert_eq!(Some(Duration::from_secs(1)), ogg_duration(&head, &tail));
+
+    let info = parse(b"MThd\0\0\0\x06\0\x01\0\x03\x01\xE0").unwrap();
+    assert_eq!(
+        Some(MidiHeader {
+            format: 1,
+            tracks: 3,
+            division: 480
+        }),
+        info.midi
+    );
+}
+
+#[test]
+fn test_sound_mpeg_amr() {
+    // MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, joint stereo, with a Xing header
+    // counting 100 frames and a LAME tag with 576 samples of delay and 1152 of padding.
+    let mut frame = vec![0xFF, 0xFB, 0x90, 0x40];
+    frame.resize(36, 0);
+    frame.extend_from_slice(b"Xing\0\0\0\x01");
+    frame.extend_from_slice(&100_u32.to_be_bytes());
+    frame.extend_from_slice(b"LAME3.100");
+    frame.resize(frame.len() + 12, 0);
+    frame.extend_from_slice(&[0x24, 0x04, 0x80]);
+    frame.resize(417, 0);
+    let mut cbr = frame[..4].to_vec();
+    cbr.resize(417, 0);
+    let mp3 = [frame, cbr.clone()].concat();
+    let info = parse(&mp3).unwrap();
+    assert_eq!((Some(44100), Some(2)), (info.sample_rate, info.channels));
+    assert_eq!(
+        Some(samples_duration(100 * 1152 - 1728, 44100)),
+        info.duration
+    );
+    assert_eq!(Some(128_000), parse(&cbr.repeat(2)).unwrap().bitrate);
+
+    // Three 12.2 kbit/s frames.
+    let mut amr = b"#!AMR\n".to_vec();
+    amr.extend([&[0x3C][..], &[0; 31]].concat().repeat(3));
+    let info = parse(&amr).unwrap();
+    assert_eq!(Some(Duration::from_millis(60)), info.duration);
+    assert_eq!(Some(12_800), info.bitrate);
+}
@@ -0,0 +1,369 @@
+// Scripts and source code. A `#!` line names the interpreter outright, including when it
+// is run through `env`; without one, each language scores a point for every kind of
+// line characteristic of it (a keyword that opens a declaration, a call to its standard
+// library) and the best score of at least two wins. Languages that extend another, C++
+// over C and TypeScript over JavaScript, repeat its rules and win only on their own.
+// ref: https://man7.org/linux/man-pages/man2/execve.2.html (Interpreter scripts)
+// ref: https://www.gnu.org/software/coreutils/manual/html_node/env-invocation.html
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    Sh,
+    Bash,
+    Zsh,
+    Python,
+    Perl,
+    Ruby,
+    JavaScript,
+    TypeScript,
+    Php,
+    Lua,
+    R,
+    Awk,
+    Rust,
+    C,
+    Cpp,
+    Go,
+    Java,
+    Sql,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shebang {
+    // The program that runs the script, as written: `/bin/sh`, or `python3` after `env`.
+    pub interpreter: String,
+    pub args: Vec<String>,
+}
+
+impl Shebang {
+    // The language of a known interpreter, ignoring its directory and version suffix.
+    pub fn language(&self) -> Option<Language> {
+        let name = self.interpreter.rsplit('/').next().unwrap_or_default();
+        let name = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+        Some(match name {
+            "sh" | "dash" | "ash" | "ksh" | "mksh" | "posh" => Language::Sh,
+            "bash" => Language::Bash,
+            "zsh" => Language::Zsh,
+            "python" | "pypy" => Language::Python,
+            "perl" => Language::Perl,
+            "ruby" | "jruby" => Language::Ruby,
+            "node" | "nodejs" => Language::JavaScript,
+            "deno" | "ts-node" | "tsx" => Language::TypeScript,
+            "php" | "php-cli" => Language::Php,
+            "lua" | "luajit" => Language::Lua,
+            "Rscript" => Language::R,
+            "awk" | "gawk" | "mawk" | "nawk" => Language::Awk,
+            _ => return None,
+        })
+    }
+}
+
+// `(prefix, infix, suffix)` matched against a trimmed line; empty parts match anything.
+type Signal = (&'static str, &'static str, &'static str);
+
+struct Rule {
+    language: Language,
+    // SQL keywords are case-insensitive, so its lines are upper-cased first.
+    upper: bool,
+    signals: &'static [&'static [Signal]],
+}
+
+const RUST: &[Signal] = &[
+    ("fn ", "(", ""),
+    ("pub fn ", "", ""),
+    ("pub struct ", "", ""),
+    ("use std::", "", ""),
+    ("use crate::", "", ""),
+    ("impl", "", "{"),
+    ("#[derive(", "", ""),
+    ("#[test]", "", ""),
+    ("let mut ", "", ""),
+    ("", "println!(", ""),
+    ("", "&self", ""),
+];
+const GO: &[Signal] = &[
+    ("package ", "", ""),
+    ("func ", "(", "{"),
+    ("import (", "", ""),
+    ("", " := ", ""),
+    ("", "fmt.", ""),
+    ("if err != nil", "", ""),
+];
+const JAVA: &[Signal] = &[
+    ("package ", "", ";"),
+    ("import java.", "", ""),
+    ("public class ", "", ""),
+    ("public interface ", "", ""),
+    ("", "public static void main(", ""),
+    ("", "System.out.", ""),
+    ("@Override", "", ""),
+    ("private final ", "", ""),
+];
+const C: &[Signal] = &[
+    ("#include <", "", ">"),
+    ("#include \"", "", "\""),
+    ("#define ", "", ""),
+    ("typedef ", "", ";"),
+    ("", "int main(", ""),
+    ("", "printf(", ""),
+    ("", "malloc(", ""),
+    ("", "sizeof(", ""),
+];
+const CPP: &[Signal] = &[
+    ("", "std::", ""),
+    ("namespace ", "", ""),
+    ("using namespace ", "", ";"),
+    ("template", "<", ""),
+    ("#include <iostream>", "", ""),
+    ("public:", "", ""),
+    ("private:", "", ""),
+];
+const PYTHON: &[Signal] = &[
+    ("def ", "(", ":"),
+    ("class ", "", ":"),
+    ("import ", "", ""),
+    ("from ", " import ", ""),
+    ("if __name__ == ", "", ":"),
+    ("elif ", "", ":"),
+    ("", "self.", ""),
+    ("print(", "", ")"),
+];
+const JAVASCRIPT: &[Signal] = &[
+    ("function ", "(", ""),
+    ("const ", " = ", ""),
+    ("let ", " = ", ""),
+    ("import ", " from ", ""),
+    ("export ", "", ""),
+    ("module.exports", "", ""),
+    ("", "console.log(", ""),
+    ("", "require(", ""),
+    ("", " => ", ""),
+];
+const TYPESCRIPT: &[Signal] = &[
+    ("", "interface ", "{"),
+    ("type ", " = ", ""),
+    ("", ": string", ""),
+    ("", ": number", ""),
+    ("", ": boolean", ""),
+    ("", "): void", ""),
+];
+const SQL: &[Signal] = &[
+    ("SELECT ", "", ""),
+    ("", "FROM ", ""),
+    ("WHERE ", "", ""),
+    ("CREATE TABLE ", "", ""),
+    ("CREATE INDEX ", "", ""),
+    ("INSERT INTO ", "", ""),
+    ("UPDATE ", " SET ", ""),
+    ("DELETE FROM ", "", ""),
+    ("ALTER TABLE ", "", ""),
+    ("DROP TABLE ", "", ""),
+];
+
+// In order of preference when scores tie.
+const RULES: [Rule; 8] = [
+    Rule {
+        language: Language::Rust,
+        upper: false,
+        signals: &[RUST],
+    },
+    Rule {
+        language: Language::Go,
+        upper: false,
+        signals: &[GO],
+    },
+    Rule {
+        language: Language::Java,
+        upper: false,
+        signals: &[JAVA],
+    },
+    Rule {
+        language: Language::C,
+        upper: false,
+        signals: &[C],
+    },
+    Rule {
+        language: Language::Cpp,
+        upper: false,
+        signals: &[C, CPP],
+    },
+    Rule {
+        language: Language::Python,
+        upper: false,
+        signals: &[PYTHON],
+    },
+    Rule {
+        language: Language::JavaScript,
+        upper: false,
+        signals: &[JAVASCRIPT],
+    },
+    Rule {
+        language: Language::TypeScript,
+        upper: false,
+        signals: &[JAVASCRIPT, TYPESCRIPT],
+    },
+];
+const SQL_RULE: Rule = Rule {
+    language: Language::Sql,
+    upper: true,
+    signals: &[SQL],
+};
+
+// Lines inspected by the heuristics.
+const MAX_LINES: usize = 200;
+// Distinct signals needed before a language is reported.
+const MIN_SCORE: usize = 2;
+
+pub fn identify(text: &str) -> Option<Language> {
+    match shebang(text) {
+        Some(shebang) => shebang.language(),
+        None => classify(text),
+    }
+}
+
+// The `#!` line, with the interpreter that `env` would run in place of `env` itself.
+pub fn shebang(text: &str) -> Option<Shebang> {
+    let line = text.strip_prefix("#!")?.lines().next()?;
+    let mut words = line.split_whitespace();
+    let interpreter = words.next()?;
+    let mut args: Vec<&str> = words.collect();
+
+    if interpreter.rsplit('/').next() != Some("env") {
+        return Some(Shebang {
+            interpreter: interpreter.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        });
+    }
+
+    // `env [-i] [-u NAME] [-S] [NAME=VALUE]... COMMAND [ARG]...`
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        match *arg {
+            "-u" | "--unset" | "-C" | "--chdir" | "-P" => i += 2,
+            // `-S` splits the rest of the line, which has been split already.
+            "-S" | "--split-string" | "--split-string=" => i += 1,
+            "-i" | "--ignore-environment" | "-" | "-0" | "-v" => i += 1,
+            "--" => {
+                i += 1;
+                break;
+            }
+            a if a.starts_with("-S") || a.starts_with("--split-string=") => {
+                args[i] = a.strip_prefix("--split-string=").unwrap_or(&a[2..]);
+                break;
+            }
+            a if a.starts_with('-') || a.contains('=') => i += 1,
+            _ => break,
+        }
+    }
+    let (interpreter, args) = args.get(i..)?.split_first()?;
+    Some(Shebang {
+        interpreter: interpreter.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    })
+}
+
+fn classify(text: &str) -> Option<Language> {
+    let lines: Vec<&str> = text
+        .lines()
+        .take(MAX_LINES)
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let upper: Vec<String> = lines.iter().map(|l| l.to_uppercase()).collect();
+
+    let score = |rule: &Rule| -> usize {
+        rule.signals
+            .iter()
+            .flat_map(|s| s.iter())
+            .filter(|signal| match rule.upper {
+                true => upper.iter().any(|l| matches(l, signal)),
+                false => lines.iter().any(|l| matches(l, signal)),
+            })
+            .count()
+    };
+
+    let mut best = (0, None);
+    for rule in RULES.iter().chain([&SQL_RULE]) {
+        let score = score(rule);
+        if score > best.0 {
+            best = (score, Some(rule.language));
+        }
+    }
+    match best {
+        (score, language) if score >= MIN_SCORE => language,
+        _ => None,
+    }
+}
+
+fn matches(line: &str, (prefix, infix, suffix): &Signal) -> bool {
+    line.starts_with(prefix)
+        && line.ends_with(suffix)
+        && line.len() >= prefix.len() + suffix.len()
+        && line[prefix.len()..line.len() - suffix.len()].contains(infix)
+}
+
+#[test]
+fn test_source_shebang() {
+    let language = |s: &str| shebang(s).and_then(|s| s.language());
+    assert_eq!(Some(Language::Sh), language("#!/bin/sh\necho hi\n"));
+    assert_eq!(
+        Some(Language::Bash),
+        language("#! /usr/local/bin/bash -e\n")
+    );
+    assert_eq!(
+        Some(Language::Python),
+        language("#!/usr/bin/env python3.11\n")
+    );
+    assert_eq!(
+        Some(Language::JavaScript),
+        language("#!/usr/bin/env -S node --no-warnings\n")
+    );
+    assert_eq!(
+        Some(Language::Perl),
+        language("#!/usr/bin/env -i PATH=/bin perl -w\n")
+    );
+    assert_eq!(Some(Language::R), language("#!/usr/bin/env Rscript\n"));
+    assert_eq!(Some(Language::Awk), language("#!/usr/bin/gawk -f\n"));
+    assert_eq!(None, language("#!/usr/bin/env\n"));
+    assert_eq!(None, language("#!/opt/custom/tool\n"));
+
+    for line in [
+        "#!/usr/bin/env -Spython3 -u\n",
+        "#!/usr/bin/env --split-string=python3 -u\n",
+    ] {
+        let env = shebang(line).unwrap();
+        assert_eq!("python3", env.interpreter);
+        assert_eq!(vec!["-u"], env.args);
+    }
+}
+
+#[test]
+fn test_source_heuristics() {
+    let rust = "use std::io;\n\nfn main() {\n    let mut s = String::new();\n    println!(\"{}\", s);\n}\n";
+    assert_eq!(Some(Language::Rust), identify(rust));
+
+    let c = "#include <stdio.h>\n\nint main(void) {\n    printf(\"hi\\n\");\n}\n";
+    assert_eq!(Some(Language::C), identify(c));
+    let cpp = "#include <iostream>\n\nint main() {\n    std::cout << \"hi\";\n}\n";
+    assert_eq!(Some(Language::Cpp), identify(cpp));
+
+    let go = "package main\n\nimport (\n\t\"fmt\"\n)\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}\n";
+    assert_eq!(Some(Language::Go), identify(go));
+
+    let java = "package demo;\n\npublic class Main {\n    public static void main(String[] args) {\n        System.out.println(\"hi\");\n    }\n}\n";
+    assert_eq!(Some(Language::Java), identify(java));
+
+    let python = "import os\n\ndef main():\n    print(os.getcwd())\n\nif __name__ == \"__main__\":\n    main()\n";
+    assert_eq!(Some(Language::Python), identify(python));
+
+    let js = "const fs = require('fs');\nmodule.exports = () => console.log(fs);\n";
+    assert_eq!(Some(Language::JavaScript), identify(js));
+    let ts =
+        "export interface User {\n  name: string;\n}\nexport const id = (u: User): number => 1;\n";
+    assert_eq!(Some(Language::TypeScript), identify(ts));
+
+    let sql = "create table users (id integer);\ninsert into users values (1);\nselect * from users where id = 1;\n";
+    assert_eq!(Some(Language::Sql), identify(sql));
+
+    assert_eq!(None, identify("Dear diary,\nToday I wrote some code.\n"));
+}
@@ -0,0 +1,229 @@
+// Streams delimited by `00 00 01` start codes: MPEG-1/2 program streams, MPEG-1/2
+// video elementary streams, and H.264/H.265 in the Annex B byte stream format. The
+// byte after the start code tells them apart: MPEG start codes 0xB0-0xFF have the
+// top bit set, which is the forbidden_zero_bit of an H.264/H.265 NAL unit header.
+// ref: ISO/IEC 13818-1, 2.5.3 (program stream), ISO/IEC 13818-2, 6.2 (video)
+// ref: ITU-T H.264, 7.3.1 and Annex B; ITU-T H.265, 7.3.1.2
+use super::compare_bytes;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    ProgramStream,
+    // A DVD-Video program stream, which opens with a navigation pack.
+    Vob,
+    Mpeg1Video,
+    Mpeg2Video,
+    H264,
+    H265,
+}
+
+const PACK_HEADER: u8 = 0xBA;
+const SYSTEM_HEADER: u8 = 0xBB;
+const PRIVATE_STREAM_2: u8 = 0xBF;
+const SEQUENCE_HEADER: u8 = 0xB3;
+const EXTENSION: u8 = 0xB5;
+const SEQUENCE_EXTENSION_ID: u8 = 1;
+
+const H264_SEI: u8 = 6;
+const H264_SPS: u8 = 7;
+const H264_PPS: u8 = 8;
+const H264_AUD: u8 = 9;
+
+const H265_VPS: u8 = 32;
+const H265_SPS: u8 = 33;
+const H265_PPS: u8 = 34;
+const H265_AUD: u8 = 35;
+const H265_PREFIX_SEI: u8 = 39;
+
+// profile_idc values defined by H.264.
+const H264_PROFILES: [u8; 16] = [
+    44, 66, 77, 83, 86, 88, 100, 110, 118, 122, 128, 134, 135, 138, 139, 244,
+];
+
+// NAL units inspected before giving up on finding the parameter sets.
+const MAX_NAL_UNITS: usize = 16;
+
+pub fn identify(buf: &[u8]) -> Option<Kind> {
+    // Annex B streams may open with a 4-byte start code.
+    let start = match buf {
+        [0, 0, 1, ..] => 3,
+        [0, 0, 0, 1, ..] => 4,
+        _ => return None,
+    };
+
+    match *buf.get(start)? {
+        PACK_HEADER if start == 3 => match pack_header_len(buf)? {
+            len if is_nav_pack(buf, len) => Some(Kind::Vob),
+            _ => Some(Kind::ProgramStream),
+        },
+        SEQUENCE_HEADER if start == 3 => mpeg_video(buf),
+        b if b & 0x80 == 0 => {
+            let units: Vec<&[u8]> = nal_units(buf).take(MAX_NAL_UNITS).collect();
+            if is_h264(&units) {
+                Some(Kind::H264)
+            } else if is_h265(&units) {
+                Some(Kind::H265)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// The length of the pack header, which must be followed by another start code: a system
+// header, a PES packet, another pack or the end code.
+fn pack_header_len(buf: &[u8]) -> Option<usize> {
+    let len = match *buf.get(4)? {
+        // MPEG-2: `01` marker bits, then pack_stuffing_length in byte 13.
+        b if b & 0xC0 == 0x40 => 14 + (buf.get(13)? & 0x07) as usize,
+        // MPEG-1: `0010` marker bits.
+        b if b & 0xF0 == 0x20 => 12,
+        _ => return None,
+    };
+    matches!(buf.get(len..len + 4), Some([0, 0, 1, code]) if *code >= 0xB9).then_some(len)
+}
+
+// A DVD navigation pack: an MPEG-2 pack holding the system header and the presentation
+// control information in a private stream 2 PES packet.
+fn is_nav_pack(buf: &[u8], len: usize) -> bool {
+    if buf[4] & 0xC0 != 0x40 || buf[len + 3] != SYSTEM_HEADER {
+        return false;
+    }
+    let system_len = match buf.get(len + 4..len + 6) {
+        Some(l) => 6 + u16::from_be_bytes([l[0], l[1]]) as usize,
+        None => return false,
+    };
+    compare_bytes(buf, &[0, 0, 1, PRIVATE_STREAM_2], len + system_len)
+}
+
+fn mpeg_video(buf: &[u8]) -> Option<Kind> {
+    let header = buf.get(4..8)?;
+    let width = (header[0] as u16) << 4 | (header[1] >> 4) as u16;
+    let height = ((header[1] & 0x0F) as u16) << 8 | header[2] as u16;
+    let aspect_ratio = header[3] >> 4;
+    let frame_rate = header[3] & 0x0F;
+    if width == 0 || height == 0 || aspect_ratio == 0 || !(1..=8).contains(&frame_rate) {
+        return None;
+    }
+
+    // MPEG-2 requires a sequence_extension right after the sequence header.
+    let mpeg2 = buf
+        .windows(5)
+        .any(|w| w[..4] == [0, 0, 1, EXTENSION] && w[4] >> 4 == SEQUENCE_EXTENSION_ID);
+    Some(if mpeg2 {
+        Kind::Mpeg2Video
+    } else {
+        Kind::Mpeg1Video
+    })
+}
+
+// NAL unit payloads, header byte(s) first, split on `00 00 01`.
+fn nal_units(buf: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut starts = vec![];
+    let mut i = 0;
+    while i + 3 <= buf.len() {
+        if buf[i..i + 3] == [0, 0, 1] {
+            starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .map(|s| s - 3)
+        .chain([buf.len()])
+        .collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .map(move |(s, e)| &buf[s..e.max(s)])
+        .filter(|unit| !unit.is_empty())
+}
+
+// An H.264 stream opens with an access unit delimiter, SEI or SPS, and the first access
+// unit carries both parameter sets.
+fn is_h264(units: &[&[u8]]) -> bool {
+    let header = |unit: &[u8]| (unit[0] & 0x80 == 0).then_some((unit[0] >> 5, unit[0] & 0x1F));
+    let valid_first = match units.first().and_then(|u| header(u)) {
+        Some((_, H264_AUD | H264_SPS)) => true,
+        // SEI messages are never referenced.
+        Some((0, H264_SEI)) => true,
+        _ => false,
+    };
+
+    let sps = units.iter().any(|u| {
+        header(u).is_some_and(|(ref_idc, t)| t == H264_SPS && ref_idc != 0)
+            && u.get(1).is_some_and(|p| H264_PROFILES.contains(p))
+    });
+    let pps = units
+        .iter()
+        .any(|u| header(u).is_some_and(|(ref_idc, t)| t == H264_PPS && ref_idc != 0));
+    valid_first && sps && pps
+}
+
+// An H.265 stream opens with an access unit delimiter, VPS or prefix SEI, and the
+// first access unit carries the three parameter sets. Base-layer headers have
+// nuh_layer_id 0 and a non-zero nuh_temporal_id_plus1.
+fn is_h265(units: &[&[u8]]) -> bool {
+    let nal_type = |unit: &[u8]| match unit {
+        [b0, b1, ..] if b0 & 0x81 == 0 && b1 & 0xF8 == 0 && b1 & 0x07 != 0 => Some(b0 >> 1),
+        _ => None,
+    };
+    let valid_first = matches!(
+        units.first().and_then(|u| nal_type(u)),
+        Some(H265_AUD | H265_VPS | H265_PREFIX_SEI)
+    );
+
+    let has = |t: u8| units.iter().any(|u| nal_type(u) == Some(t));
+    valid_first && has(H265_VPS) && has(H265_SPS) && has(H265_PPS)
+}
+
+#[test]
+fn test_startcode_mpeg() {
+    // MPEG-2 pack header followed by a system header.
+    let mut ps = vec![0, 0, 1, 0xBA, 0x44, 0, 4, 0, 4, 1, 1, 0x89, 0xC3, 0xF8];
+    ps.extend_from_slice(&[0, 0, 1, 0xBB, 0, 0x12]);
+    assert_eq!(Some(Kind::ProgramStream), identify(&ps));
+    // A DVD navigation pack: the PCI packet follows the 24-byte system header.
+    let mut vob = ps.clone();
+    vob.extend_from_slice(&[0; 18]);
+    vob.extend_from_slice(&[0, 0, 1, 0xBF, 0x03, 0xD4]);
+    assert_eq!(Some(Kind::Vob), identify(&vob));
+    ps[4] = 0;
+    assert_eq!(None, identify(&ps));
+
+    // 720x576, 4:3, 25 fps.
+    let mut video = vec![
+        0, 0, 1, 0xB3, 0x2D, 0x02, 0x40, 0x23, 0x12, 0x4F, 0xA3, 0x80,
+    ];
+    assert_eq!(Some(Kind::Mpeg1Video), identify(&video));
+    video.extend_from_slice(&[0, 0, 1, 0xB5, 0x14, 0x8A, 0, 1, 0, 0]);
+    assert_eq!(Some(Kind::Mpeg2Video), identify(&video));
+}
+
+#[test]
+fn test_startcode_annex_b() {
+    let h264 = [
+        &[0, 0, 0, 1, 0x09, 0xF0][..],
+        &[0, 0, 0, 1, 0x67, 0x64, 0, 0x1F, 0xAC][..],
+        &[0, 0, 0, 1, 0x68, 0xEE, 0x3C, 0x80][..],
+        &[0, 0, 1, 0x65, 0x88, 0x84][..],
+    ]
+    .concat();
+    assert_eq!(Some(Kind::H264), identify(&h264));
+
+    let h265 = [
+        &[0, 0, 0, 1, 0x40, 0x01, 0x0C, 0x01][..],
+        &[0, 0, 0, 1, 0x42, 0x01, 0x01, 0x01][..],
+        &[0, 0, 0, 1, 0x44, 0x01, 0xC1, 0x72][..],
+        &[0, 0, 0, 1, 0x26, 0x01, 0xAF][..],
+    ]
+    .concat();
+    assert_eq!(Some(Kind::H265), identify(&h265));
+
+    // A start code followed by a NAL header alone is not enough.
+    assert_eq!(None, identify(&[0, 0, 0, 1, 0x67, 0x64, 0, 0x1F]));
+}
@@ -0,0 +1,107 @@
+// Compressed surround audio as raw frames behind a sync word: Dolby AC-3 and E-AC-3
+// (`0B 77`) and the DTS core (`7F FE 80 01`, or byte-swapped). Both sync words are
+// short, so the frame size is decoded and the next frame must start right after.
+// ref: ATSC A/52, 5.3 and E.1.2
+// ref: ETSI TS 102 114, 5.3
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Ac3,
+    Eac3,
+    Dts,
+}
+
+// kbit/s by frmsizecod / 2
+const AC3_BITRATES: [usize; 19] = [
+    32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384, 448, 512, 576, 640,
+];
+const AC3_SAMPLE_RATES: [usize; 3] = [48000, 44100, 32000];
+
+const DTS_SYNC: [u8; 4] = [0x7F, 0xFE, 0x80, 0x01];
+const DTS_SYNC_LE: [u8; 4] = [0xFE, 0x7F, 0x01, 0x80];
+
+pub fn identify(buf: &[u8]) -> Option<Kind> {
+    let (kind, len) = frame(buf)?;
+    match buf.get(len..) {
+        Some([]) => Some(kind),
+        Some(next) => frame(next).filter(|(k, _)| *k == kind).map(|(k, _)| k),
+        None => None,
+    }
+}
+
+fn frame(buf: &[u8]) -> Option<(Kind, usize)> {
+    match buf.get(..4)? {
+        [0x0B, 0x77, _, _] => dolby_frame(buf),
+        sync if *sync == DTS_SYNC => dts_frame(buf, false),
+        sync if *sync == DTS_SYNC_LE => dts_frame(buf, true),
+        _ => None,
+    }
+}
+
+fn dolby_frame(buf: &[u8]) -> Option<(Kind, usize)> {
+    let header = buf.get(..6)?;
+    match header[5] >> 3 {
+        // bsid 9 and 10 are the reduced sample rate variants of AC-3.
+        0..=10 => {
+            let fscod = (header[4] >> 6) as usize;
+            let frmsizecod = (header[4] & 0x3F) as usize;
+            if fscod == 3 || frmsizecod >= 38 {
+                return None;
+            }
+            let bitrate = AC3_BITRATES[frmsizecod / 2];
+            let mut words = bitrate * 96000 / AC3_SAMPLE_RATES[fscod];
+            // 44.1 kHz frames alternate in length to keep the average bitrate.
+            if fscod == 1 {
+                words += frmsizecod & 1;
+            }
+            Some((Kind::Ac3, words * 2))
+        }
+        11..=16 => {
+            let frmsiz = ((header[2] & 0x07) as usize) << 8 | header[3] as usize;
+            Some((Kind::Eac3, (frmsiz + 1) * 2))
+        }
+        _ => None,
+    }
+}
+
+fn dts_frame(buf: &[u8], swapped: bool) -> Option<(Kind, usize)> {
+    let mut header = [0_u8; 8];
+    header.copy_from_slice(buf.get(..8)?);
+    if swapped {
+        header.chunks_exact_mut(2).for_each(|pair| pair.swap(0, 1));
+    }
+    let nblks = ((header[4] & 0x01) as usize) << 6 | (header[5] >> 2) as usize;
+    let fsize =
+        ((header[5] & 0x03) as usize) << 12 | (header[6] as usize) << 4 | (header[7] >> 4) as usize;
+    // Fewer than 6 blocks (NBLKS + 1) and frames under 96 bytes are invalid.
+    if nblks < 5 || fsize < 95 {
+        return None;
+    }
+    Some((Kind::Dts, fsize + 1))
+}
+
+#[test]
+fn test_syncframe() {
+    // AC-3, 48 kHz, 192 kbit/s: 768 bytes.
+    let mut ac3 = [0x0B, 0x77, 0, 0, 0x14, 0x40].to_vec();
+    ac3.resize(768, 0);
+    assert_eq!(Some(Kind::Ac3), identify(&ac3.repeat(2)));
+    assert_eq!(None, identify(&[&ac3[..], &[0, 0]].concat()));
+
+    // E-AC-3 with frmsiz 383: 768 bytes.
+    let mut eac3 = [0x0B, 0x77, 0x01, 0x7F, 0x3F, 0x80].to_vec();
+    eac3.resize(768, 0);
+    assert_eq!(Some(Kind::Eac3), identify(&eac3.repeat(2)));
+
+    // DTS core, 16 blocks, 2012-byte frames, and its byte-swapped form.
+    let mut dts = [0x7F, 0xFE, 0x80, 0x01, 0xFC, 0x3C, 0x7D, 0xB0].to_vec();
+    dts.resize(2012, 0);
+    assert_eq!(Some(Kind::Dts), identify(&dts.repeat(2)));
+    let swapped: Vec<u8> = dts
+        .repeat(2)
+        .chunks(2)
+        .flat_map(|pair| pair.iter().rev().copied().collect::<Vec<_>>())
+        .collect();
+    assert_eq!(Some(Kind::Dts), identify(&swapped));
+    assert_eq!(DTS_SYNC_LE, swapped[..4]);
+}
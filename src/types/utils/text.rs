@@ -0,0 +1,291 @@
+// Text versus binary, and the character encoding of text. A byte order mark settles
+// the encoding; without one, UTF-16 is recognised by its pattern of zero bytes, then
+// the bytes are checked as ASCII, UTF-8 and finally the 8-bit Latin encodings.
+// Control characters other than whitespace, BEL, BS and ESC mean binary.
+// ref: https://www.unicode.org/faq/utf_bom.html#bom4
+
+// Windows-1252 characters for bytes 0x80-0x9F; the five unassigned bytes map to U+FFFD.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{FFFD}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{FFFD}', '\u{017D}', '\u{FFFD}',
+    '\u{FFFD}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{FFFD}', '\u{017E}', '\u{0178}',
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Ascii,
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+    // ISO-8859-1: bytes 0x80-0x9F (C1 controls) are absent.
+    Latin1,
+    // Windows-1252 uses 0x80-0x9F for printable characters such as curly quotes.
+    Windows1252,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    Mixed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextInfo {
+    pub encoding: Encoding,
+    pub bom: bool,
+    // None when the inspected text holds no line break.
+    pub line_ending: Option<LineEnding>,
+}
+
+impl Encoding {
+    pub fn charset(&self) -> &'static str {
+        match self {
+            Encoding::Ascii => "us-ascii",
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Utf32Le => "utf-32le",
+            Encoding::Utf32Be => "utf-32be",
+            Encoding::Latin1 => "iso-8859-1",
+            Encoding::Windows1252 => "windows-1252",
+        }
+    }
+}
+
+impl TextInfo {
+    // The text as characters, the BOM stripped. Invalid sequences become U+FFFD and a
+    // sequence cut off at the end of `buf` is dropped.
+    pub fn decode(&self, buf: &[u8]) -> String {
+        let buf = &buf[self.bom_len()..];
+        match self.encoding {
+            Encoding::Ascii | Encoding::Utf8 => {
+                let end = buf.len() - incomplete_utf8_tail(buf);
+                String::from_utf8_lossy(&buf[..end]).into_owned()
+            }
+            Encoding::Latin1 => buf.iter().map(|b| *b as char).collect(),
+            Encoding::Windows1252 => buf
+                .iter()
+                .map(|b| match b {
+                    0x80..=0x9F => WINDOWS_1252[(b - 0x80) as usize],
+                    _ => *b as char,
+                })
+                .collect(),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                char::decode_utf16(utf16_units(buf, self.encoding == Encoding::Utf16Be))
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect()
+            }
+            Encoding::Utf32Le | Encoding::Utf32Be => {
+                utf32_units(buf, self.encoding == Encoding::Utf32Be)
+                    .map(|u| char::from_u32(u).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect()
+            }
+        }
+    }
+
+    fn bom_len(&self) -> usize {
+        match (self.bom, self.encoding) {
+            (false, _) => 0,
+            (true, Encoding::Utf8) => 3,
+            (true, Encoding::Utf16Le | Encoding::Utf16Be) => 2,
+            (true, _) => 4,
+        }
+    }
+}
+
+pub fn detect(buf: &[u8]) -> Option<TextInfo> {
+    if buf.is_empty() {
+        return None;
+    }
+
+    let (encoding, bom) = match buf {
+        [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, true),
+        [0xFF, 0xFE, 0, 0, ..] => (Encoding::Utf32Le, true),
+        [0, 0, 0xFE, 0xFF, ..] => (Encoding::Utf32Be, true),
+        [0xFF, 0xFE, ..] => (Encoding::Utf16Le, true),
+        [0xFE, 0xFF, ..] => (Encoding::Utf16Be, true),
+        _ => (sniff(buf)?, false),
+    };
+
+    let info = TextInfo {
+        encoding,
+        bom,
+        line_ending: None,
+    };
+    let text = info.decode(buf);
+    if !text.chars().all(is_text_char) {
+        return None;
+    }
+    Some(TextInfo {
+        line_ending: line_ending(&text),
+        ..info
+    })
+}
+
+fn sniff(buf: &[u8]) -> Option<Encoding> {
+    if let Some(utf16) = sniff_utf16(buf) {
+        return Some(utf16);
+    }
+    if buf.iter().all(u8::is_ascii) {
+        return Some(Encoding::Ascii);
+    }
+
+    let end = buf.len() - incomplete_utf8_tail(buf);
+    if std::str::from_utf8(&buf[..end]).is_ok() {
+        return Some(Encoding::Utf8);
+    }
+
+    let c1: Vec<u8> = buf
+        .iter()
+        .copied()
+        .filter(|b| (0x80..=0x9F).contains(b))
+        .collect();
+    if c1.is_empty() {
+        Some(Encoding::Latin1)
+    } else if c1
+        .iter()
+        .all(|b| WINDOWS_1252[(b - 0x80) as usize] != char::REPLACEMENT_CHARACTER)
+    {
+        Some(Encoding::Windows1252)
+    } else {
+        None
+    }
+}
+
+// Mostly-Latin text in UTF-16 has a zero high byte in nearly every code unit, and
+// almost never a zero low byte.
+fn sniff_utf16(buf: &[u8]) -> Option<Encoding> {
+    let units = buf.len() / 2;
+    if units < 2 {
+        return None;
+    }
+    let zeros = |parity: usize| buf.chunks_exact(2).filter(|pair| pair[parity] == 0).count();
+    let (even, odd) = (zeros(0), zeros(1));
+
+    if odd * 10 >= units * 9 && even * 10 < units {
+        Some(Encoding::Utf16Le)
+    } else if even * 10 >= units * 9 && odd * 10 < units {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn is_text_char(c: char) -> bool {
+    // BEL, BS, TAB, LF, VT, FF, CR and ESC appear in terminal output and old documents.
+    !c.is_control() || matches!(c, '\x07'..='\r' | '\x1B')
+}
+
+fn line_ending(text: &str) -> Option<LineEnding> {
+    let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() == Some(&'\n') => {
+                chars.next();
+                crlf += 1;
+            }
+            '\r' => cr += 1,
+            '\n' => lf += 1,
+            _ => {}
+        }
+    }
+
+    match (lf > 0, crlf > 0, cr > 0) {
+        (false, false, false) => None,
+        (true, false, false) => Some(LineEnding::Lf),
+        (false, true, false) => Some(LineEnding::Crlf),
+        (false, false, true) => Some(LineEnding::Cr),
+        _ => Some(LineEnding::Mixed),
+    }
+}
+
+// The number of bytes at the end of `buf` that start a UTF-8 sequence it cuts short.
+fn incomplete_utf8_tail(buf: &[u8]) -> usize {
+    for back in 1..=3.min(buf.len()) {
+        let b = buf[buf.len() - back];
+        if b & 0xC0 != 0x80 {
+            let needed = match b {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => return 0,
+            };
+            return if needed > back { back } else { 0 };
+        }
+    }
+    0
+}
+
+fn utf16_units(buf: &[u8], big_endian: bool) -> impl Iterator<Item = u16> + '_ {
+    buf.chunks_exact(2).map(move |pair| {
+        let pair = [pair[0], pair[1]];
+        if big_endian {
+            u16::from_be_bytes(pair)
+        } else {
+            u16::from_le_bytes(pair)
+        }
+    })
+}
+
+fn utf32_units(buf: &[u8], big_endian: bool) -> impl Iterator<Item = u32> + '_ {
+    buf.chunks_exact(4).map(move |quad| {
+        let quad = [quad[0], quad[1], quad[2], quad[3]];
+        if big_endian {
+            u32::from_be_bytes(quad)
+        } else {
+            u32::from_le_bytes(quad)
+        }
+    })
+}
+
+#[test]
+fn test_text_encodings() {
+    let ascii = detect(b"hello\nworld\n").unwrap();
+    assert_eq!(Encoding::Ascii, ascii.encoding);
+    assert_eq!(Some(LineEnding::Lf), ascii.line_ending);
+
+    let utf8 = detect("caf\u{e9}\r\nna\u{ef}ve\r\n".as_bytes()).unwrap();
+    assert_eq!(Encoding::Utf8, utf8.encoding);
+    assert_eq!(Some(LineEnding::Crlf), utf8.line_ending);
+    // A multi-byte sequence cut off by the end of the buffer.
+    let cut = "\u{4e2d}\u{6587}".as_bytes();
+    assert_eq!(Encoding::Utf8, detect(&cut[..5]).unwrap().encoding);
+
+    assert_eq!(Encoding::Latin1, detect(b"caf\xE9\r").unwrap().encoding);
+    assert_eq!(
+        Encoding::Windows1252,
+        detect(b"\x93quoted\x94").unwrap().encoding
+    );
+
+    let bom = detect(b"\xEF\xBB\xBFtext").unwrap();
+    assert!(bom.bom);
+    assert_eq!("text", bom.decode(b"\xEF\xBB\xBFtext"));
+    assert_eq!(None, bom.line_ending);
+}
+
+#[test]
+fn test_text_utf16_and_binary() {
+    let le: Vec<u8> = "plain\ntext"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let info = detect(&le).unwrap();
+    assert_eq!(Encoding::Utf16Le, info.encoding);
+    assert!(!info.bom);
+    assert_eq!(Some(LineEnding::Lf), info.line_ending);
+
+    let be: Vec<u8> = [0xFE, 0xFF, 0, b'a', 0, b'\r', 0, b'b'].to_vec();
+    assert_eq!(Encoding::Utf16Be, detect(&be).unwrap().encoding);
+    let utf32 = [0xFF, 0xFE, 0, 0, b'a', 0, 0, 0];
+    assert_eq!(Encoding::Utf32Le, detect(&utf32).unwrap().encoding);
+
+    assert_eq!(None, detect(b""));
+    assert_eq!(None, detect(b"\x7FELF\x02\x01\x01\0\0\0"));
+    assert_eq!(None, detect(b"abc\x81\x00def"));
+}
@@ -0,0 +1,438 @@
+// Most camera RAW formats are TIFF containers that differ only in their tags.
+// ref: https://www.itu.int/itudoc/itu-t/com16/tiff-fx/docs/tiff6.pdf
+// ref: https://exiftool.org/TagNames/EXIF.html
+use super::{bigendian_bytes, bytes_to_str, littleendian_bytes};
+
+pub const TAG_COMPRESSION: u16 = 0x0103;
+pub const TAG_PHOTOMETRIC: u16 = 0x0106;
+pub const TAG_MAKE: u16 = 0x010F;
+pub const TAG_MODEL: u16 = 0x0110;
+pub const TAG_ORIENTATION: u16 = 0x0112;
+pub const TAG_DATE_TIME: u16 = 0x0132;
+pub const TAG_SUB_IFDS: u16 = 0x014A;
+pub const TAG_XMP: u16 = 0x02BC;
+pub const TAG_EXIF_IFD: u16 = 0x8769;
+pub const TAG_ICC_PROFILE: u16 = 0x8773;
+pub const TAG_GPS_IFD: u16 = 0x8825;
+pub const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+pub const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+pub const TAG_MAKER_NOTE: u16 = 0x927C;
+pub const TAG_DNG_VERSION: u16 = 0xC612;
+pub const TAG_DNG_PRIVATE_DATA: u16 = 0xC634;
+
+const MAGIC_TIFF: u16 = 0x2A;
+const MAGIC_ORF: u16 = 0x4F52; // "RO"
+const MAGIC_ORF_S: u16 = 0x5352; // "RS"
+const MAGIC_RW2: u16 = 0x55;
+
+const PHOTOMETRIC_CFA: u32 = 32803;
+const PHOTOMETRIC_LINEAR_RAW: u32 = 34892;
+
+// Deep or cyclic IFD chains are cut off after this many directories.
+const MAX_IFDS: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Tiff,
+    Cr2,
+    Dng,
+    Nef,
+    Nrw,
+    Arw,
+    Sr2,
+    Pef,
+    Orf,
+    Rw2,
+    Erf,
+    Fff,
+    Iiq,
+    Srw,
+}
+
+pub struct Tiff<'a> {
+    buf: &'a [u8],
+    big_endian: bool,
+    pub magic: u16,
+    pub ifd0: u32,
+}
+
+pub struct Entry {
+    pub tag: u16,
+    pub field_type: u16,
+    pub count: u32,
+    // Buffer offset of the 4-byte value field, which holds either the value or its offset.
+    value_offset: usize,
+}
+
+impl<'a> Tiff<'a> {
+    pub fn parse(buf: &'a [u8]) -> Option<Tiff<'a>> {
+        let big_endian = match buf.get(..2)? {
+            b"II" => false,
+            b"MM" => true,
+            _ => return None,
+        };
+
+        let mut tiff = Tiff {
+            buf,
+            big_endian,
+            magic: 0,
+            ifd0: 0,
+        };
+        tiff.magic = tiff.read_u16(2)?;
+        tiff.ifd0 = tiff.read_u32(4)?;
+        Some(tiff)
+    }
+
+    pub fn read_u16(&self, offset: usize) -> Option<u16> {
+        let b = self.buf.get(offset..offset.checked_add(2)?)?;
+        Some(self.decode_u16(b))
+    }
+
+    pub fn read_u32(&self, offset: usize) -> Option<u32> {
+        let b = self.buf.get(offset..offset.checked_add(4)?)?;
+        Some(self.decode_u32(b))
+    }
+
+    fn decode_u16(&self, b: &[u8]) -> u16 {
+        if self.big_endian {
+            u16::from_be_bytes([b[0], b[1]])
+        } else {
+            u16::from_le_bytes([b[0], b[1]])
+        }
+    }
+
+    fn decode_u32(&self, b: &[u8]) -> u32 {
+        if self.big_endian {
+            bigendian_bytes(b)
+        } else {
+            littleendian_bytes(b)
+        }
+    }
+
+    // The entries of the IFD at `offset`, and the offset of the next IFD.
+    // An IFD running past the buffer yields the entries that fit.
+    pub fn ifd(&self, offset: u32) -> Option<(Vec<Entry>, u32)> {
+        let offset = offset as usize;
+        let count = self.read_u16(offset)? as usize;
+        let fit = (self.buf.len() - offset - 2) / 12;
+        let mut entries = Vec::with_capacity(count.min(fit));
+
+        for i in 0..count.min(fit) {
+            let at = offset + 2 + i * 12;
+            entries.push(Entry {
+                tag: self.read_u16(at)?,
+                field_type: self.read_u16(at + 2)?,
+                count: self.read_u32(at + 4)?,
+                value_offset: at + 8,
+            });
+        }
+
+        let next = self.read_u32(offset + 2 + count * 12).unwrap_or(0);
+        Some((entries, next))
+    }
+
+    // The raw bytes of an entry's value, inline or out of line.
+    pub fn bytes(&self, entry: &Entry) -> Option<&'a [u8]> {
+        let size: usize = match entry.field_type {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 | 13 => 4,
+            5 | 10 | 12 => 8,
+            _ => return None,
+        };
+        let len = size.checked_mul(entry.count as usize)?;
+        let offset = if len <= 4 {
+            entry.value_offset
+        } else {
+            self.read_u32(entry.value_offset)? as usize
+        };
+        self.buf.get(offset..offset.checked_add(len)?)
+    }
+
+    pub fn ascii(&self, entry: &Entry) -> Option<&'a str> {
+        if entry.field_type != 2 {
+            return None;
+        }
+        let s = bytes_to_str(self.bytes(entry)?);
+        Some(s.trim_end_matches('\0').trim())
+    }
+
+    // The first value of a SHORT, LONG or IFD entry.
+    pub fn value(&self, entry: &Entry) -> Option<u32> {
+        match entry.field_type {
+            3 | 4 | 13 => self.values(entry).first().copied(),
+            _ => None,
+        }
+    }
+
+    // All values of a SHORT, LONG or IFD entry, e.g. the offsets listed by SubIFDs.
+    pub fn values(&self, entry: &Entry) -> Vec<u32> {
+        let bytes = match self.bytes(entry) {
+            Some(b) => b,
+            None => return vec![],
+        };
+        match entry.field_type {
+            3 => bytes
+                .chunks_exact(2)
+                .map(|c| self.decode_u16(c) as u32)
+                .collect(),
+            4 | 13 => bytes.chunks_exact(4).map(|c| self.decode_u32(c)).collect(),
+            _ => vec![],
+        }
+    }
+
+    // All values of a RATIONAL or SRATIONAL entry, e.g. the degrees, minutes and seconds
+    // of a GPS coordinate. Zero denominators give NaN.
+    pub fn rationals(&self, entry: &Entry) -> Vec<f64> {
+        let bytes = match self.bytes(entry) {
+            Some(b) => b,
+            None => return vec![],
+        };
+        let ratio = |c: &[u8]| match entry.field_type {
+            5 => self.decode_u32(&c[..4]) as f64 / self.decode_u32(&c[4..]) as f64,
+            _ => self.decode_u32(&c[..4]) as i32 as f64 / self.decode_u32(&c[4..]) as i32 as f64,
+        };
+        match entry.field_type {
+            5 | 10 => bytes.chunks_exact(8).map(ratio).collect(),
+            _ => vec![],
+        }
+    }
+
+    // Offsets of IFD0 and its successors, followed by the IFDs listed in their SubIFDs.
+    pub fn ifd_offsets(&self) -> Vec<u32> {
+        let mut offsets = vec![];
+        let mut next = self.ifd0;
+        while next != 0 && offsets.len() < MAX_IFDS && !offsets.contains(&next) {
+            offsets.push(next);
+            next = self.ifd(next).map_or(0, |(_, n)| n);
+        }
+
+        for i in 0..offsets.len() {
+            if let Some((entries, _)) = self.ifd(offsets[i]) {
+                if let Some(e) = find(&entries, TAG_SUB_IFDS) {
+                    for sub in self.values(e) {
+                        if offsets.len() < MAX_IFDS && !offsets.contains(&sub) {
+                            offsets.push(sub);
+                        }
+                    }
+                }
+            }
+        }
+
+        offsets
+    }
+}
+
+pub fn find(entries: &[Entry], tag: u16) -> Option<&Entry> {
+    entries.iter().find(|e| e.tag == tag)
+}
+
+pub fn identify(buf: &[u8]) -> Option<Kind> {
+    let tiff = Tiff::parse(buf)?;
+    match tiff.magic {
+        MAGIC_ORF | MAGIC_ORF_S => return Some(Kind::Orf),
+        MAGIC_RW2 => return Some(Kind::Rw2),
+        MAGIC_TIFF => {}
+        _ => return None,
+    }
+
+    // CR2 stores its own magic word and major version right after the TIFF header.
+    if buf.len() > 10 && buf[8..10] == *b"CR" && buf[10] == 0x02 {
+        return Some(Kind::Cr2);
+    }
+
+    let (entries, _) = match tiff.ifd(tiff.ifd0) {
+        Some(ifd) => ifd,
+        None => return Some(Kind::Tiff),
+    };
+    if find(&entries, TAG_DNG_VERSION).is_some() {
+        return Some(Kind::Dng);
+    }
+
+    // Ordinary TIFFs exported from RAW converters keep the camera Make, so the
+    // vendor alone is not enough: require some trace of unprocessed sensor data.
+    if !has_raw_data(&tiff, &entries) {
+        return Some(Kind::Tiff);
+    }
+
+    let make = find(&entries, TAG_MAKE).and_then(|e| tiff.ascii(e));
+    let model = find(&entries, TAG_MODEL)
+        .and_then(|e| tiff.ascii(e))
+        .unwrap_or_default();
+    let vendor = match make {
+        Some(make) => vendor_from_make(make, model),
+        None => vendor_from_maker_note(&tiff, &entries),
+    };
+
+    Some(match vendor {
+        // Coolpix cameras write NRW; NEF is the DSLR/mirrorless format.
+        Some(Kind::Nef) if model.starts_with("COOLPIX") => Kind::Nrw,
+        // SR2 was only ever produced by the DSC-R1.
+        Some(Kind::Arw) if model == "DSC-R1" => Kind::Sr2,
+        Some(kind) => kind,
+        None => Kind::Tiff,
+    })
+}
+
+fn has_raw_data(tiff: &Tiff, ifd0: &[Entry]) -> bool {
+    if find(ifd0, TAG_SUB_IFDS).is_some() || find(ifd0, TAG_DNG_PRIVATE_DATA).is_some() {
+        return true;
+    }
+
+    tiff.ifd_offsets().into_iter().any(|offset| {
+        let (entries, _) = match tiff.ifd(offset) {
+            Some(ifd) => ifd,
+            None => return false,
+        };
+        let photometric = find(&entries, TAG_PHOTOMETRIC).and_then(|e| tiff.value(e));
+        let compression = find(&entries, TAG_COMPRESSION).and_then(|e| tiff.value(e));
+        matches!(photometric, Some(PHOTOMETRIC_CFA | PHOTOMETRIC_LINEAR_RAW))
+            // Vendor-private compression schemes, e.g. Nikon 34713 or Sony 32767.
+            || matches!(compression, Some(c) if c >= 32767 && c != 32773 && c != 32946)
+    })
+}
+
+fn vendor_from_make(make: &str, model: &str) -> Option<Kind> {
+    let make = make.to_ascii_uppercase();
+    if make.starts_with("NIKON") {
+        Some(Kind::Nef)
+    } else if make.starts_with("SONY") {
+        Some(Kind::Arw)
+    } else if make.starts_with("PENTAX") || model.starts_with("PENTAX") {
+        Some(Kind::Pef)
+    } else if make.starts_with("OLYMPUS") || make.starts_with("OM DIGITAL") {
+        Some(Kind::Orf)
+    } else if make.starts_with("SEIKO EPSON") {
+        Some(Kind::Erf)
+    } else if make.starts_with("HASSELBLAD") {
+        Some(Kind::Fff)
+    } else if make.starts_with("PHASE ONE") {
+        Some(Kind::Iiq)
+    } else if make.starts_with("SAMSUNG") {
+        Some(Kind::Srw)
+    } else {
+        None
+    }
+}
+
+fn vendor_from_maker_note(tiff: &Tiff, ifd0: &[Entry]) -> Option<Kind> {
+    let exif = find(ifd0, TAG_EXIF_IFD).and_then(|e| tiff.value(e))?;
+    let (entries, _) = tiff.ifd(exif)?;
+    let note = tiff.bytes(find(&entries, TAG_MAKER_NOTE)?)?;
+
+    if note.starts_with(b"Nikon\0") {
+        Some(Kind::Nef)
+    } else if note.starts_with(b"SONY DSC ") || note.starts_with(b"SONY CAM ") {
+        Some(Kind::Arw)
+    } else if note.starts_with(b"AOC\0") || note.starts_with(b"PENTAX \0") {
+        Some(Kind::Pef)
+    } else if note.starts_with(b"OLYMP") || note.starts_with(b"OM SYSTEM\0") {
+        Some(Kind::Orf)
+    } else if note.starts_with(b"EPSON\0") {
+        Some(Kind::Erf)
+    } else {
+        None
+    }
+}
+
+// A little-endian TIFF with IFD0 at offset 8; values longer than 4 bytes follow the IFD.
+#[cfg(test)]
+fn build(entries: &[(u16, u16, &[u8])]) -> Vec<u8> {
+    let mut buf = b"II\x2A\x00\x08\x00\x00\x00".to_vec();
+    let mut data = vec![];
+    let data_start = 8 + 2 + entries.len() * 12 + 4;
+
+    buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, field_type, value) in entries {
+        let size = match field_type {
+            3 => 2,
+            4 => 4,
+            5 => 8,
+            _ => 1,
+        };
+        buf.extend_from_slice(&tag.to_le_bytes());
+        buf.extend_from_slice(&field_type.to_le_bytes());
+        buf.extend_from_slice(&((value.len() / size) as u32).to_le_bytes());
+        if value.len() <= 4 {
+            let mut inline = value.to_vec();
+            inline.resize(4, 0);
+            buf.extend(inline);
+        } else {
+            buf.extend_from_slice(&((data_start + data.len()) as u32).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+    }
+    buf.extend_from_slice(&[0; 4]);
+    buf.extend(data);
+    buf
+}
+
+#[test]
+fn test_tiff_entries() {
+    let buf = build(&[
+        (TAG_MAKE, 2, b"NIKON CORPORATION\0"),
+        (TAG_PHOTOMETRIC, 3, &[0x23, 0x80]),
+        (TAG_SUB_IFDS, 4, &[0x40, 0, 0, 0, 0x80, 0, 0, 0]),
+    ]);
+    let tiff = Tiff::parse(&buf).unwrap();
+    let (entries, next) = tiff.ifd(tiff.ifd0).unwrap();
+    assert_eq!(0, next);
+    assert_eq!(3, entries.len());
+    assert_eq!(Some("NIKON CORPORATION"), tiff.ascii(&entries[0]));
+    assert_eq!(Some(PHOTOMETRIC_CFA), tiff.value(&entries[1]));
+    assert_eq!(vec![0x40, 0x80], tiff.values(&entries[2]));
+
+    let buf = build(&[(TAG_GPS_IFD, 5, &[3, 0, 0, 0, 2, 0, 0, 0])]);
+    let tiff = Tiff::parse(&buf).unwrap();
+    let (entries, _) = tiff.ifd(tiff.ifd0).unwrap();
+    assert_eq!(vec![1.5], tiff.rationals(&entries[0]));
+}
+
+#[test]
+fn test_tiff_identify() {
+    let cfa: &[u8] = &[0x23, 0x80];
+    assert_eq!(
+        Some(Kind::Tiff),
+        identify(&build(&[(TAG_PHOTOMETRIC, 3, &[2, 0])]))
+    );
+    assert_eq!(
+        Some(Kind::Dng),
+        identify(&build(&[(TAG_DNG_VERSION, 1, &[1, 4, 0, 0])]))
+    );
+    assert_eq!(
+        Some(Kind::Nef),
+        identify(&build(&[
+            (TAG_MAKE, 2, b"NIKON\0"),
+            (TAG_PHOTOMETRIC, 3, cfa)
+        ]))
+    );
+    assert_eq!(
+        Some(Kind::Nrw),
+        identify(&build(&[
+            (TAG_MAKE, 2, b"NIKON\0"),
+            (TAG_MODEL, 2, b"COOLPIX P7000\0"),
+            (TAG_PHOTOMETRIC, 3, cfa),
+        ]))
+    );
+    assert_eq!(
+        Some(Kind::Sr2),
+        identify(&build(&[
+            (TAG_MAKE, 2, b"SONY\0"),
+            (TAG_MODEL, 2, b"DSC-R1\0"),
+            (TAG_PHOTOMETRIC, 3, cfa),
+        ]))
+    );
+    // A developed TIFF that kept the camera Make is still a plain TIFF.
+    assert_eq!(
+        Some(Kind::Tiff),
+        identify(&build(&[
+            (TAG_MAKE, 2, b"SONY\0"),
+            (TAG_PHOTOMETRIC, 3, &[2, 0])
+        ]))
+    );
+
+    let mut rw2 = build(&[]);
+    rw2[2] = 0x55;
+    assert_eq!(Some(Kind::Rw2), identify(&rw2));
+    assert_eq!(None, identify(b"II\xBC\x00\x08\x00\x00\x00"));
+}
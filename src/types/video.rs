@@ -1,18 +1,49 @@
 use super::{
     base::{new_type, HashMapTypeMatcher, Type},
-    utils::{bytes_index, compare_bytes},
+    utils::{
+        asf, codecs, compare_bytes,
+        ebml::{self, Variant},
+        movie, mpegts, ogg,
+        ogg::Codec,
+        riff::{self, Kind},
+        startcode,
+    },
 };
 
+pub use super::utils::movie::{TrackKind, VideoInfo, VideoTrack};
+pub use super::utils::mpegts::{TsInfo, TsProgram, TsStream};
+
 const TYPE_MP4: Type = new_type("video/mp4", "mp4");
 const TYPE_M4V: Type = new_type("video/x-m4v", "m4v");
 const TYPE_MKV: Type = new_type("video/x-matroska", "mkv");
+const TYPE_MK3D: Type = new_type("video/x-matroska-3d", "mk3d");
+const TYPE_MKS: Type = new_type("application/x-matroska", "mks");
 const TYPE_WEBM: Type = new_type("video/webm", "webm");
 const TYPE_MOV: Type = new_type("video/quicktime", "mov");
 const TYPE_AVI: Type = new_type("video/x-msvideo", "avi");
 const TYPE_WMV: Type = new_type("video/x-ms-wmv", "wmv");
+const TYPE_ASF: Type = new_type("video/x-ms-asf", "asf");
+const TYPE_DVR_MS: Type = new_type("video/x-ms-dvr", "dvr-ms");
 const TYPE_MPG: Type = new_type("video/mpeg", "mpg");
+const TYPE_M1V: Type = new_type("video/mpv", "m1v");
+const TYPE_M2V: Type = new_type("video/mpv", "m2v");
+const TYPE_H264: Type = new_type("video/h264", "264");
+const TYPE_H265: Type = new_type("video/h265", "265");
+const TYPE_TS: Type = new_type("video/mp2t", "ts");
+const TYPE_M2TS: Type = new_type("video/mp2t", "m2ts");
+const TYPE_VOB: Type = new_type("video/dvd", "vob");
+const TYPE_RM: Type = new_type("application/vnd.rn-realmedia", "rm");
+const TYPE_MXF: Type = new_type("application/mxf", "mxf");
+const TYPE_IVF: Type = new_type("video/x-ivf", "ivf");
+const TYPE_BIK: Type = new_type("video/vnd.radgamettools.bink", "bik");
+const TYPE_BK2: Type = new_type("video/vnd.radgamettools.bink", "bk2");
+const TYPE_SMK: Type = new_type("video/vnd.radgamettools.smacker", "smk");
+const TYPE_NUT: Type = new_type("video/x-nut", "nut");
+const TYPE_DV: Type = new_type("video/dv", "dv");
 const TYPE_FLV: Type = new_type("video/x-flv", "flv");
 const TYPE_3GP: Type = new_type("video/3gpp", "3gp");
+const TYPE_OGV: Type = new_type("video/ogg", "ogv");
+const TYPE_OGM: Type = new_type("video/x-ogm+ogg", "ogm");
 
 fn is_m4v(buf: &[u8]) -> bool {
     buf.len() > 10
@@ -25,22 +56,24 @@ fn is_m4v(buf: &[u8]) -> bool {
         && buf[10] == 0x56
 }
 
+// Audio-only Matroska and WebM files are reported by the audio matchers.
 fn is_mkv(buf: &[u8]) -> bool {
-    buf.len() > 3
-        && buf[0] == 0x1A
-        && buf[1] == 0x45
-        && buf[2] == 0xDF
-        && buf[3] == 0xA3
-        && contains_matroska_signature(buf, b"matroska")
+    matches!(
+        ebml::identify(buf),
+        Some(("matroska", Variant::Video | Variant::Unknown))
+    )
+}
+
+fn is_mk3d(buf: &[u8]) -> bool {
+    matches!(ebml::identify(buf), Some(("matroska", Variant::Stereo3d)))
+}
+
+fn is_mks(buf: &[u8]) -> bool {
+    matches!(ebml::identify(buf), Some(("matroska", Variant::Subtitles)))
 }
 
 fn is_webm(buf: &[u8]) -> bool {
-    buf.len() > 3
-        && buf[0] == 0x1A
-        && buf[1] == 0x45
-        && buf[2] == 0xDF
-        && buf[3] == 0xA3
-        && contains_matroska_signature(buf, b"webm")
+    matches!(ebml::identify(buf), Some(("webm", v)) if v != Variant::Audio)
 }
 
 fn is_mov(buf: &[u8]) -> bool {
@@ -55,29 +88,60 @@ fn is_mov(buf: &[u8]) -> bool {
     (buf[12] == 0x6d && buf[13] == 0x64 && buf[14] == 0x61 && buf[15] == 0x74))
 }
 
+// Covers both AVI 1.0 and the OpenDML (AVI 2.0) extension.
 fn is_avi(buf: &[u8]) -> bool {
-    buf.len() > 10
-        && buf[0] == 0x52
-        && buf[1] == 0x49
-        && buf[2] == 0x46
-        && buf[3] == 0x46
-        && buf[8] == 0x41
-        && buf[9] == 0x56
-        && buf[10] == 0x49
+    matches!(riff::identify(buf), Some((_, Kind::Avi | Kind::Avi2)))
 }
 
+// ASF files with a video stream; audio-only ones are reported by the audio matchers.
 fn is_wmv(buf: &[u8]) -> bool {
-    let subs = [0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9];
-    compare_bytes(buf, &subs, 0)
+    matches!(asf::identify(buf), Some(asf::Kind::Wmv))
 }
 
+fn is_asf(buf: &[u8]) -> bool {
+    matches!(asf::identify(buf), Some(asf::Kind::Asf))
+}
+
+fn is_dvr_ms(buf: &[u8]) -> bool {
+    matches!(asf::identify(buf), Some(asf::Kind::DvrMs))
+}
+
+// MPEG-1/2 program streams; raw video elementary streams are matched separately.
 fn is_mpeg(buf: &[u8]) -> bool {
-    buf.len() > 3
-        && buf[0] == 0x0
-        && buf[1] == 0x0
-        && buf[2] == 0x1
-        && buf[3] >= 0xb0
-        && buf[3] <= 0xbf
+    matches!(
+        startcode::identify(buf),
+        Some(startcode::Kind::ProgramStream)
+    )
+}
+
+fn is_vob(buf: &[u8]) -> bool {
+    matches!(startcode::identify(buf), Some(startcode::Kind::Vob))
+}
+
+fn is_m1v(buf: &[u8]) -> bool {
+    matches!(startcode::identify(buf), Some(startcode::Kind::Mpeg1Video))
+}
+
+fn is_m2v(buf: &[u8]) -> bool {
+    matches!(startcode::identify(buf), Some(startcode::Kind::Mpeg2Video))
+}
+
+fn is_h264(buf: &[u8]) -> bool {
+    matches!(startcode::identify(buf), Some(startcode::Kind::H264))
+}
+
+fn is_h265(buf: &[u8]) -> bool {
+    matches!(startcode::identify(buf), Some(startcode::Kind::H265))
+}
+
+// 188-byte packets, or 204 with Reed-Solomon parity.
+fn is_ts(buf: &[u8]) -> bool {
+    matches!(mpegts::packet_size(buf), Some(188 | 204))
+}
+
+// Blu-ray and AVCHD streams with a timestamp before each packet.
+fn is_m2ts(buf: &[u8]) -> bool {
+    matches!(mpegts::packet_size(buf), Some(192))
 }
 
 fn is_flv(buf: &[u8]) -> bool {
@@ -122,11 +186,59 @@ fn is_3gp(buf: &[u8]) -> bool {
     compare_bytes(buf, &subs, 4)
 }
 
-fn contains_matroska_signature(buf: &[u8], subt: &[u8]) -> bool {
-    let limit = buf.len().min(4096);
-    let index = bytes_index(&buf[..limit], subt);
+fn is_rm(buf: &[u8]) -> bool {
+    compare_bytes(buf, b".RMF\0\0\0", 0)
+}
+
+// The key of a header partition pack at the start of the file. Files with a run-in
+// before the key are left out: searching further in for the key also finds MXF files
+// stored inside archives.
+// ref: SMPTE ST 377-1, 7.1
+fn is_mxf(buf: &[u8]) -> bool {
+    let key = [
+        0x06, 0x0E, 0x2B, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0D, 0x01, 0x02, 0x01, 0x01, 0x02,
+    ];
+    compare_bytes(buf, &key, 0)
+}
+
+// Version 0 with a 32-byte header.
+fn is_ivf(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"DKIF\0\0\x20\0", 0)
+}
+
+fn is_bik(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"BIK", 0) && buf.len() > 3 && (b'b'..=b'k').contains(&buf[3])
+}
+
+fn is_bk2(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"KB2", 0) && buf.len() > 3 && (b'a'..=b'j').contains(&buf[3])
+}
 
-    index >= 3 && buf[index as usize - 3] == 0x42 && buf[index as usize - 2] == 0x82
+fn is_smk(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"SMK2", 0) || compare_bytes(buf, b"SMK4", 0)
+}
+
+fn is_nut(buf: &[u8]) -> bool {
+    compare_bytes(buf, b"nut/multimedia container\0", 0)
+}
+
+// Raw DV: 80-byte DIF blocks, the first sequence opening with a header block followed
+// by a subcode block.
+// ref: IEC 61834-2, SMPTE 314M
+fn is_dv(buf: &[u8]) -> bool {
+    buf.len() > 162
+        && buf[..3] == [0x1F, 0x07, 0x00]
+        && buf[80] & 0xE0 == 0x20
+        && buf[81] == 0x07
+        && buf[82] == 0x00
+}
+
+fn is_ogv(buf: &[u8]) -> bool {
+    matches!(ogg::identify(buf), Some(Codec::Theora | Codec::Daala))
+}
+
+fn is_ogm(buf: &[u8]) -> bool {
+    matches!(ogg::identify(buf), Some(c) if c.is_ogm())
 }
 
 pub fn sum() -> HashMapTypeMatcher {
@@ -136,13 +248,50 @@ pub fn sum() -> HashMapTypeMatcher {
     ret.insert(TYPE_MP4, is_mp4);
     ret.insert(TYPE_M4V, is_m4v);
     ret.insert(TYPE_MKV, is_mkv);
+    ret.insert(TYPE_MK3D, is_mk3d);
+    ret.insert(TYPE_MKS, is_mks);
     ret.insert(TYPE_WEBM, is_webm);
     ret.insert(TYPE_MOV, is_mov);
     ret.insert(TYPE_AVI, is_avi);
     ret.insert(TYPE_WMV, is_wmv);
+    ret.insert(TYPE_ASF, is_asf);
+    ret.insert(TYPE_DVR_MS, is_dvr_ms);
     ret.insert(TYPE_MPG, is_mpeg);
+    ret.insert(TYPE_M1V, is_m1v);
+    ret.insert(TYPE_M2V, is_m2v);
+    ret.insert(TYPE_H264, is_h264);
+    ret.insert(TYPE_H265, is_h265);
+    ret.insert(TYPE_TS, is_ts);
+    ret.insert(TYPE_M2TS, is_m2ts);
+    ret.insert(TYPE_VOB, is_vob);
+    ret.insert(TYPE_RM, is_rm);
+    ret.insert(TYPE_MXF, is_mxf);
+    ret.insert(TYPE_IVF, is_ivf);
+    ret.insert(TYPE_BIK, is_bik);
+    ret.insert(TYPE_BK2, is_bk2);
+    ret.insert(TYPE_SMK, is_smk);
+    ret.insert(TYPE_NUT, is_nut);
+    ret.insert(TYPE_DV, is_dv);
     ret.insert(TYPE_FLV, is_flv);
     ret.insert(TYPE_3GP, is_3gp);
+    ret.insert(TYPE_OGV, is_ogv);
+    ret.insert(TYPE_OGM, is_ogm);
 
     ret
 }
+
+// Container analysis of MP4, QuickTime, Matroska, AVI, FLV and ASF: duration, picture
+// size, frame rate and the codec and language of each track.
+pub fn video_info(buf: &[u8]) -> Option<VideoInfo> {
+    movie::parse(buf)
+}
+
+// RFC 6381 `codecs` values of the MP4, QuickTime and Matroska tracks, in track order.
+pub fn codecs(buf: &[u8]) -> Option<Vec<String>> {
+    codecs::parse(buf)
+}
+
+// Programs and elementary stream types listed by the PAT and PMTs of a transport stream.
+pub fn ts_info(buf: &[u8]) -> Option<TsInfo> {
+    mpegts::parse(buf)
+}
@@ -1,21 +1,41 @@
 use std::path::Path;
-use std::{fs::File, io::Read};
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+};
 
-pub fn get_signature_bytes(path: &Path, mut buffer: &mut [u8]) -> bool {
+/// Fill `buffer` with the leading bytes of the file and return how many were read.
+pub fn get_signature_bytes(path: &Path, buffer: &mut [u8]) -> usize {
+    get_bytes_at(path, 0, buffer)
+}
+
+/// Fill `buffer` with the bytes found at `offset` and return how many were read.
+pub fn get_bytes_at(path: &Path, offset: u64, buffer: &mut [u8]) -> usize {
     let mut file = match File::open(path) {
         Ok(file) => file,
         Err(e) => {
             eprintln!("Unable to open file {}: {}", path.display(), e);
-            return false;
+            return 0;
         }
     };
 
-    // let mut buffer = [0_u8; NUM_SIGNATURE_BYTES];
-    match file.read(&mut buffer) {
-        Ok(_) => true,
-        Err(e) => {
-            eprintln!("Unable to read file {}: {}", path.display(), e);
-            return false;
+    if let Err(e) = file.seek(SeekFrom::Start(offset)) {
+        eprintln!("Unable to seek file {}: {}", path.display(), e);
+        return 0;
+    }
+
+    let mut read = 0;
+    let mut handle = file.take(buffer.len() as u64);
+    while read < buffer.len() {
+        match handle.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) => {
+                eprintln!("Unable to read file {}: {}", path.display(), e);
+                break;
+            }
         }
     }
+
+    read
 }
@@ -0,0 +1,10 @@
+package main
+
+import (
+	"fmt"
+)
+
+func main() {
+	name := "world"
+	fmt.Println("hello", name)
+}
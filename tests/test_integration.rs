@@ -1,11 +1,14 @@
 use filetypes::matcher::Matcher;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const SAMPLE_DIR: &str = "tests/fixtures";
 
+fn sample(file: &str) -> PathBuf {
+    Path::new(SAMPLE_DIR).join(file)
+}
+
 fn test(matcher: Matcher, file: &str, mime: &str, extension: &str) {
-    let sample = Path::new(SAMPLE_DIR).join(file);
-    let ret = matcher(sample.as_path());
+    let ret = matcher(sample(file).as_path());
     assert_eq!(mime, ret.mime);
     assert_eq!(extension, ret.extension);
 }
@@ -15,24 +18,334 @@ mod tests {
 
     use filetypes::matcher::*;
 
-    use super::test;
+    use super::{sample, test};
+    use filetypes::info;
+    use filetypes::types::archive::ElfKind;
+    use filetypes::types::font::Outlines;
+    use filetypes::types::image::ColorType;
+    use filetypes::types::text::LineEnding;
+    use filetypes::types::video::TrackKind;
+    use std::time::Duration;
 
     #[test]
     fn test_image() {
         test(match_image, "sample.jpg", "image/jpeg", "jpg");
         test(match_image, "sample.png", "image/png", "png");
         test(match_image, "sample.gif", "image/gif", "gif");
+        test(match_image, "sample.webp", "image/webp", "webp");
+        test(match_image, "sample.tif", "image/tiff", "tif");
+        test(match_image, "sample.svg", "image/svg+xml", "svg");
+        test(match_image, "sample.svgz", "image/svg+xml", "svgz");
+        test(match_all, "sample.svgz", "image/svg+xml", "svgz");
+
+        let png = info::image_info(&sample("sample.png")).unwrap();
+        assert_eq!(
+            (512, 512, ColorType::Rgb),
+            (png.width, png.height, png.color_type)
+        );
+        assert!(png.alpha);
+        let jpeg = info::image_info(&sample("sample.jpg")).unwrap();
+        assert_eq!(
+            (640, 480, Some(8)),
+            (jpeg.width, jpeg.height, jpeg.bit_depth)
+        );
+        // Two 12 KB ICC segments put the frame header past the first read.
+        test(match_image, "sample_late_sof.jpg", "image/jpeg", "jpg");
+        let jpeg = info::image_info(&sample("sample_late_sof.jpg")).unwrap();
+        assert_eq!(
+            (300, 200, ColorType::Grayscale),
+            (jpeg.width, jpeg.height, jpeg.color_type)
+        );
+        let gif = info::image_info(&sample("sample.gif")).unwrap();
+        assert_eq!((ColorType::Indexed, Some(12)), (gif.color_type, gif.frames));
+        let tiff = info::image_info(&sample("sample.tif")).unwrap();
+        assert_eq!(ColorType::Bilevel, tiff.color_type);
+        let heic = info::image_info(&sample("sample.heic")).unwrap();
+        assert_eq!((1440, 960), (heic.width, heic.height));
+        let avif = info::image_info(&sample("sample.avif")).unwrap();
+        assert_eq!((640, 480, Some(1)), (avif.width, avif.height, avif.frames));
+        for name in ["sample.svg", "sample.svgz"] {
+            let svg = info::image_info(&sample(name)).unwrap();
+            assert_eq!((16, 16), (svg.width, svg.height));
+        }
+
+        let jpeg = info::metadata(&sample("sample.jpg")).unwrap();
+        assert!(jpeg.exif && jpeg.xmp && jpeg.icc);
+        assert_eq!(
+            (Some("KYOCERA"), Some(1)),
+            (jpeg.make.as_deref(), jpeg.orientation)
+        );
+        assert_eq!(Some("2003-10-31T21:32:49"), jpeg.capture_time.as_deref());
+        assert_eq!(Some("sRGB IEC61966-2.1"), jpeg.icc_profile.as_deref());
+        let avif = info::metadata(&sample("sample.avif")).unwrap();
+        assert_eq!(Some("FC-S3x"), avif.model.as_deref());
+        assert_eq!(Some("sRGB IEC61966-2.1"), avif.icc_profile.as_deref());
+        let png = info::metadata(&sample("sample.png")).unwrap();
+        assert!(!png.exif && !png.xmp && !png.icc);
     }
 
     #[test]
     fn test_video() {
         test(match_video, "sample.mov", "video/quicktime", "mov");
         test(match_video, "sample.mp4", "video/mp4", "mp4");
+        test(match_video, "sample.wmv", "video/x-ms-wmv", "wmv");
+        test(match_video, "sample.asf", "video/x-ms-asf", "asf");
+        test(match_video, "sample.dvr-ms", "video/x-ms-dvr", "dvr-ms");
+        test(match_video, "sample.mpg", "video/mpeg", "mpg");
+        test(match_video, "sample.vob", "video/dvd", "vob");
+        test(
+            match_video,
+            "sample.rm",
+            "application/vnd.rn-realmedia",
+            "rm",
+        );
+        test(match_video, "sample.mxf", "application/mxf", "mxf");
+        // An MXF file stored in a tar archive is the archive.
+        test(match_all, "sample_mxf.tar", "application/x-tar", "tar");
+        test(match_video, "sample.ivf", "video/x-ivf", "ivf");
+        test(
+            match_video,
+            "sample.bik",
+            "video/vnd.radgamettools.bink",
+            "bik",
+        );
+        test(
+            match_video,
+            "sample.bk2",
+            "video/vnd.radgamettools.bink",
+            "bk2",
+        );
+        test(
+            match_video,
+            "sample.smk",
+            "video/vnd.radgamettools.smacker",
+            "smk",
+        );
+        test(match_video, "sample.nut", "video/x-nut", "nut");
+        test(match_video, "sample.dv", "video/dv", "dv");
+        test(match_video, "sample.ts", "video/mp2t", "ts");
+        test(match_video, "sample.m2ts", "video/mp2t", "m2ts");
+        test(match_video, "sample_rs.ts", "video/mp2t", "ts");
+        // Audio-only ASF is left to the audio matchers.
+        test(match_video, "sample.wma", "UNKNOWN", "");
+
+        let mp4 = info::video_info(&sample("sample.mp4")).unwrap();
+        assert_eq!(Some(30.0), mp4.frame_rate);
+        let kinds: Vec<_> = mp4.tracks.iter().map(|t| t.kind).collect();
+        assert_eq!(vec![TrackKind::Audio, TrackKind::Video], kinds);
+        assert_eq!(Some("eng"), mp4.tracks[1].language.as_deref());
+        let mov = info::video_info(&sample("sample.mov")).unwrap();
+        assert_eq!((Some(320), Some(240)), (mov.width, mov.height));
+        assert_eq!(Some(Duration::from_secs(1)), mov.duration);
+        let wmv = info::video_info(&sample("sample.wmv")).unwrap();
+        assert_eq!(Some("WMV3"), wmv.tracks[1].codec.as_deref());
+        for (name, size) in [
+            ("sample.ts", 188),
+            ("sample.m2ts", 192),
+            ("sample_rs.ts", 204),
+        ] {
+            let ts = info::ts_info(&sample(name)).unwrap();
+            assert_eq!(size, ts.packet_size);
+            let names: Vec<_> = ts.streams().map(|s| s.stream_type_name()).collect();
+            assert_eq!(vec!["h264", "aac-adts"], names);
+        }
+
+        assert_eq!(
+            Some(vec!["mp4a.40.2".to_string(), "mp4v.20.243".to_string()]),
+            info::codecs(&sample("sample.mp4"))
+        );
+        assert_eq!(
+            "video/mp4; codecs=\"mp4a.40.2, mp4v.20.243\"",
+            match_video_with_codecs(&sample("sample.mp4"))
+        );
+        assert_eq!(
+            "video/x-ms-wmv",
+            match_video_with_codecs(&sample("sample.wmv"))
+        );
     }
 
     #[test]
     fn test_audio() {
         test(match_audio, "sample.m4a", "audio/mp4", "m4a");
+        test(match_audio, "sample.wav", "audio/x-wav", "wav");
+        test(match_audio, "sample.wma", "audio/x-ms-wma", "wma");
+        test(match_audio, "sample_cut.ogg", "audio/ogg", "ogg");
+        test(match_audio, "sample.ape", "audio/x-ape", "ape");
+        test(match_audio, "sample.wv", "audio/x-wavpack", "wv");
+        test(match_audio, "sample.dsf", "audio/x-dsf", "dsf");
+        test(match_audio, "sample.dff", "audio/x-dff", "dff");
+        test(match_audio, "sample.caf", "audio/x-caf", "caf");
+        test(match_audio, "sample.au", "audio/basic", "au");
+        test(match_audio, "sample.w64", "audio/x-w64", "w64");
+        test(match_audio, "sample.mpc", "audio/x-musepack", "mpc");
+        test(match_audio, "sample.tta", "audio/x-tta", "tta");
+        test(match_audio, "sample.ofr", "audio/x-optimfrog", "ofr");
+        test(match_audio, "sample.mod", "audio/x-mod", "mod");
+        test(match_audio, "sample.xm", "audio/x-xm", "xm");
+        test(match_audio, "sample.s3m", "audio/x-s3m", "s3m");
+        test(match_audio, "sample.it", "audio/x-it", "it");
+        test(match_audio, "sample.voc", "audio/x-voc", "voc");
+        test(match_audio, "sample.awb", "audio/amr-wb", "awb");
+        test(match_audio, "sample.ac3", "audio/ac3", "ac3");
+        test(match_audio, "sample.eac3", "audio/eac3", "eac3");
+        test(match_audio, "sample.dts", "audio/vnd.dts", "dts");
+        // A module channel tag at offset 1080 of unrelated data.
+        test(match_audio, "sample_10ch.bin", "UNKNOWN", "");
+
+        let wav = info::audio_info(&sample("sample.wav")).unwrap();
+        assert_eq!(
+            (Some(8000), Some(1), Some(16)),
+            (wav.sample_rate, wav.channels, wav.bits_per_sample)
+        );
+        assert_eq!(Some(Duration::from_millis(100)), wav.duration);
+        let m4a = info::audio_info(&sample("sample.m4a")).unwrap();
+        assert_eq!((Some(32000), Some(2)), (m4a.sample_rate, m4a.channels));
+        assert_eq!(Some(Duration::from_millis(4992)), m4a.duration);
+        assert_eq!(
+            "audio/mp4; codecs=\"mp4a.40.2\"",
+            match_audio_with_codecs(&sample("sample.m4a"))
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        test(
+            match_text,
+            "sample.txt",
+            "text/plain; charset=us-ascii",
+            "txt",
+        );
+        test(
+            match_text,
+            "sample_utf8.txt",
+            "text/plain; charset=utf-8",
+            "txt",
+        );
+        test(
+            match_text,
+            "sample_utf16.txt",
+            "text/plain; charset=utf-16le",
+            "txt",
+        );
+        test(
+            match_text,
+            "sample_cp1252.txt",
+            "text/plain; charset=windows-1252",
+            "txt",
+        );
+        test(match_text, "sample.elf", "UNKNOWN", "");
+
+        // Text is only the fallback for content without a magic number.
+        test(
+            match_all,
+            "sample.txt",
+            "text/plain; charset=us-ascii",
+            "txt",
+        );
+        test(match_all, "sample.wav", "audio/x-wav", "wav");
+
+        test(match_text, "sample.json", "application/json", "json");
+        test(
+            match_text,
+            "sample.ipynb",
+            "application/x-ipynb+json",
+            "ipynb",
+        );
+        test(
+            match_text,
+            "sample.geojson",
+            "application/geo+json",
+            "geojson",
+        );
+        test(
+            match_text,
+            "sample_package.json",
+            "application/vnd.npm.package+json",
+            "json",
+        );
+        test(
+            match_text,
+            "sample.ndjson",
+            "application/x-ndjson",
+            "ndjson",
+        );
+        test(match_text, "sample.yaml", "application/yaml", "yaml");
+        test(
+            match_text,
+            "sample_settings.toml",
+            "application/toml",
+            "toml",
+        );
+        test(
+            match_text,
+            "sample.toml",
+            "application/vnd.cargo.manifest+toml",
+            "toml",
+        );
+        test(
+            match_text,
+            "sample_k8s.yaml",
+            "application/vnd.kubernetes+yaml",
+            "yaml",
+        );
+        let k8s = info::config_info(&sample("sample_k8s.yaml")).unwrap();
+        assert_eq!(Some("Deployment"), k8s.kind.as_deref());
+        assert_eq!("Kubernetes manifest", k8s.dialect.description());
+        test(match_text, "sample.ini", "text/x-ini", "ini");
+        test(
+            match_text,
+            "sample.properties",
+            "text/x-java-properties",
+            "properties",
+        );
+        test(match_text, "sample.csv", "text/csv", "csv");
+        test(match_text, "sample.tsv", "text/tab-separated-values", "tsv");
+        // Two lines with one comma each are prose, not a table.
+        test(
+            match_all,
+            "sample_prose.txt",
+            "text/plain; charset=us-ascii",
+            "txt",
+        );
+
+        test(match_text, "sample.html", "text/html", "html");
+        test(match_text, "sample.xhtml", "application/xhtml+xml", "xhtml");
+        test(match_text, "sample.xml", "application/xml", "xml");
+        test(match_text, "sample.md", "text/markdown", "md");
+        test(match_text, "sample.rst", "text/x-rst", "rst");
+        test(match_text, "sample.tex", "text/x-tex", "tex");
+        test(match_text, "sample.man", "application/x-troff-man", "man");
+        test(match_text, "sample.adoc", "text/asciidoc", "adoc");
+        test(match_all, "sample.svg", "image/svg+xml", "svg");
+        test(match_text, "sample.svg", "image/svg+xml", "svg");
+        test(match_text, "sample.rss", "application/rss+xml", "rss");
+        test(match_text, "sample.gpx", "application/gpx+xml", "gpx");
+        test(match_text, "sample.pom", "application/x-maven+xml", "pom");
+        test(
+            match_text,
+            "sample.fodt",
+            "application/vnd.oasis.opendocument.text-flat-xml",
+            "fodt",
+        );
+
+        test(match_text, "sample_script", "text/x-shellscript", "bash");
+        test(match_text, "sample.py", "text/x-python", "py");
+        test(match_text, "sample.go", "text/x-go", "go");
+        let shebang = info::shebang_info(&sample("sample_script")).unwrap();
+        assert_eq!("bash", shebang.interpreter);
+        assert_eq!(vec!["-eu"], shebang.args);
+
+        let csv = info::csv_info(&sample("sample.csv")).unwrap();
+        assert_eq!(',', csv.delimiter);
+        assert!(csv.has_header);
+
+        let info = info::text_info(&sample("sample_utf16.txt")).unwrap();
+        assert!(info.bom);
+        assert_eq!(Some(LineEnding::Crlf), info.line_ending);
+        assert_eq!(
+            Some(LineEnding::Lf),
+            info::text_info(&sample("sample.txt")).unwrap().line_ending
+        );
     }
 
     #[test]
@@ -41,6 +354,119 @@ mod tests {
         test(match_archive, "sample.tar", "application/x-tar", "tar");
     }
 
+    #[test]
+    fn test_font() {
+        test(match_font, "sample.ttf", "application/font-sfnt", "ttf");
+        test(match_font, "sample.otf", "application/font-sfnt", "otf");
+        test(match_font, "sample.ttc", "font/collection", "ttc");
+        test(match_font, "sample.woff", "application/font-woff", "woff");
+        test(match_font, "sample.pfa", "application/x-font-type1", "pfa");
+        test(match_font, "sample.pfb", "application/x-font-type1", "pfb");
+        test(match_font, "sample.dfont", "application/x-dfont", "dfont");
+        // The resource fork header starts like an icon directory.
+        test(match_all, "sample.dfont", "application/x-dfont", "dfont");
+        test(match_image, "sample.dfont", "UNKNOWN", "");
+        test(match_font, "sample.bdf", "application/x-font-bdf", "bdf");
+        test(match_font, "sample.pcf", "application/x-font-pcf", "pcf");
+        test(match_all, "sample.pfa", "application/x-font-type1", "pfa");
+        test(match_all, "sample.bdf", "application/x-font-bdf", "bdf");
+
+        let ttf = info::font_info(&sample("sample.ttf")).unwrap();
+        assert_eq!(Some("Sample Sans"), ttf.family.as_deref());
+        assert_eq!(
+            (Some(400), Some(Outlines::TrueType)),
+            (ttf.weight, ttf.outlines)
+        );
+        assert!(ttf.variable);
+        assert_eq!(vec!["wght", "wdth"], ttf.axes);
+
+        let otf = info::font_info(&sample("sample.otf")).unwrap();
+        assert_eq!(
+            (Some("Sample Serif Display"), Some("Bold")),
+            (otf.family.as_deref(), otf.subfamily.as_deref())
+        );
+        assert_eq!((Some(700), Some(Outlines::Cff)), (otf.weight, otf.outlines));
+        assert!(!otf.variable);
+        assert_eq!(otf, info::font_info(&sample("sample.woff")).unwrap());
+
+        let ttc = info::font_info(&sample("sample.ttc")).unwrap();
+        assert_eq!((Some("Sample Sans"), 2), (ttc.family.as_deref(), ttc.fonts));
+        let dfont = info::font_info(&sample("sample.dfont")).unwrap();
+        assert_eq!(
+            (Some("Sample Sans"), 1),
+            (dfont.family.as_deref(), dfont.fonts)
+        );
+
+        let pfb = info::font_info(&sample("sample.pfb")).unwrap();
+        assert_eq!(Some("Sample Type1"), pfb.family.as_deref());
+        assert_eq!(Some(Outlines::Type1), pfb.outlines);
+        let bdf = info::font_info(&sample("sample.bdf")).unwrap();
+        assert_eq!(Some("Sample Fixed"), bdf.family.as_deref());
+        assert_eq!(Some(Outlines::Bitmap), bdf.outlines);
+    }
+
+    #[test]
+    fn test_pdf() {
+        test(match_archive, "sample.pdf", "application/pdf", "pdf");
+        test(match_archive, "sample.ai", "application/illustrator", "ai");
+        test(match_archive, "sample.fdf", "application/vnd.fdf", "fdf");
+        test(
+            match_text,
+            "sample.xfdf",
+            "application/vnd.adobe.xfdf",
+            "xfdf",
+        );
+
+        let pdf = info::pdf_info(&sample("sample.pdf")).unwrap();
+        assert_eq!(("1.7", Some(2)), (pdf.version.as_str(), pdf.pages));
+        assert!(pdf.tagged && !pdf.encrypted && !pdf.linearized && !pdf.illustrator);
+        assert_eq!(Some("filetypes fixture"), pdf.producer.as_deref());
+        assert_eq!(Some("PDF/A-2B"), pdf.pdf_a.as_deref());
+        assert_eq!(Some("PDF/UA-1"), pdf.pdf_ua.as_deref());
+        assert!(info::pdf_info(&sample("sample.ai")).unwrap().illustrator);
+    }
+
+    #[test]
+    fn test_java_class() {
+        test(
+            match_application,
+            "sample.class",
+            "application/java-vm",
+            "class",
+        );
+        // `CA FE BA BE` is also the Mach-O universal binary magic.
+        test(match_archive, "sample.class", "UNKNOWN", "");
+    }
+
+    #[test]
+    fn test_elf() {
+        test(match_archive, "sample.o", "application/x-object", "o");
+        test(match_archive, "sample.so", "application/x-sharedlib", "so");
+        test(
+            match_archive,
+            "sample.elf",
+            "application/x-executable",
+            "elf",
+        );
+        test(
+            match_archive,
+            "sample_pie.elf",
+            "application/x-pie-executable",
+            "elf",
+        );
+
+        let pie = info::elf_info(sample("sample_pie.elf").as_path()).unwrap();
+        assert_eq!(ElfKind::Pie, pie.kind);
+        assert_eq!(64, pie.class);
+        assert!(pie.dynamic);
+        assert!(pie.interpreter.is_some());
+
+        let exe = info::elf_info(sample("sample.elf").as_path()).unwrap();
+        assert_eq!(ElfKind::Executable, exe.kind);
+        assert!(!exe.dynamic);
+        assert_eq!(None, exe.interpreter);
+    }
+
     #[test]
     fn test_document_2003() {
         test(